@@ -0,0 +1,197 @@
+//! `aoc bench`: runs each day's parser and solver repeatedly and reports how long each took.
+
+use std::{fs, path::PathBuf, time::Duration};
+
+use aoc::days::{Day, DAYS};
+
+/// How benchmark results are printed.
+#[derive(Clone, Copy)]
+pub enum Format {
+    Text,
+    Json,
+    Csv
+}
+
+impl Format {
+    fn parse(name: &str) -> Option<Format> {
+        match name {
+            "text" => Some(Format::Text),
+            "json" => Some(Format::Json),
+            "csv" => Some(Format::Csv),
+            _ => None
+        }
+    }
+}
+
+pub struct Options {
+    /// How many times to run each part.
+    pub runs: usize,
+    pub format: Format,
+
+    /// The directory containing the puzzle inputs, named `day<N>.txt`.
+    pub dir: PathBuf,
+
+    /// The day to benchmark, or None for every day.
+    pub day: Option<&'static Day>,
+
+    /// The part to benchmark (1 or 2), or None for both.
+    pub part: Option<usize>
+}
+
+impl Options {
+    /// Parses the arguments following `bench`, returning None if they are invalid.
+    pub fn parse(args: &[&str]) -> Option<Options> {
+        let mut options = Options {
+            runs: 10,
+            format: Format::Text,
+            dir: PathBuf::from(crate::INPUT_DIR),
+            day: None,
+            part: None
+        };
+
+        let mut args = args.iter();
+        let mut positional = Vec::new();
+        while let Some(&arg) = args.next() {
+            match arg {
+                "--runs" => options.runs = args.next()?.parse().ok().filter(|&runs| runs > 0)?,
+                "--format" => options.format = Format::parse(args.next()?)?,
+                "--inputs" => options.dir = PathBuf::from(args.next()?),
+                _ if arg.starts_with("--") => return None,
+                _ => positional.push(arg)
+            }
+        }
+
+        match positional[..] {
+            [] => {},
+            [day] => options.day = Some(Day::get(day.parse().ok()?)?),
+            [day, part] => {
+                options.day = Some(Day::get(day.parse().ok()?)?);
+                options.part = match part.parse() {
+                    Ok(part @ 1..=2) => Some(part),
+                    _ => return None
+                };
+            }
+            _ => return None
+        }
+        Some(options)
+    }
+}
+
+/// The fastest, median, and slowest of a set of timings.
+pub(crate) struct Summary {
+    pub(crate) min: Duration,
+    pub(crate) median: Duration,
+    pub(crate) max: Duration
+}
+
+impl Summary {
+    pub(crate) fn of(mut times: Vec<Duration>) -> Summary {
+        times.sort();
+        let mid = times.len() / 2;
+        let median = if times.len() % 2 == 1 {
+            times[mid]
+        } else {
+            (times[mid - 1] + times[mid]) / 2
+        };
+        Summary { min: times[0], median, max: times[times.len() - 1] }
+    }
+}
+
+/// The timings from benchmarking one part of one day.
+struct Record {
+    day: u32,
+    part: usize,
+    parse: Summary,
+    solve: Summary
+}
+
+/// Runs one part of a day `runs` times, or returns an error message if it fails.
+fn bench_part(day: &Day, part: usize, input: &str, runs: usize) -> Result<Record, String> {
+    let mut parse_times = Vec::with_capacity(runs);
+    let mut solve_times = Vec::with_capacity(runs);
+    for _ in 0..runs {
+        let run = day.parts[part - 1](input).map_err(|e| e.to_string())?;
+        parse_times.push(run.parse_time);
+        solve_times.push(run.solve_time);
+    }
+    Ok(Record { day: day.number, part, parse: Summary::of(parse_times), solve: Summary::of(solve_times) })
+}
+
+/// Benchmarks every selected day which has an input file, then prints the results.
+pub fn run(options: &Options) {
+    let days = match options.day {
+        Some(day) => std::slice::from_ref(day),
+        None => DAYS
+    };
+    let parts = match options.part {
+        Some(part) => part..=part,
+        None => 1..=2
+    };
+
+    let mut records = Vec::new();
+    for day in days {
+        if options.day.is_none() && day.is_placeholder() {
+            eprintln!("skipping day {}: it has no example answers yet", day.number);
+            continue;
+        }
+        let path = options.dir.join(format!("day{}.txt", day.number));
+        let input = match fs::read_to_string(&path) {
+            Ok(input) => input,
+            Err(e) => {
+                eprintln!("skipping day {}: could not read {}: {}", day.number, path.display(), e);
+                continue;
+            }
+        };
+
+        for part in parts.clone() {
+            match bench_part(day, part, &input, options.runs) {
+                Ok(record) => records.push(record),
+                Err(e) => eprintln!("day {} part {}: {}", day.number, part, e)
+            }
+        }
+    }
+
+    match options.format {
+        Format::Text => print_text(&records, options.runs),
+        Format::Json => print_json(&records, options.runs),
+        Format::Csv => print_csv(&records, options.runs)
+    }
+}
+
+fn print_text(records: &[Record], runs: usize) {
+    println!("{} runs each", runs);
+    println!("{:<4} {:<4} {:^32}   {:^32}", "day", "part", "parse (min / median / max)", "solve (min / median / max)");
+    for record in records {
+        println!("{:<4} {:<4} {:>10.2?} {:>10.2?} {:>10.2?}   {:>10.2?} {:>10.2?} {:>10.2?}",
+            record.day, record.part,
+            record.parse.min, record.parse.median, record.parse.max,
+            record.solve.min, record.solve.median, record.solve.max);
+    }
+}
+
+/// Prints the records as a JSON array. Times are in nanoseconds.
+fn print_json(records: &[Record], runs: usize) {
+    fn summary(summary: &Summary) -> String {
+        format!("{{\"min_ns\": {}, \"median_ns\": {}, \"max_ns\": {}}}",
+            summary.min.as_nanos(), summary.median.as_nanos(), summary.max.as_nanos())
+    }
+
+    println!("[");
+    for (index, record) in records.iter().enumerate() {
+        let separator = if index + 1 < records.len() { "," } else { "" };
+        println!("  {{\"day\": {}, \"part\": {}, \"runs\": {}, \"parse\": {}, \"solve\": {}}}{}",
+            record.day, record.part, runs, summary(&record.parse), summary(&record.solve), separator);
+    }
+    println!("]");
+}
+
+/// Prints the records as CSV with a header row. Times are in nanoseconds.
+fn print_csv(records: &[Record], runs: usize) {
+    println!("day,part,runs,parse_min_ns,parse_median_ns,parse_max_ns,solve_min_ns,solve_median_ns,solve_max_ns");
+    for record in records {
+        println!("{},{},{},{},{},{},{},{},{}",
+            record.day, record.part, runs,
+            record.parse.min.as_nanos(), record.parse.median.as_nanos(), record.parse.max.as_nanos(),
+            record.solve.min.as_nanos(), record.solve.median.as_nanos(), record.solve.max.as_nanos());
+    }
+}
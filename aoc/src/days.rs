@@ -0,0 +1,94 @@
+use std::time::{Duration, Instant};
+use common::{Answer, Error, Example, Solution};
+
+/// Parses a puzzle input and computes the answer to one part of a puzzle.
+pub type Part = fn(&str) -> Result<Run, Error>;
+
+/// Parses a puzzle input for one part of a puzzle, without solving it.
+pub type Validator = fn(&str) -> Result<(), Error>;
+
+/// The answer to one part of a puzzle, along with how long each phase took to compute it.
+pub struct Run {
+    pub answer: Answer,
+    pub parse_time: Duration,
+    pub solve_time: Duration
+}
+
+/// Runs a solution on an input, timing the parser and solver separately.
+fn run<S: Solution>(input: &str) -> Result<Run, Error> {
+    let start = Instant::now();
+    let parsed = S::parse(input)?;
+    let parse_time = start.elapsed();
+
+    let start = Instant::now();
+    let answer = S::solve(parsed)?;
+    let solve_time = start.elapsed();
+
+    Ok(Run { answer, parse_time, solve_time })
+}
+
+/// Runs just a solution's parser, which reports every problem it finds in the input.
+fn validate<S: Solution>(input: &str) -> Result<(), Error> {
+    S::parse(input).map(drop)
+}
+
+/// A day's puzzle, along with the solutions to each of its parts.
+pub struct Day {
+    pub number: u32,
+    pub parts: [Part; 2],
+    pub validators: [Validator; 2],
+    pub examples: &'static [Example]
+}
+
+impl Day {
+    /// Looks up a day by its number.
+    pub fn get(number: u32) -> Option<&'static Day> {
+        DAYS.iter().find(|day| day.number == number)
+    }
+
+    /// Whether this day is still the placeholder `aoc new` made, with no example answers filled
+    /// in. Its solutions are most likely `todo!()`, so it's left out of anything that runs every day.
+    pub fn is_placeholder(&self) -> bool {
+        self.examples.iter().all(|example| example.answers.iter().all(Option::is_none))
+    }
+}
+
+/// Registers each day's crate with the runner.
+macro_rules! days {
+    ($($number:literal => $day:ident),* $(,)?) => {
+        /// Every day we have a solution for, in order.
+        pub static DAYS: &[Day] = &[
+            $(Day {
+                number: $number,
+                parts: [run::<$day::part1::Part1>, run::<$day::part2::Part2>],
+                validators: [validate::<$day::part1::Part1>, validate::<$day::part2::Part2>],
+                examples: $day::EXAMPLES
+            }),*
+        ];
+    }
+}
+
+days! {
+    1 => day1,
+    2 => day2,
+    3 => day3,
+    4 => day4,
+    5 => day5,
+    6 => day6,
+    7 => day7,
+    8 => day8,
+    9 => day9,
+    10 => day10,
+    11 => day11,
+    12 => day12,
+    13 => day13,
+    14 => day14,
+    15 => day15,
+    16 => day16,
+    17 => day17,
+    18 => day18,
+    19 => day19,
+    20 => day20,
+    21 => day21,
+    22 => day22,
+}
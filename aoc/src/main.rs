@@ -0,0 +1,186 @@
+use std::{fs, io, path::{Path, PathBuf}, process};
+
+use aoc::days::{Day, DAYS};
+use common::{input, Error};
+
+mod bench;
+mod output;
+mod tools;
+
+use output::{Format, Output};
+
+/// The directory `aoc all` and `aoc bench` look in for puzzle inputs by default.
+const INPUT_DIR: &str = "inputs";
+
+fn usage() -> ! {
+    let name = std::env::args().next().unwrap_or_else(|| "aoc".to_string());
+    eprintln!("usage: {} [--format text|json|tap] <day> 1|2 [input]", name);
+    eprintln!("       {} [--format text|json|tap] all [input-dir]", name);
+    eprintln!("       {} validate <day> [input]", name);
+    eprintln!("       {} validate all [input-dir]", name);
+    eprintln!("       {} new <day>", name);
+    eprintln!("       {} bench [--runs N] [--format text|json|csv] [--inputs input-dir] [day [1|2]]", name);
+    for (day, tool, args, description) in tools::TOOLS {
+        eprintln!("       {} {} {} {:<16} {}", name, day, tool, args, description);
+    }
+    process::exit(2)
+}
+
+/// Reads a puzzle input from the given file, or from stdin if no file is given.
+fn read_input(path: Option<&Path>) -> io::Result<String> {
+    match path {
+        Some(path) => input::read(fs::File::open(path)?),
+        None => input::read(io::stdin().lock())
+    }
+}
+
+/// Runs a single part of a single day.
+fn run_one(day: &Day, part: usize, path: Option<&Path>, format: Format) {
+    let input = read_input(path).unwrap_or_else(|e| {
+        eprintln!("could not read input: {}", e);
+        process::exit(1)
+    });
+    let result = day.parts[part - 1](&input);
+    let mut output = Output::new(format, true);
+    output.result(day.number, part, &input, &result);
+    output.finish();
+    if result.is_err() { process::exit(1) }
+}
+
+/// Runs every day which has an input file in `dir`, named `day<N>.txt`.
+fn run_all(dir: &Path, format: Format) {
+    let mut output = Output::new(format, false);
+    for day in DAYS {
+        if day.is_placeholder() {
+            output.skip(day.number, "it has no example answers yet");
+            continue;
+        }
+        let path = dir.join(format!("day{}.txt", day.number));
+        let input = match fs::read_to_string(&path) {
+            Ok(input) => input,
+            Err(e) => {
+                output.skip(day.number, &format!("could not read {}: {}", path.display(), e));
+                continue;
+            }
+        };
+
+        for (index, part) in day.parts.iter().enumerate() {
+            output.result(day.number, index + 1, &input, &part(&input));
+        }
+    }
+    output.finish();
+}
+
+/// Runs both parts' parsers on an input, and returns every problem either of them found.
+/// Both parts usually share a parser, so a problem reported by both is only listed once.
+fn problems(day: &Day, input: &str) -> Vec<String> {
+    let mut problems = Vec::new();
+    for validate in &day.validators {
+        let messages = match validate(input) {
+            Ok(()) => continue,
+            Err(Error::Parse(mut errors)) => {
+                errors.sort_by_key(|e| (e.line, e.column));
+                errors.iter().map(ToString::to_string).collect()
+            }
+            Err(e) => vec![e.to_string()]
+        };
+        for message in messages {
+            if !problems.contains(&message) { problems.push(message); }
+        }
+    }
+    problems
+}
+
+/// Prints every problem in a day's input. Returns false if there were any.
+fn report(day: &Day, input: &str) -> bool {
+    let problems = problems(day, input);
+    if problems.is_empty() {
+        println!("day {}: ok", day.number);
+    }
+    for problem in &problems {
+        println!("day {}: {}", day.number, problem);
+    }
+    problems.is_empty()
+}
+
+/// Checks a single day's input for problems, without solving it.
+fn validate_one(day: &Day, path: Option<&Path>) {
+    let input = read_input(path).unwrap_or_else(|e| {
+        eprintln!("could not read input: {}", e);
+        process::exit(1)
+    });
+    if !report(day, &input) { process::exit(1) }
+}
+
+/// Checks every day which has an input file in `dir` for problems, without solving anything.
+fn validate_all(dir: &Path) {
+    let mut valid = true;
+    for day in DAYS {
+        let path = dir.join(format!("day{}.txt", day.number));
+        match fs::read_to_string(&path) {
+            Ok(input) => valid &= report(day, &input),
+            Err(e) => println!("day {}: skipped (could not read {}: {})", day.number, path.display(), e)
+        }
+    }
+    if !valid { process::exit(1) }
+}
+
+/// Creates a crate for a new day from `template/`, and registers it with the runner.
+fn new_day(number: u32) {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).parent().expect("aoc is in the workspace");
+    if let Err(e) = aoc::scaffold::new_day(root, number) {
+        eprintln!("could not create day {}: {}", number, e);
+        process::exit(1)
+    }
+    println!("created day{}; paste the example into day{}/test and fill in its answers in day{}/src/lib.rs",
+        number, number, number);
+}
+
+/// Removes `--format <format>` from the arguments, if it's there.
+fn take_format(args: &mut Vec<&str>) -> Format {
+    match args.iter().position(|&arg| arg == "--format") {
+        Some(index) => {
+            let format = args.get(index + 1).and_then(|name| Format::parse(name)).unwrap_or_else(|| usage());
+            args.drain(index..=index + 1);
+            format
+        }
+        None => Format::Text
+    }
+}
+
+fn main() {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let mut args = args.iter().map(String::as_str).collect::<Vec<_>>();
+    if let ["bench", ref rest @ ..] = args[..] {
+        // bench has its own set of formats
+        return bench::run(&bench::Options::parse(rest).unwrap_or_else(|| usage()));
+    }
+
+    let format = take_format(&mut args);
+    match args[..] {
+        ["all"] => run_all(Path::new(INPUT_DIR), format),
+        ["all", dir] => run_all(Path::new(dir), format),
+        ["new", day] => new_day(day.parse().unwrap_or_else(|_| usage())),
+        ["validate", "all"] => validate_all(Path::new(INPUT_DIR)),
+        ["validate", "all", dir] => validate_all(Path::new(dir)),
+        ["validate", day] | ["validate", day, _] => {
+            let day = day.parse().ok().and_then(Day::get).unwrap_or_else(|| usage());
+            let path = args.get(2).map(PathBuf::from);
+            validate_one(day, path.as_deref());
+        }
+        [day, tool, ref rest @ ..] if tool.parse::<usize>().is_err() => {
+            let day = day.parse().unwrap_or_else(|_| usage());
+            if !tools::run(day, tool, rest) { usage() }
+        }
+        [day, part] | [day, part, _] => {
+            let day = day.parse().ok().and_then(Day::get).unwrap_or_else(|| usage());
+            let part = match part.parse::<usize>() {
+                Ok(part @ 1..=2) => part,
+                _ => usage()
+            };
+            let path = args.get(2).map(PathBuf::from);
+            run_one(day, part, path.as_deref(), format);
+        }
+        _ => usage()
+    }
+}
@@ -0,0 +1,113 @@
+//! Prints the results of running solutions, either for people or for other programs.
+
+use std::hash::Hasher;
+
+use aoc::days::Run;
+use common::Error;
+
+/// How results are printed.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// `day N part M: <answer>`, or just the answer when running a single part.
+    Text,
+
+    /// One JSON object per line, with the answer, its type, timings, and a checksum of the input.
+    Json,
+
+    /// The Test Anything Protocol, with one test per part.
+    Tap
+}
+
+impl Format {
+    pub fn parse(name: &str) -> Option<Format> {
+        match name {
+            "text" => Some(Format::Text),
+            "json" => Some(Format::Json),
+            "tap" => Some(Format::Tap),
+            _ => None
+        }
+    }
+}
+
+/// Prints each result as it comes in.
+pub struct Output {
+    format: Format,
+
+    /// Whether we are only running a single part.
+    single: bool,
+
+    /// The number of results printed so far.
+    count: usize
+}
+
+impl Output {
+    pub fn new(format: Format, single: bool) -> Self {
+        Output { format, single, count: 0 }
+    }
+
+    /// Prints the result of running one part of a day on `input`.
+    pub fn result(&mut self, day: u32, part: usize, input: &str, result: &Result<Run, Error>) {
+        self.count += 1;
+        match (self.format, result) {
+            (Format::Text, Ok(run)) if self.single => println!("{}", run.answer),
+            (Format::Text, Ok(run)) => println!("day {} part {}: {}", day, part, run.answer),
+            (Format::Text, Err(e)) => eprintln!("day {} part {}: {}", day, part, e),
+
+            (Format::Json, Ok(run)) => println!(
+                "{{\"day\": {}, \"part\": {}, \"answer\": {}, \"answer_type\": \"{}\", \
+                \"parse_ns\": {}, \"solve_ns\": {}, \"input_checksum\": \"{}\"}}",
+                day, part, json_string(&run.answer.to_string()), run.answer.kind(),
+                run.parse_time.as_nanos(), run.solve_time.as_nanos(), checksum(input)
+            ),
+            (Format::Json, Err(e)) => println!(
+                "{{\"day\": {}, \"part\": {}, \"error\": {}, \"input_checksum\": \"{}\"}}",
+                day, part, json_string(&e.to_string()), checksum(input)
+            ),
+
+            (Format::Tap, Ok(run)) => println!("ok {} - day {} part {}: {}", self.count, day, part, run.answer),
+            (Format::Tap, Err(e)) => println!("not ok {} - day {} part {}: {}", self.count, day, part, e)
+        }
+    }
+
+    /// Notes that a day was skipped, and why.
+    pub fn skip(&mut self, day: u32, reason: &str) {
+        match self.format {
+            Format::Text | Format::Json => eprintln!("skipping day {}: {}", day, reason),
+            Format::Tap => for part in 1..=2 {
+                self.count += 1;
+                println!("ok {} - day {} part {} # SKIP {}", self.count, day, part, reason);
+            }
+        }
+    }
+
+    /// Prints anything that has to come after all the results.
+    pub fn finish(self) {
+        if self.format == Format::Tap {
+            println!("1..{}", self.count);
+        }
+    }
+}
+
+/// A 64-bit FNV-1a hash of the input, in hex, so that results from different inputs can be told apart.
+fn checksum(input: &str) -> String {
+    let mut hasher = fnv::FnvHasher::default();
+    hasher.write(input.as_bytes());
+    format!("{:016x}", hasher.finish())
+}
+
+/// Quotes and escapes a string for inclusion in JSON.
+fn json_string(s: &str) -> String {
+    let mut result = String::with_capacity(s.len() + 2);
+    result.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            c if (c as u32) < 0x20 => result.push_str(&format!("\\u{:04x}", c as u32)),
+            c => result.push(c)
+        }
+    }
+    result.push('"');
+    result
+}
@@ -0,0 +1,96 @@
+//! Generates a new day's crate from `template/` and registers it with the runner.
+
+use std::{fs, io, path::Path};
+
+/// The files copied from `template/` into each new day's crate, and where they go.
+const TEMPLATE_FILES: &[(&str, &str)] = &[
+    ("Cargo.toml", "Cargo.toml"),
+    ("lib.rs", "src/lib.rs"),
+    ("part1.rs", "src/part1.rs"),
+    ("part2.rs", "src/part2.rs"),
+    ("test", "test")
+];
+
+/// Creates the crate for day `number` in the workspace at `root`, and registers it with the runner.
+/// Refuses to touch anything if the day's directory already exists or the day is already registered.
+pub fn new_day(root: &Path, number: u32) -> io::Result<()> {
+    let name = format!("day{}", number);
+    let dir = root.join(&name);
+    if dir.exists() {
+        return Err(already_exists(format!("{} already exists", dir.display())));
+    }
+
+    let manifest_path = root.join("aoc/Cargo.toml");
+    let days_path = root.join("aoc/src/days.rs");
+    let manifest = register_dependency(&fs::read_to_string(&manifest_path)?, number)?;
+    let days = register_day(&fs::read_to_string(&days_path)?, number)?;
+
+    // Read the whole template up front, so that a missing file doesn't leave a half-made day behind.
+    let template = TEMPLATE_FILES.iter()
+        .map(|(source, dest)| {
+            let contents = fs::read_to_string(root.join("template").join(source))?;
+            Ok((dest, contents.replace("{{name}}", &name)))
+        })
+        .collect::<io::Result<Vec<_>>>()?;
+
+    fs::create_dir(&dir)?;
+    fs::create_dir(dir.join("src"))?;
+    for (dest, contents) in template {
+        fs::write(dir.join(dest), contents)?;
+    }
+
+    fs::write(manifest_path, manifest)?;
+    fs::write(days_path, days)?;
+    Ok(())
+}
+
+fn already_exists(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::AlreadyExists, message)
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// The number of the day registered by a line like `dayN = ...` or `N => dayN,`, if it is one.
+fn day_number(line: &str, prefix: &str) -> Option<u32> {
+    line.trim().strip_prefix(prefix)?.split('=').next()?.trim().parse().ok()
+}
+
+/// Inserts `line` after the last of the `numbered` lines with a lower number,
+/// or before the first of them if there isn't one.
+fn insert_sorted(lines: &mut Vec<String>, numbered: &[(usize, u32)], number: u32, line: String)
+    -> io::Result<()> {
+    if numbered.iter().any(|&(_, other)| other == number) {
+        return Err(already_exists(format!("day {} is already registered", number)));
+    }
+    let index = match numbered.iter().rfind(|&&(_, other)| other < number) {
+        Some(&(index, _)) => index + 1,
+        None => numbered.first().map(|&(index, _)| index).ok_or_else(|| invalid_data("no days registered"))?
+    };
+    lines.insert(index, line);
+    Ok(())
+}
+
+/// Adds day `number` as a dependency of the runner, keeping the days in order.
+fn register_dependency(manifest: &str, number: u32) -> io::Result<String> {
+    let mut lines = manifest.lines().map(str::to_string).collect::<Vec<_>>();
+    let numbered = lines.iter().enumerate()
+        .filter_map(|(index, line)| day_number(line, "day").map(|number| (index, number)))
+        .collect::<Vec<_>>();
+    insert_sorted(&mut lines, &numbered, number, format!("day{} = {{ path = \"../day{}\" }}", number, number))?;
+    Ok(lines.join("\n") + "\n")
+}
+
+/// Adds day `number` to the `days!` invocation in the runner, keeping the days in order.
+fn register_day(days: &str, number: u32) -> io::Result<String> {
+    let mut lines = days.lines().map(str::to_string).collect::<Vec<_>>();
+    let start = lines.iter().position(|line| line.starts_with("days! {"))
+        .ok_or_else(|| invalid_data("could not find the days! invocation"))?;
+    let numbered = lines.iter().enumerate().skip(start + 1)
+        .take_while(|(_, line)| !line.starts_with('}'))
+        .filter_map(|(index, line)| day_number(line, "").map(|number| (index, number)))
+        .collect::<Vec<_>>();
+    insert_sorted(&mut lines, &numbered, number, format!("    {} => day{},", number, number))?;
+    Ok(lines.join("\n") + "\n")
+}
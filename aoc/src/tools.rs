@@ -0,0 +1,413 @@
+//! Extra tools for particular days, beyond solving the puzzle: `aoc <day> <tool> [args]`.
+
+use std::{io, path::Path, process, time::{Duration, Instant}};
+use common::ParseError;
+use day18::rules::Rules;
+
+/// Every tool, as (day, name, arguments, description), for the usage message.
+pub const TOOLS: &[(u32, &str, &str, &str)] = &[
+    (8, "debug", "<input>", "step through a boot program interactively"),
+    (8, "repair", "[input]", "list every single-instruction fix for a looping boot program"),
+    (8, "analyze", "[--dot] [input]", "report loops, unreachable code and bad jumps in a boot program"),
+    (8, "asm", "[input]", "assemble extended boot code, and print it in canonical form"),
+    (8, "exec", "[input]", "assemble and run extended boot code, printing its output"),
+    (8, "bench-compiled", "[--size N] [--runs N]", "race the compiled backend against the interpreter"),
+    (18, "group", "[input]", "show how each part groups every expression, in ordinary notation"),
+    (18, "eval", "[--rules R] [--arithmetic checked|big|rational] [--keep-going] [input]",
+        "evaluate homework under a preset, a rules file, or rules like 'left + -; left * /'"),
+    (18, "repl", "[--rules R] [--arithmetic checked|big|rational]", "a calculator with variables, min, max and abs"),
+    (19, "explain", "[--part2] [--trees N] [input]",
+        "show how each message matches rule 0 and how many ways it can, or where it stops matching"),
+    (19, "compile", "[--part2] [input]", "compile the rules into DFAs, and print them as regular expressions"),
+    (19, "lint", "[--part2] [input]", "report undefined, unused, unproductive and left-recursive rules"),
+];
+
+/// Runs one of a day's tools. Returns false if there is no such tool.
+pub fn run(day: u32, tool: &str, args: &[&str]) -> bool {
+    match (day, tool, args) {
+        (8, "debug", [path]) => debug8(path),
+        (8, "repair", [] | [_]) => repair8(args.first().copied()),
+        (8, "analyze", ["--dot", ref rest @ ..]) if rest.len() <= 1 => analyze8(rest.first().copied(), true),
+        (8, "analyze", [] | [_]) => analyze8(args.first().copied(), false),
+        (8, "asm", [] | [_]) => print!("{}", day8::asm::disassemble(&assemble8(args.first().copied()))),
+        (8, "exec", [] | [_]) => exec8(args.first().copied()),
+        (8, "bench-compiled", _) => bench_compiled8(args),
+        (18, "group", [] | [_]) => group18(args.first().copied()),
+        (18, "eval", _) => eval18(args),
+        (18, "repl", _) => repl18(args),
+        (19, "explain", _) => explain19(args),
+        (19, "compile", ["--part2", ref rest @ ..]) if rest.len() <= 1 => compile19(rest.first().copied(), true),
+        (19, "compile", [] | [_]) => compile19(args.first().copied(), false),
+        (19, "lint", ["--part2", ref rest @ ..]) if rest.len() <= 1 => lint19(rest.first().copied(), true),
+        (19, "lint", [] | [_]) => lint19(args.first().copied(), false),
+        _ => return false
+    }
+    true
+}
+
+/// Reads an input and parses it with `parse`, exiting if it's malformed.
+fn read<T>(path: Option<&str>, parse: impl FnOnce(&str) -> Result<T, Vec<ParseError>>) -> T {
+    let input = crate::read_input(path.map(Path::new)).unwrap_or_else(|e| {
+        eprintln!("could not read input: {}", e);
+        process::exit(1)
+    });
+    parse(&input).unwrap_or_else(|errors| {
+        errors.iter().for_each(|e| eprintln!("{}", e));
+        process::exit(1)
+    })
+}
+
+/// Reads and parses a day 8 boot program.
+fn read_program(path: Option<&str>) -> Vec<day8::program::Instruction> {
+    read(path, day8::program::parse)
+}
+
+/// Reads and assembles a day 8 boot program written with the extended instruction set.
+fn assemble8(path: Option<&str>) -> Vec<day8::asm::Instruction> {
+    read(path, day8::asm::assemble)
+}
+
+/// Day 8: runs the debugger on a boot program. Commands are read from stdin,
+/// so the program has to come from a file.
+fn debug8(path: &str) {
+    let mut debugger = day8::debugger::Debugger::new(read_program(Some(path)));
+    if let Err(e) = day8::debugger::repl(&mut debugger, io::stdin().lock(), io::stdout()) {
+        eprintln!("{}", e);
+        process::exit(1)
+    }
+}
+
+/// Day 8: lists every instruction which makes a boot program terminate when it's flipped.
+fn repair8(path: Option<&str>) {
+    let program = read_program(path);
+    if day8::repair::terminating(&program)[0].is_some() {
+        println!("the program already terminates, so there's nothing to repair");
+        return;
+    }
+    let repairs = day8::repair::repairs(&program);
+    if repairs.is_empty() {
+        println!("no single flipped instruction makes the program terminate");
+        process::exit(1)
+    }
+    for repair in repairs {
+        let instruction = program[repair.index];
+        println!("{:>5}: {} -> {} {:+} (accum {})",
+            repair.index, instruction, repair.replacement, instruction.operand, repair.accum);
+    }
+}
+
+/// Day 8: analyzes a boot program without running it, printing either a report or a Graphviz graph.
+fn analyze8(path: Option<&str>, dot: bool) {
+    let program = read_program(path);
+    let analysis = day8::analysis::analyze(&program);
+    if dot {
+        print!("{}", day8::analysis::to_dot(&program, &analysis));
+    } else {
+        print!("{}", analysis);
+    }
+}
+
+/// Day 8: assembles and runs a boot program written with the extended instruction set.
+fn exec8(path: Option<&str>) {
+    use day8::asm::Halt;
+    /// Programs which run for longer than this probably never terminate.
+    const STEP_LIMIT: usize = 100_000_000;
+
+    let run = day8::asm::run(&assemble8(path), STEP_LIMIT);
+    run.output.iter().for_each(|value| println!("{}", value));
+    let [a, b, c, d] = run.registers;
+    eprintln!("a = {}, b = {}, c = {}, d = {}", a, b, c, d);
+    match run.halt {
+        Halt::Terminated => (),
+        Halt::OutOfBounds(pc) => {
+            eprintln!("jumped out of the program to {}", pc);
+            process::exit(1)
+        }
+        Halt::StepLimit => {
+            eprintln!("gave up after {} steps", STEP_LIMIT);
+            process::exit(1)
+        }
+    }
+}
+
+/// Runs `f` `runs` times, returning its result and how long it took.
+fn time<T>(runs: usize, mut f: impl FnMut() -> T) -> (T, crate::bench::Summary) {
+    let mut result = None;
+    let times = (0..runs).map(|_| {
+        let start = Instant::now();
+        result = Some(f());
+        start.elapsed()
+    }).collect::<Vec<Duration>>();
+    (result.expect("there is at least one run"), crate::bench::Summary::of(times))
+}
+
+/// Day 8: times the interpreter and the compiled backend on a large generated program.
+fn bench_compiled8(args: &[&str]) {
+    use day8::{compiled::{Compiled, Halt}, part1::Interpreter, program};
+
+    let (mut size, mut runs) = (1_000_000, 10);
+    let mut args = args.iter();
+    while let Some(&flag) = args.next() {
+        let value = args.next().and_then(|value| value.parse().ok()).filter(|&value| value > 0);
+        match (flag, value) {
+            ("--size", Some(value)) => size = value,
+            ("--runs", Some(value)) => runs = value,
+            _ => crate::usage()
+        }
+    }
+
+    let program = program::generate(size, 2020);
+    println!("{} instructions, {} runs each", size, runs);
+    println!("{:<12} {:^32}", "", "min / median / max");
+
+    // The interpreter marks instructions as it runs them, so each run needs a fresh copy.
+    let (interpreted, interpreter) = time(runs, || Interpreter::new(program.clone()).run_until_infinite_loop());
+    let (compiled, compile) = time(runs, || Compiled::new(&program));
+    let (result, execute) = time(runs, || compiled.run());
+    for (name, summary) in &[("interpreter", &interpreter), ("compile", &compile), ("compiled", &execute)] {
+        println!("{:<12} {:>10.2?} {:>10.2?} {:>10.2?}", name, summary.min, summary.median, summary.max);
+    }
+
+    println!("the compiled program runs {:.1}x as fast as the interpreter, or {:.1}x including compilation",
+        interpreter.median.as_secs_f64() / execute.median.as_secs_f64(),
+        interpreter.median.as_secs_f64() / (compile.median + execute.median).as_secs_f64());
+
+    if interpreted.map(Halt::Loop) != Some(result) {
+        eprintln!("the backends disagree: the interpreter gave {:?}, but the compiled program gave {:?}", interpreted, result);
+        process::exit(1)
+    }
+}
+
+/// Day 18: prints each expression the way each part groups it, with the parentheses
+/// someone used to multiplication coming first would need to read it the same way.
+fn group18(path: Option<&str>) {
+    use common::Solution;
+    use day18::{part1::Part1, part2::Part2};
+
+    let input = read(path, |input| Ok(input.to_string()));
+    let parse = |result: Result<_, common::Error>| result.unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        process::exit(1)
+    });
+    let (part1, part2) = (parse(Part1::parse(&input)), parse(Part2::parse(&input)));
+
+    for ((line, part1), part2) in input.lines().zip(&part1).zip(&part2) {
+        println!("{}", line);
+        for (part, expr) in &[(1, part1), (2, part2)] {
+            match expr.eval() {
+                Ok(value) => println!("  part {}: {} = {}", part, expr.pretty(&Rules::STANDARD), value),
+                Err(e) => println!("  part {}: {} ({})", part, expr.fold().pretty(&Rules::STANDARD), e)
+            }
+        }
+    }
+}
+
+/// Day 18: evaluates homework under the given rules, printing how each line is grouped and the total.
+/// Stops at the first line with a problem, unless `--keep-going` is given, in which case every problem
+/// is reported at the end and the total leaves those lines out.
+fn eval18(args: &[&str]) {
+    use day18::arithmetic::{Arithmetic, BigInt, BigRational};
+
+    let (mut rules, mut arithmetic, mut keep_going, mut path) = (Rules::STANDARD, Arithmetic::Checked, false, None);
+    let mut args = args.iter();
+    while let Some(&arg) = args.next() {
+        match arg {
+            "--rules" => rules = args.next().map_or_else(|| crate::usage(), |rules| rules18(rules)),
+            "--arithmetic" => arithmetic = args.next().and_then(|name| Arithmetic::parse(name))
+                .unwrap_or_else(|| crate::usage()),
+            "--keep-going" => keep_going = true,
+            _ if path.is_none() => path = Some(arg),
+            _ => crate::usage()
+        }
+    }
+
+    let input = read(path, |input| Ok(input.to_string()));
+    match arithmetic {
+        Arithmetic::Checked => evaluate18::<i64>(&input, &rules, keep_going),
+        Arithmetic::Big => evaluate18::<BigInt>(&input, &rules, keep_going),
+        Arithmetic::Rational => evaluate18::<BigRational>(&input, &rules, keep_going)
+    }
+}
+
+/// Day 18: does the work of `eval18` with a particular kind of number.
+fn evaluate18<N: day18::arithmetic::Number>(input: &str, rules: &Rules, keep_going: bool) {
+    use day18::{diagnostic, expr::Operator};
+
+    let (mut total, mut failures) = (Some(N::from_i64(0)), Vec::new());
+    for result in diagnostic::evaluate_all::<N>(input, rules) {
+        match result {
+            Ok((expr, value)) => {
+                println!("{} = {}", expr.pretty(&Rules::STANDARD), value);
+                total = total.and_then(|total| N::apply(Operator::Add, total, value).ok());
+            }
+            Err(diagnostic) if keep_going => failures.push(diagnostic),
+            Err(diagnostic) => {
+                eprint!("{}", diagnostic.render(input));
+                process::exit(1)
+            }
+        }
+    }
+
+    let failed = total.is_none() || !failures.is_empty();
+    match total {
+        Some(total) if failures.is_empty() => println!("total: {}", total),
+        Some(total) => println!("total: {} (leaving out {} line(s) with problems)", total, failures.len()),
+        None => println!("total: overflow")
+    }
+    for diagnostic in &failures {
+        eprintln!("\n{}", diagnostic.render(input).trim_end());
+    }
+    if failed { process::exit(1) }
+}
+
+/// Day 18: reads precedence rules, which are either the name of a preset, the path to a file,
+/// or a table written out in the argument itself.
+fn rules18(rules: &str) -> Rules {
+    match Rules::preset(rules) {
+        Some(rules) => rules,
+        None if Path::new(rules).exists() => read(Some(rules), Rules::parse),
+        None => Rules::parse(rules).unwrap_or_else(|errors| {
+            errors.iter().for_each(|e| eprintln!("in the rules: {}", e));
+            process::exit(1)
+        })
+    }
+}
+
+/// Day 18: runs the calculator, reading expressions from stdin.
+fn repl18(args: &[&str]) {
+    use day18::{arithmetic::{Arithmetic, BigInt, BigRational, Number}, repl::{self, Calculator}};
+
+    fn run<N: Number>(rules: Rules) -> io::Result<()> {
+        repl::repl(&mut Calculator::<N>::new(rules), io::stdin().lock(), io::stdout())
+    }
+
+    let (mut rules, mut arithmetic) = (Rules::STANDARD, Arithmetic::Checked);
+    let mut args = args.iter();
+    while let Some(&arg) = args.next() {
+        match (arg, args.next()) {
+            ("--rules", Some(rules_arg)) => rules = rules18(rules_arg),
+            ("--arithmetic", Some(name)) => arithmetic = Arithmetic::parse(name).unwrap_or_else(|| crate::usage()),
+            _ => crate::usage()
+        }
+    }
+
+    let result = match arithmetic {
+        Arithmetic::Checked => run::<i64>(rules),
+        Arithmetic::Big => run::<BigInt>(rules),
+        Arithmetic::Rational => run::<BigRational>(rules)
+    };
+    if let Err(e) = result {
+        eprintln!("{}", e);
+        process::exit(1)
+    }
+}
+
+/// Day 19: explains each message: how it matches rule 0 if it does, and otherwise how far it got
+/// and what could have come next. `--part2` swaps in part 2's replacement rules first.
+fn explain19(args: &[&str]) {
+    use day19::{derivation::Count, earley::Parser, grammar::Rule};
+
+    let (mut part2, mut limit, mut path) = (false, 1, None);
+    let mut args = args.iter();
+    while let Some(&arg) = args.next() {
+        match arg {
+            "--part2" => part2 = true,
+            "--trees" => limit = args.next().and_then(|limit| limit.parse().ok()).unwrap_or_else(|| crate::usage()),
+            _ if path.is_none() => path = Some(arg),
+            _ => crate::usage()
+        }
+    }
+
+    let (grammar, messages) = read_grammar19(path, part2);
+    for problem in day19::lint::lint(&grammar, 0) {
+        eprintln!("warning: {}", problem.describe(&grammar));
+    }
+    let parser = Parser::new(&grammar);
+    let describe = |rule: usize| match grammar.rules[&rule] {
+        Rule::Verbatim(c) if !grammar.names.contains_key(&rule) => format!("{} ('{}')", rule, c.escape_ascii()),
+        _ => grammar.label(rule)
+    };
+    for message in &messages {
+        let parse = parser.parse(0, message);
+        if parse.matched() {
+            match parse.count() {
+                Count::Finite(1) => println!("{}: matches", message),
+                count => println!("{}: matches in {} ways", message, count)
+            }
+            for tree in parse.trees(limit) {
+                print!("{}", tree.outline(message).labelled(&grammar));
+            }
+        } else {
+            let prefix = parse.longest_prefix();
+            let mut expected = parse.expected().into_iter().map(describe).collect::<Vec<_>>();
+            if parse.prefix_matched(prefix) { expected.push("the end of the message".to_string()); }
+            let place = if prefix == 0 { "at the start".to_string() } else { format!("after '{}'", &message[..prefix]) };
+            println!("{}: no match; expected {} {}", message, expected.join(" or "), place);
+        }
+    }
+}
+
+/// Day 19: reads the rules and messages, swapping in part 2's replacement rules if `part2` is set.
+fn read_grammar19(path: Option<&str>, part2: bool) -> (day19::grammar::Grammar, Vec<String>) {
+    use common::Solution;
+    use day19::{part1::Part1, part2::Part2};
+
+    let input = read(path, |input| Ok(input.to_string()));
+    let parse = if part2 { Part2::parse(&input) } else { Part1::parse(&input) };
+    let (mut grammar, messages) = parse.unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        process::exit(1)
+    });
+    if part2 { day19::part2::replace_rules(&mut grammar); }
+    (grammar, messages)
+}
+
+/// Day 19: compiles rule 0 into a DFA and prints it as a regular expression. If rule 0 is recursive,
+/// does the same for each rule used by a recursive rule, which is what the parser works with instead.
+fn compile19(path: Option<&str>, part2: bool) {
+    use day19::{earley::Parser, grammar::Rule};
+
+    let (grammar, _) = read_grammar19(path, part2);
+    let parser = Parser::compiled(&grammar);
+    let mut rules = vec![0];
+    if parser.automaton(0).is_none() {
+        println!("rule 0 is recursive");
+        rules = grammar.rules.iter()
+            .filter(|(number, _)| parser.automaton(**number).is_none())
+            .flat_map(|(_, rule)| match rule {
+                Rule::Alternatives(alternatives) => alternatives.iter().flatten().copied().collect(),
+                Rule::Verbatim(_) => Vec::new()
+            })
+            .filter(|&number| parser.automaton(number).is_some())
+            .collect();
+        rules.sort_unstable();
+        rules.dedup();
+    }
+    for rule in rules {
+        let dfa = parser.automaton(rule).expect("only compiled rules are listed");
+        match dfa.to_regex() {
+            Some(regex) => println!("rule {}: {} states\n{}", grammar.label(rule), dfa.len(), regex),
+            None => print!("rule {}: {} states, too many ways through them to write as a regular expression\n{}",
+                grammar.label(rule), dfa.len(), dfa)
+        }
+    }
+}
+
+/// Day 19: reports every problem with the rules, checked from rule 0. Undefined rules are
+/// reported along with everything else, rather than stopping the rules from being read.
+fn lint19(path: Option<&str>, part2: bool) {
+    let input = read(path, |input| Ok(input.to_string()));
+    let (mut grammar, _) = day19::grammar::Grammar::parse_lenient(&input, &[0]).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        process::exit(1)
+    });
+    if part2 { day19::part2::replace_rules(&mut grammar); }
+    let problems = day19::lint::lint(&grammar, 0);
+    if problems.is_empty() {
+        println!("no problems");
+    }
+    for problem in &problems {
+        println!("{}", problem.describe(&grammar));
+    }
+    if !problems.is_empty() { process::exit(1) }
+}
@@ -0,0 +1,70 @@
+//! Checks each day's solutions against inputs whose answers we already know.
+
+use std::{fs, path::Path};
+use aoc::days::{Day, DAYS};
+
+/// Runs one part of a day on an input. Returns a description of the failure
+/// if it doesn't produce the expected answer.
+fn check(day: &Day, part: usize, input: &str, expected: &str, source: &str) -> Option<String> {
+    let problem = match day.parts[part](input).map(|run| run.answer) {
+        Ok(answer) if answer.to_string() == expected => return None,
+        Ok(answer) => format!("got {}", answer),
+        Err(e) => format!("got error: {}", e)
+    };
+    Some(format!("day {} part {} ({}): expected {}, {}", day.number, part + 1, source, expected, problem))
+}
+
+/// Panics with every failure, if there were any.
+fn report(failures: Vec<String>) {
+    assert!(failures.is_empty(), "{} known answer(s) did not match:\n{}", failures.len(), failures.join("\n"));
+}
+
+#[test]
+fn examples() {
+    let mut failures = Vec::new();
+    for day in DAYS {
+        for (index, example) in day.examples.iter().enumerate() {
+            for (part, expected) in example.answers.iter().enumerate() {
+                if let Some(expected) = expected {
+                    let source = format!("example {}", index + 1);
+                    failures.extend(check(day, part, example.input, expected, &source));
+                }
+            }
+        }
+    }
+    report(failures);
+}
+
+#[test]
+fn every_part_has_an_example() {
+    // Days fresh from `aoc new` have no answers until they're solved.
+    let missing = DAYS.iter().filter(|day| !day.is_placeholder()).flat_map(|day| (0..2).map(move |part| (day, part)))
+        .filter(|(day, part)| day.examples.iter().all(|example| example.answers[*part].is_none()))
+        .map(|(day, part)| format!("day {} part {}", day.number, part + 1))
+        .collect::<Vec<_>>();
+    assert!(missing.is_empty(), "no example answers for {}", missing.join(", "));
+}
+
+/// Checks `inputs/day<N>.txt` against `answers/day<N>.txt` for each day which has both.
+/// The answers file holds the answer to part 1 on its first line and part 2 on its second;
+/// either may be left blank.
+#[test]
+fn local_inputs() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
+    let mut failures = Vec::new();
+    for day in DAYS {
+        let read = |dir: &str| fs::read_to_string(root.join(dir).join(format!("day{}.txt", day.number))).ok();
+        let (input, answers) = match (read("inputs"), read("answers")) {
+            (Some(input), Some(answers)) => (input, answers),
+            _ => continue
+        };
+
+        for (part, expected) in answers.lines().take(2).enumerate() {
+            let expected = expected.trim();
+            if !expected.is_empty() {
+                failures.extend(check(day, part, &input, expected, "local input"));
+            }
+        }
+    }
+    report(failures);
+}
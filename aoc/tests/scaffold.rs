@@ -0,0 +1,57 @@
+use std::{fs, path::{Path, PathBuf}};
+
+/// Copies the parts of the workspace the scaffolder touches into a fresh temporary directory.
+fn scratch_workspace(name: &str) -> PathBuf {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
+    let scratch = std::env::temp_dir().join(format!("aoc-scaffold-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&scratch);
+
+    fs::create_dir_all(scratch.join("aoc/src")).unwrap();
+    fs::create_dir_all(scratch.join("template")).unwrap();
+    for file in &["aoc/Cargo.toml", "aoc/src/days.rs"] {
+        fs::copy(root.join(file), scratch.join(file)).unwrap();
+    }
+    for entry in fs::read_dir(root.join("template")).unwrap() {
+        let entry = entry.unwrap();
+        fs::copy(entry.path(), scratch.join("template").join(entry.file_name())).unwrap();
+    }
+    scratch
+}
+
+#[test]
+fn creates_and_registers_day() {
+    let root = scratch_workspace("create");
+    aoc::scaffold::new_day(&root, 23).unwrap();
+
+    for file in &["Cargo.toml", "src/lib.rs", "src/part1.rs", "src/part2.rs", "test"] {
+        assert!(root.join("day23").join(file).exists(), "missing day23/{}", file);
+    }
+    let manifest = fs::read_to_string(root.join("day23/Cargo.toml")).unwrap();
+    assert!(manifest.contains("name = \"day23\""));
+
+    let dependencies = fs::read_to_string(root.join("aoc/Cargo.toml")).unwrap();
+    assert!(dependencies.contains("day22 = { path = \"../day22\" }\nday23 = { path = \"../day23\" }\n"));
+    let days = fs::read_to_string(root.join("aoc/src/days.rs")).unwrap();
+    assert!(days.contains("    22 => day22,\n    23 => day23,\n}"));
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn refuses_to_overwrite() {
+    let root = scratch_workspace("overwrite");
+    let days = fs::read_to_string(root.join("aoc/src/days.rs")).unwrap();
+
+    // day 5 is registered, but its directory isn't in the scratch workspace
+    assert!(aoc::scaffold::new_day(&root, 5).is_err());
+    assert!(!root.join("day5").exists());
+
+    // day 24 isn't registered, but its directory exists
+    fs::create_dir(root.join("day24")).unwrap();
+    fs::write(root.join("day24/notes"), "mine").unwrap();
+    assert!(aoc::scaffold::new_day(&root, 24).is_err());
+    assert_eq!(fs::read_to_string(root.join("day24/notes")).unwrap(), "mine");
+
+    assert_eq!(fs::read_to_string(root.join("aoc/src/days.rs")).unwrap(), days);
+    fs::remove_dir_all(root).unwrap();
+}
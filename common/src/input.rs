@@ -0,0 +1,74 @@
+//! Helpers for reading puzzle inputs and splitting them into the pieces most puzzles are made of.
+//!
+//! Everything here hands back slices of the input rather than copies, so that errors
+//! can still be located with [`ParseError::at`](crate::ParseError::at).
+
+use std::{io::{self, Read}, str::FromStr};
+use crate::{ErrorKind, ParseError};
+
+/// Reads an entire puzzle input from a file, stdin, or any other source.
+pub fn read(mut source: impl Read) -> io::Result<String> {
+    let mut input = String::new();
+    source.read_to_string(&mut input)?;
+    Ok(input)
+}
+
+/// An iterator over the blank-line-separated records of an input. See [`records`].
+pub struct Records<'a> {
+    lines: std::str::Lines<'a>
+}
+
+impl<'a> Iterator for Records<'a> {
+    type Item = Vec<&'a str>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let record = self.lines.by_ref()
+            .skip_while(|line| line.is_empty())
+            .take_while(|line| !line.is_empty())
+            .collect::<Vec<_>>();
+        if record.is_empty() { None } else { Some(record) }
+    }
+}
+
+/// Splits `input` into records separated by one or more blank lines, yielding the lines of each record.
+/// Leading and trailing blank lines are ignored, so no record is ever empty.
+pub fn records(input: &str) -> Records<'_> {
+    Records { lines: input.lines() }
+}
+
+/// Parses an input containing one number on each line.
+/// Returns an error for every line which isn't a number.
+pub fn numbers<T: FromStr>(input: &str) -> Result<Vec<T>, Vec<ParseError>> {
+    crate::collect_all(input.lines().map(|line| crate::parse_number(input, line)))
+}
+
+/// Parses `text`, a slice of `input`, as a list of numbers separated by `separator`.
+/// Whitespace around each number is ignored. Returns an error for every item which isn't a number.
+pub fn separated<T: FromStr>(input: &str, text: &str, separator: char) -> Result<Vec<T>, Vec<ParseError>> {
+    crate::collect_all(text.split(separator).map(|item| crate::parse_number(input, item.trim())))
+}
+
+/// Parses a rectangular grid of characters, converting each one with `cell`.
+/// Returns the rows of the grid, which are all the same (nonzero) width,
+/// or an error for every row which is malformed.
+pub fn grid<T>(input: &str, cell: impl Fn(char) -> Option<T>) -> Result<Vec<Vec<T>>, Vec<ParseError>> {
+    // The width of the first row, which every other row must match.
+    let mut width = None;
+    let rows = crate::collect_all(input.lines().map(|line| {
+        let row = line.char_indices().map(|(index, c)|
+            cell(c).ok_or_else(||
+                ParseError::at(input, &line[index..index + c.len_utf8()], ErrorKind::UnexpectedChar(c))
+            )
+        ).collect::<Result<Vec<T>, _>>()?;
+
+        if row.is_empty() || *width.get_or_insert(row.len()) != row.len() {
+            return Err(ParseError::at(input, line, ErrorKind::Expected("a row as wide as the first")));
+        }
+        Ok(row)
+    }))?;
+
+    if width.is_none() {
+        return Err(vec![ParseError::at(input, input, ErrorKind::UnexpectedEof)]);
+    }
+    Ok(rows)
+}
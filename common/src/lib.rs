@@ -0,0 +1,240 @@
+use std::fmt;
+
+pub mod input;
+
+/// The answer to one part of a puzzle.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Answer {
+    Signed(i64),
+    Unsigned(u64),
+    Text(String)
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Signed(value) => write!(f, "{}", value),
+            Answer::Unsigned(value) => write!(f, "{}", value),
+            Answer::Text(value) => write!(f, "{}", value)
+        }
+    }
+}
+
+impl Answer {
+    /// The name of the kind of value this answer holds.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Answer::Signed(_) => "signed",
+            Answer::Unsigned(_) => "unsigned",
+            Answer::Text(_) => "text"
+        }
+    }
+}
+
+macro_rules! impl_from {
+    ($variant:ident($inner:ty): $($from:ty),*) => {
+        $(impl From<$from> for Answer {
+            fn from(value: $from) -> Self { Answer::$variant(value as $inner) }
+        })*
+    }
+}
+impl_from!(Signed(i64): i32, i64, isize);
+impl_from!(Unsigned(u64): u32, u64, usize);
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self { Answer::Text(value) }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self { Answer::Text(value.to_string()) }
+}
+
+/// An error that prevents a solution from producing an answer.
+#[derive(Debug)]
+pub enum Error {
+    /// The puzzle input is malformed at one or more specific locations.
+    /// There is always at least one error.
+    Parse(Vec<ParseError>),
+
+    /// The puzzle input is malformed as a whole.
+    InvalidInput(String),
+
+    /// The puzzle input is well-formed, but has no solution.
+    NoSolution
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Parse(errors) => {
+                for (index, e) in errors.iter().enumerate() {
+                    if index > 0 { writeln!(f)?; }
+                    write!(f, "{}", e)?;
+                }
+                Ok(())
+            }
+            Error::InvalidInput(reason) => write!(f, "invalid input: {}", reason),
+            Error::NoSolution => write!(f, "no solution")
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Parse(errors) => errors.first().map(|e| e as _),
+            _ => None
+        }
+    }
+}
+
+impl From<ParseError> for Error {
+    fn from(e: ParseError) -> Self { Error::Parse(vec![e]) }
+}
+
+impl From<ParseError> for Vec<ParseError> {
+    fn from(e: ParseError) -> Self { vec![e] }
+}
+
+impl From<Vec<ParseError>> for Error {
+    fn from(errors: Vec<ParseError>) -> Self {
+        assert!(!errors.is_empty(), "a parse error needs at least one error");
+        Error::Parse(errors)
+    }
+}
+
+/// An error at a specific location in a puzzle input.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    /// The line the error occurred on, starting from 1.
+    pub line: usize,
+
+    /// The column the error occurred at, starting from 1.
+    pub column: usize,
+
+    /// The offending text. Empty if the error occurred at the end of a line or of the input.
+    pub text: String,
+
+    /// What went wrong.
+    pub kind: ErrorKind
+}
+
+impl ParseError {
+    pub fn new(kind: ErrorKind, line: usize, column: usize, text: impl Into<String>) -> Self {
+        ParseError { line, column, text: text.into(), kind }
+    }
+
+    /// Creates an error pointing at `text`, which must be a slice of `input`.
+    /// The line and column are computed from where `text` lies within `input`.
+    pub fn at(input: &str, text: &str, kind: ErrorKind) -> Self {
+        let offset = (text.as_ptr() as usize).wrapping_sub(input.as_ptr() as usize);
+        assert!(offset <= input.len(), "error text is not part of the input");
+
+        let before = &input[..offset];
+        let line_start = before.rfind('\n').map(|index| index + 1).unwrap_or(0);
+        ParseError {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            text: text.to_string(),
+            kind
+        }
+    }
+
+    /// Creates an error pointing just past the end of `text`, which must be a slice of `input`.
+    pub fn after(input: &str, text: &str, kind: ErrorKind) -> Self {
+        Self::at(input, &text[text.len()..], kind)
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}: {}", self.line, self.column, self.kind)?;
+        // An unexpected character already names the offending text.
+        if !self.text.is_empty() && !matches!(self.kind, ErrorKind::UnexpectedChar(_)) {
+            write!(f, " (found '{}')", self.text)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// The ways a puzzle input can be malformed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// The input ended before we found everything we needed.
+    UnexpectedEof,
+
+    /// A number could not be parsed.
+    InvalidNumber,
+
+    /// A number was parsed, but lies outside the range the puzzle allows.
+    OutOfRange,
+
+    /// A character which is not allowed here.
+    UnexpectedChar(char),
+
+    /// The text does not have the expected form, described by the string.
+    Expected(&'static str),
+
+    /// A reference to something that is not defined anywhere in the input.
+    Unknown(&'static str),
+
+    /// Something which should only be defined once is defined again.
+    Duplicate(&'static str)
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorKind::UnexpectedEof => write!(f, "unexpected end of input"),
+            ErrorKind::InvalidNumber => write!(f, "invalid number"),
+            ErrorKind::OutOfRange => write!(f, "value out of range"),
+            ErrorKind::UnexpectedChar(c) => write!(f, "unexpected character '{}'", c),
+            ErrorKind::Expected(what) => write!(f, "expected {}", what),
+            ErrorKind::Unknown(what) => write!(f, "unknown {}", what),
+            ErrorKind::Duplicate(what) => write!(f, "duplicate {}", what)
+        }
+    }
+}
+
+/// Parses `text`, a slice of `input`, as a number.
+pub fn parse_number<T: std::str::FromStr>(input: &str, text: &str) -> Result<T, ParseError> {
+    text.parse().map_err(|_| ParseError::at(input, text, ErrorKind::InvalidNumber))
+}
+
+/// Collects an iterator of results, like `collect::<Result<C, _>>()`, except that
+/// it keeps going after the first error so that every error can be reported at once.
+pub fn collect_all<T, C, E>(results: impl IntoIterator<Item = Result<T, E>>) -> Result<C, Vec<ParseError>>
+where C: std::iter::FromIterator<T>, E: Into<Vec<ParseError>> {
+    let mut errors = Vec::new();
+    let collection = results.into_iter()
+        .filter_map(|result| result.map_err(|e| errors.extend(e.into())).ok())
+        .collect();
+    if errors.is_empty() { Ok(collection) } else { Err(errors) }
+}
+
+/// An example puzzle input, along with the answers it is known to produce.
+pub struct Example {
+    pub input: &'static str,
+
+    /// The expected answer to each part, or None if the example doesn't cover that part.
+    pub answers: [Option<&'static str>; 2]
+}
+
+/// A solution to one part of a puzzle.
+pub trait Solution {
+    /// The puzzle input, parsed into whatever form the solution works on.
+    type Input;
+
+    /// Parses the puzzle input.
+    fn parse(input: &str) -> Result<Self::Input, Error>;
+
+    /// Computes the answer from the parsed input.
+    fn solve(input: Self::Input) -> Result<Answer, Error>;
+
+    /// Parses the puzzle input and computes the answer.
+    fn run(input: &str) -> Result<Answer, Error> {
+        Self::solve(Self::parse(input)?)
+    }
+}
@@ -0,0 +1,38 @@
+use common::{input, ErrorKind};
+
+#[test]
+fn records_skip_extra_blank_lines() {
+    let text = "\na\nb\n\n\nc\n\n";
+    let records = input::records(text).collect::<Vec<_>>();
+    assert_eq!(records, vec![vec!["a", "b"], vec!["c"]]);
+    assert_eq!(input::records("").count(), 0);
+}
+
+#[test]
+fn numbers_and_lists() {
+    assert_eq!(input::numbers::<u32>("1\n22\n333\n").unwrap(), vec![1, 22, 333]);
+    assert_eq!(input::separated::<i32>("0, -3,6", "0, -3,6", ',').unwrap(), vec![0, -3, 6]);
+
+    let e = input::numbers::<u32>("1\nx\n3\ny\n").unwrap_err();
+    assert_eq!(e.iter().map(|e| (e.line, e.column, &e.kind)).collect::<Vec<_>>(),
+        vec![(2, 1, &ErrorKind::InvalidNumber), (4, 1, &ErrorKind::InvalidNumber)]);
+}
+
+#[test]
+fn grids_are_rectangular() {
+    let cell = |c| match c { '.' => Some(false), '#' => Some(true), _ => None };
+    assert_eq!(input::grid("#.\n.#\n", cell).unwrap(), vec![vec![true, false], vec![false, true]]);
+
+    let e = input::grid("#.\n.?\n", cell).unwrap_err();
+    assert_eq!((e[0].line, e[0].column, &e[0].kind), (2, 2, &ErrorKind::UnexpectedChar('?')));
+    let e = input::grid("#.\n.\n?.\n", cell).unwrap_err();
+    assert_eq!(e.len(), 2);
+    assert_eq!((e[0].line, &e[0].kind), (2, &ErrorKind::Expected("a row as wide as the first")));
+    assert_eq!((e[1].line, &e[1].kind), (3, &ErrorKind::UnexpectedChar('?')));
+    assert_eq!(input::grid("", cell).unwrap_err()[0].kind, ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn reads_from_any_source() {
+    assert_eq!(input::read(&b"1\n2\n"[..]).unwrap(), "1\n2\n");
+}
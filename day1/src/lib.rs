@@ -0,0 +1,9 @@
+use common::Example;
+
+pub mod part1;
+pub mod part2;
+
+/// The examples from the puzzle description.
+pub const EXAMPLES: &[Example] = &[
+    Example { input: include_str!("../test"), answers: [Some("514579"), Some("241861950")] },
+];
@@ -1,22 +1,32 @@
-use std::io::{self, BufRead};
+use common::{Answer, Error, ErrorKind, ParseError, Solution};
 
-pub fn run() {
-    // The sum we're looking for.
-    const TARGET: usize = 2020;
+/// The sum we're looking for.
+const TARGET: usize = 2020;
 
-    // Values which we've encountered in the input so far.
-    let mut seen = vec![false; TARGET];
-    
-    for line in io::stdin().lock().lines() {
-        let value = line.expect("read error")
-            .parse::<usize>().expect("invalid integer");
-        assert!(value <= TARGET, "value {} out of range", value);
+pub struct Part1;
 
-        seen[value] = true;
-        let other = TARGET - value;
-        if seen[other] {
-            println!("{}", value * other);
-            break;
+impl Solution for Part1 {
+    type Input = Vec<usize>;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        Ok(common::collect_all(input.lines().map(|line| {
+            let value = common::parse_number::<usize>(input, line)?;
+            if value > TARGET { return Err(ParseError::at(input, line, ErrorKind::OutOfRange)); }
+            Ok(value)
+        }))?)
+    }
+
+    fn solve(input: Self::Input) -> Result<Answer, Error> {
+        // Values which we've encountered in the input so far.
+        let mut seen = vec![false; TARGET + 1];
+
+        for value in input {
+            seen[value] = true;
+            let other = TARGET - value;
+            if seen[other] {
+                return Ok((value * other).into());
+            }
         }
+        Err(Error::NoSolution)
     }
 }
@@ -1,30 +1,40 @@
 use std::collections;
-use std::io::{self, BufRead};
+use common::{Answer, Error, ErrorKind, ParseError, Solution};
 
-pub fn run() {
-    // The sum we're looking for.
-    const TARGET: i32 = 2020;
+/// The sum we're looking for.
+const TARGET: i32 = 2020;
 
-    // Values which we've encountered in the input so far.
-    let mut seen = collections::HashSet::<i32>::new();
-    
-    for line in io::stdin().lock().lines() {
-        let value = line.expect("read error")
-            .parse::<i32>().expect("invalid integer");
-        assert!(value <= TARGET, "value {} out of range", value);
+pub struct Part2;
 
-        // For each pair of numbers we've seen so far...
-        for other in &seen {
-            // Compute the third value we need to complete the sum.
-            let expected = TARGET - other - value;
-            // Do we have this value?
-            if seen.contains(&expected) {
-                println!("{}", value * other * expected);
-                break;
+impl Solution for Part2 {
+    type Input = Vec<i32>;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        Ok(common::collect_all(input.lines().map(|line| {
+            let value = common::parse_number::<i32>(input, line)?;
+            if value > TARGET { return Err(ParseError::at(input, line, ErrorKind::OutOfRange)); }
+            Ok(value)
+        }))?)
+    }
+
+    fn solve(input: Self::Input) -> Result<Answer, Error> {
+        // Values which we've encountered in the input so far.
+        let mut seen = collections::HashSet::<i32>::new();
+
+        for value in input {
+            // For each pair of numbers we've seen so far...
+            for other in &seen {
+                // Compute the third value we need to complete the sum.
+                let expected = TARGET - other - value;
+                // Do we have this value?
+                if seen.contains(&expected) {
+                    return Ok((value * other * expected).into());
+                }
             }
-        }
 
-        // We still haven't found the sum, add this value to the list and loop.
-        seen.insert(value);
+            // We still haven't found the sum, add this value to the list and loop.
+            seen.insert(value);
+        }
+        Err(Error::NoSolution)
     }
 }
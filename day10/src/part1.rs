@@ -1,23 +1,31 @@
-use std::io::{self, BufRead};
 use std::collections::HashMap;
+use common::{Answer, Error, Solution};
 
-pub fn run() {
-    let mut adapters: Vec<u32> = std::iter::once(0).chain(
-        io::stdin().lock().lines()
-            .map(|line| line.expect("read error").parse::<u32>().expect("parse error"))
-    ).collect();
+pub struct Part1;
 
-    adapters.sort_unstable();
-    adapters.push(adapters.last().unwrap() + 3);
+impl Solution for Part1 {
+    type Input = Vec<u32>;
 
-    let differences = adapters.windows(2).map(|window| match window {
-        [a, b] => b-a,
-        _ => panic!(".windows(2) always returns slices of two elements")
-    });
-    let mut histogram = HashMap::<u32, u32>::new();
-    differences.for_each(|difference|
-        *histogram.entry(difference).or_insert(0) += 1
-    );
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        Ok(common::input::numbers(input)?)
+    }
 
-    println!("{:?}", histogram);
+    fn solve(input: Self::Input) -> Result<Answer, Error> {
+        let mut adapters: Vec<u32> = std::iter::once(0).chain(input).collect();
+
+        adapters.sort_unstable();
+        adapters.push(adapters.last().unwrap() + 3);
+
+        let differences = adapters.windows(2).map(|window| match window {
+            [a, b] => b-a,
+            _ => panic!(".windows(2) always returns slices of two elements")
+        });
+        let mut histogram = HashMap::<u32, u32>::new();
+        differences.for_each(|difference|
+            *histogram.entry(difference).or_insert(0) += 1
+        );
+
+        let count = |difference| histogram.get(&difference).cloned().unwrap_or(0);
+        Ok((count(1) * count(3)).into())
+    }
 }
@@ -1,18 +1,12 @@
-use std::{cell::Cell, io::{self, BufRead}, str::FromStr};
+use std::cell::Cell;
+use common::{Answer, Error, Solution};
 
 #[derive(Eq, Ord, PartialOrd, PartialEq, Clone)]
-struct Adapter { 
+pub struct Adapter { 
     joltage: i32,
     chains: Cell<Option<usize>> // A memoized number of chains
 }
 
-impl FromStr for Adapter {
-    type Err = std::num::ParseIntError;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Self::new(s.parse()?))
-    }
-}
-
 impl Adapter {
     fn new(joltage: i32) -> Self {
         Self { joltage, chains: Cell::new(None) }
@@ -58,15 +52,23 @@ impl Adapter {
     }
 }
 
-pub fn run() {
-    let mut adapters: Vec<Adapter> = std::iter::once(Adapter::new(0))
-        .chain(
-            io::stdin().lock().lines()
-                .map(|line| line.expect("read error").parse::<Adapter>().expect("parse error"))
-    ).collect();
+pub struct Part2;
+
+impl Solution for Part2 {
+    type Input = Vec<Adapter>;
 
-    adapters.sort_unstable();
-    adapters.push(Adapter::new(adapters.last().unwrap().joltage + 3));
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        Ok(common::input::numbers(input)?.into_iter().map(Adapter::new).collect())
+    }
+
+    fn solve(input: Self::Input) -> Result<Answer, Error> {
+        let mut adapters: Vec<Adapter> = std::iter::once(Adapter::new(0))
+            .chain(input)
+            .collect();
 
-    println!("{}", Adapter::num_chains(&adapters));
+        adapters.sort_unstable();
+        adapters.push(Adapter::new(adapters.last().unwrap().joltage + 3));
+
+        Ok(Adapter::num_chains(&adapters).into())
+    }
 }
@@ -1,7 +1,8 @@
-use std::io::{self, BufRead};
+use common::{Answer, Error, Solution};
+use grid::{Grid, Neighborhood};
 
 #[derive(Copy, Clone, Eq, PartialEq)]
-enum Cell {
+pub enum Cell {
     Floor,
     Empty,
     Occupied
@@ -18,132 +19,41 @@ impl Cell {
     }
 }
 
-/// A simple 2D array.
-#[derive(Clone)]
-struct Grid<T> {
-    cells: Vec<T>,
-    width: usize
-}
-
-impl<T> Grid<T> {
-    /// Converts an X/Y index pair to a one-dimensional index in self.cells.
-    /// Panics if an index is out of bounds.
-    fn index(&self, x: usize, y: usize) -> usize {
-        assert!(
-            (0..self.width()).contains(&x) && 
-            (0..self.height()).contains(&y),
-            "indices out of bounds"
-        );
-        y*self.width + x
-    }
-
-    /// Returns a reference to the element at the given indices.
-    /// Panics if an index is out of bounds.
-    fn get(&self, x: usize, y: usize) -> &T {
-        self.cells.get(self.index(x, y)).unwrap()
-    }
-    /// Returns a mutable reference to the element at the gien indices.
-    /// Panics if an index is out of bounds.
-    fn get_mut(&mut self, x: usize, y: usize) -> &mut T {
-        let index = self.index(x, y);
-        self.cells.get_mut(index).unwrap()
-    }
-
-    /// The width of the grid.
-    fn width(&self) -> usize { self.width }
-
-    /// The height of the grid.
-    fn height(&self) -> usize { self.cells.len() / self.width }
-
-    /// Applies an offset to the given x/y coordinate pair
-    /// and bounds-checks the result.
-    fn offset(&self, xy: (usize, usize), dxy: (isize, isize)) -> Option<(usize, usize)> {
-        /// Helper function to compute the one-dimensional offset
-        fn offset(x: usize, dx: isize, max: usize) -> Option<usize> {
-            if dx < 0 {
-                x.checked_sub(-dx as usize)
-            } else {
-                x.checked_add(dx as usize)
-                    .and_then(|result| if result < max { Some(result) } else { None } )
-            }
+/// Runs an iteration, returning the new seating arrangement.
+fn step(seats: &Grid<Cell>) -> Grid<Cell> {
+    Grid::from_fn(seats.width(), seats.height(), |x, y| {
+        // The number of occupied seats visible from this one.
+        let occupied = Neighborhood::Moore.offsets()
+            .filter_map(|[dx, dy]| seats.cast((x, y), (dx, dy), |&cell| cell != Cell::Floor))
+            .filter(|&position| seats[position] == Cell::Occupied)
+            .count();
+
+        match (seats[(x, y)], occupied) {
+            (Cell::Empty, 0) => Cell::Occupied,
+            (Cell::Occupied, occupied) =>
+                if occupied < 5 { Cell::Occupied }
+                else { Cell::Empty },
+            (cell, _) => cell
         }
-        Some((
-            offset(xy.0, dxy.0, self.width())?,
-            offset(xy.1, dxy.1, self.height())?
-        ))
-    }
-
-    /// Creates a new, empty Grid.
-    fn empty() -> Grid<T> { Self { cells: Vec::new(), width: 0 } }
+    })
 }
 
-impl Grid<Cell> {
-    /// Runs an iteration. Returns false if we updated any
-    /// cells, or true if we've stabalized.
-    fn run_iter(&mut self) -> bool {
-        let original = self.clone();
-        let mut stable = true;
+pub struct Part2;
 
-        for y in 0..self.height() {
-            for x in 0..self.width {
-                // By Duncan, iterates from -1..1 in 2 dimensions (excluding the origin)
-                let neighbors_iter = (-1..=1)
-                    .flat_map(move |y| (-1..=1).map(move |x| (x, y)))
-                    .filter(|xy| !matches!(xy, (0, 0)));
+impl Solution for Part2 {
+    type Input = Grid<Cell>;
 
-                let occupied_neighbors = neighbors_iter
-                    .filter(|&dxy| original.search_offset((x, y), dxy) == Some(Cell::Occupied))
-                    .count();
-
-                let current = self.get_mut(x, y);
-                let new = match (*current, occupied_neighbors) {
-                    (Cell::Empty, 0) => Cell::Occupied,
-                    (Cell::Occupied, adjacent) =>
-                        if adjacent < 5 { Cell::Occupied }
-                        else { Cell::Empty },
-                    (cell, _) => cell
-                };
-
-                if *current != new {
-                    *current = new;
-                    stable = false;
-                }
-            }
-        }
-
-        stable
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        Ok(Grid::parse(input, Cell::from_char)?)
     }
 
-    /// Searches along the specified direction for a non-floor cell.
-    /// Returns None if the edge of the map was encountered.
-    fn search_offset(&self, xy: (usize, usize), dxy: (isize, isize)) -> Option<Cell> {
-        let xy = self.offset(xy, dxy);
-        match xy.map(|(x, y)| *self.get(x, y)) {
-            // We've encounterd a wall. Stop.
-            None => None,
-
-            // This is floor; keep looking in the same direction.
-            Some(Cell::Floor) => self.search_offset(xy.unwrap(), dxy),
-
-            // We've found a seat.
-            seat => seat
+    fn solve(mut seats: Self::Input) -> Result<Answer, Error> {
+        // Run until the seating arrangement stabilizes.
+        loop {
+            let next = step(&seats);
+            if next == seats { break; }
+            seats = next;
         }
+        Ok(seats.iter().filter(|&&c| c == Cell::Occupied).count().into())
     }
 }
-
-pub fn run() {
-    let mut grid = Grid::<Cell>::empty();
-    for line in io::stdin().lock().lines() {
-        let line = line.expect("read error");
-        let mut items: Vec<Cell> = line.chars().map(|c| 
-            Cell::from_char(c).expect("invalid character in line")
-        ).collect();
-
-        assert!(grid.width == 0 || items.len() == grid.width);
-        grid.width = items.len();
-        grid.cells.append(&mut items);
-    }
-
-    while !grid.run_iter() {}
-    println!("{}", grid.cells.iter().filter(|&&c| c == Cell::Occupied).count());
-}
@@ -1,4 +1,4 @@
-use std::io::{self, BufRead};
+use common::{Answer, Error, ErrorKind, ParseError, Solution};
 
 #[derive(Copy, Clone)]
 enum Direction {
@@ -57,22 +57,41 @@ impl Direction {
     }
 }
 
-pub fn run() {
-    let mut coords: (i32, i32) = (0, 0);
-    let mut direction = Direction::East;
-    for line in io::stdin().lock().lines() {
-        let line = line.expect("read error");
-        let (op, operand) = (line.bytes().next(), &line[1..]);
-        let op = op.expect("empty line");
-        let operand: i32 = operand.parse().expect("invalid input");
+pub struct Part1;
 
-        match op {
-            b'N' | b'S' | b'E' | b'W' => coords = Direction::from_char(op).unwrap().offset_coords_by(coords, operand),
-            b'L' => direction = direction.left(operand),
-            b'R' => direction = direction.right(operand),
-            b'F' => coords = direction.offset_coords_by(coords, operand),
-            _ => panic!("invalid operation '{}'", op)
+impl Solution for Part1 {
+    /// A list of (operation, operand) instructions.
+    type Input = Vec<(u8, i32)>;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        Ok(common::collect_all(input.lines().map(|line| {
+            let op = line.chars().next()
+                .ok_or_else(|| ParseError::at(input, line, ErrorKind::Expected("an instruction")))?;
+            if !"NSEWLRF".contains(op) {
+                return Err(ParseError::at(input, &line[..op.len_utf8()], ErrorKind::UnexpectedChar(op)));
+            }
+
+            let operand = &line[1..];
+            let value: i32 = common::parse_number(input, operand)?;
+            if (op == 'L' || op == 'R') && value % 90 != 0 {
+                return Err(ParseError::at(input, operand, ErrorKind::Expected("a multiple of 90 degrees")));
+            }
+            Ok((op as u8, value))
+        }))?)
+    }
+
+    fn solve(input: Self::Input) -> Result<Answer, Error> {
+        let mut coords: (i32, i32) = (0, 0);
+        let mut direction = Direction::East;
+        for (op, operand) in input {
+            match op {
+                b'N' | b'S' | b'E' | b'W' => coords = Direction::from_char(op).unwrap().offset_coords_by(coords, operand),
+                b'L' => direction = direction.left(operand),
+                b'R' => direction = direction.right(operand),
+                b'F' => coords = direction.offset_coords_by(coords, operand),
+                _ => unreachable!("operations are checked while parsing")
+            }
         }
+        Ok((coords.0.abs() + coords.1.abs()).into())
     }
-    println!("{}", coords.0.abs() + coords.1.abs());
 }
@@ -1,4 +1,4 @@
-use std::io::{self, BufRead};
+use common::{Answer, Error, ErrorKind, ParseError, Solution};
 
 #[derive(Copy, Clone)]
 enum Direction {
@@ -35,34 +35,53 @@ impl Direction {
     }
 }
 
-pub fn run() {
-    // The current ship location.
-    let mut coords: (i32, i32) = (0, 0);
+pub struct Part2;
 
-    // The location of the waypoint relative to the ship.
-    let mut wpt_offset: (i32, i32) = (10, 1);
-    for line in io::stdin().lock().lines() {
-        let line = line.expect("read error");
-        let (op, operand) = (line.bytes().next(), &line[1..]);
-        let op = op.expect("empty line");
-        let operand: i32 = operand.parse().expect("invalid input");
+impl Solution for Part2 {
+    /// A list of (operation, operand) instructions.
+    type Input = Vec<(u8, i32)>;
 
-        match op {
-            b'N' | b'S' | b'E' | b'W' => wpt_offset = Direction::from_char(op).unwrap().offset_coords_by(wpt_offset, operand),
-            b'L' => (0..operand/90).for_each(|_| wpt_offset = (
-                -wpt_offset.1,
-                wpt_offset.0
-            )),
-            b'R' => (0..operand/90).for_each(|_| wpt_offset = (
-                wpt_offset.1,
-                -wpt_offset.0
-            )),
-            b'F' => coords = (
-                coords.0 + wpt_offset.0*operand,
-                coords.1 + wpt_offset.1*operand
-            ),
-            _ => panic!("invalid operation")
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        Ok(common::collect_all(input.lines().map(|line| {
+            let op = line.chars().next()
+                .ok_or_else(|| ParseError::at(input, line, ErrorKind::Expected("an instruction")))?;
+            if !"NSEWLRF".contains(op) {
+                return Err(ParseError::at(input, &line[..op.len_utf8()], ErrorKind::UnexpectedChar(op)));
+            }
+
+            let operand = &line[1..];
+            let value: i32 = common::parse_number(input, operand)?;
+            if (op == 'L' || op == 'R') && value % 90 != 0 {
+                return Err(ParseError::at(input, operand, ErrorKind::Expected("a multiple of 90 degrees")));
+            }
+            Ok((op as u8, value))
+        }))?)
+    }
+
+    fn solve(input: Self::Input) -> Result<Answer, Error> {
+        // The current ship location.
+        let mut coords: (i32, i32) = (0, 0);
+
+        // The location of the waypoint relative to the ship.
+        let mut wpt_offset: (i32, i32) = (10, 1);
+        for (op, operand) in input {
+            match op {
+                b'N' | b'S' | b'E' | b'W' => wpt_offset = Direction::from_char(op).unwrap().offset_coords_by(wpt_offset, operand),
+                b'L' => (0..operand/90).for_each(|_| wpt_offset = (
+                    -wpt_offset.1,
+                    wpt_offset.0
+                )),
+                b'R' => (0..operand/90).for_each(|_| wpt_offset = (
+                    wpt_offset.1,
+                    -wpt_offset.0
+                )),
+                b'F' => coords = (
+                    coords.0 + wpt_offset.0*operand,
+                    coords.1 + wpt_offset.1*operand
+                ),
+                _ => unreachable!("operations are checked while parsing")
+            }
         }
+        Ok((coords.0.abs() + coords.1.abs()).into())
     }
-    println!("{}", coords.0.abs() + coords.1.abs());
 }
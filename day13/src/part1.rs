@@ -1,20 +1,42 @@
-use std::io::{self, BufRead};
+use common::{Answer, Error, ErrorKind, ParseError, Solution};
 
-pub fn run() {
-    let stdin = io::stdin();
-    let mut lines = stdin.lock().lines();
+pub struct Part1;
 
-    let start = lines.next().expect("unexpected EOF");
-    let start = start.expect("read error").parse::<i32>().expect("invalid start time");
+impl Solution for Part1 {
+    /// The earliest departure time, and the IDs of the buses in service.
+    type Input = (i32, Vec<i32>);
 
-    let schedule = lines.next().expect("unexpected EOF").expect("read error");
-    let schedule = schedule.split(',').filter_map(|entry|
-        entry.parse::<i32>().ok()
-    );
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        let mut lines = input.lines();
 
-    let result = schedule.map(|entry|
-        (entry, (start + entry-1)/entry * entry)
-    ).min_by(|a, b| a.1.cmp(&b.1)).unwrap();
+        let start = lines.next()
+            .ok_or_else(|| ParseError::after(input, input, ErrorKind::UnexpectedEof))?;
+        let start = common::parse_number::<i32>(input, start);
 
-    println!("{}", result.0 * (result.1 - start));
+        let line = lines.next()
+            .ok_or_else(|| ParseError::after(input, input, ErrorKind::UnexpectedEof))?;
+        let schedule = common::collect_all::<_, Vec<_>, _>(line.split(',').filter(|entry| *entry != "x").map(|entry|
+            match common::parse_number::<i32>(input, entry)? {
+                id if id > 0 => Ok(id),
+                _ => Err(ParseError::at(input, entry, ErrorKind::OutOfRange))
+            }
+        )).and_then(|schedule|
+            if schedule.is_empty() { Err(vec![ParseError::at(input, line, ErrorKind::Expected("at least one bus"))]) }
+            else { Ok(schedule) }
+        );
+
+        // Report problems on both lines, not just the first one.
+        match (start, schedule) {
+            (Ok(start), Ok(schedule)) => Ok((start, schedule)),
+            (start, schedule) => Err(start.err().into_iter().map(Vec::from).chain(schedule.err()).flatten().collect::<Vec<_>>().into())
+        }
+    }
+
+    fn solve((start, schedule): Self::Input) -> Result<Answer, Error> {
+        let result = schedule.into_iter().map(|entry|
+            (entry, (start + entry-1)/entry * entry)
+        ).min_by(|a, b| a.1.cmp(&b.1)).ok_or(Error::NoSolution)?;
+
+        Ok((result.0 * (result.1 - start)).into())
+    }
 }
@@ -1,4 +1,4 @@
-use std::io::{self, BufRead};
+use common::{Answer, Error, ErrorKind, ParseError, Solution};
 
 /// Solves a set of equations of the form
 /// x mod M1 = A1
@@ -6,14 +6,15 @@ use std::io::{self, BufRead};
 /// x mod M3 = A3
 /// ...and so on.
 /// elements is a boxed iterator over (A, N) pairs.
-fn solve<'a>(elements: Box<dyn Iterator<Item=(u64, u64)> + 'a>) -> u64 {
+/// Returns None if the equations have no common solution.
+fn solve<'a>(elements: Box<dyn Iterator<Item=(u64, u64)> + 'a>) -> Option<u64> {
     let mut elements = elements;
     
     // Base case: if we have zero equations, then
     // zero is the smallest solution in ℕ.
     let (first_offset, first_mod) = match elements.next() {
         Some((a, m)) => (a, m),
-        None => return 0
+        None => return Some(0)
     };
     // first_offset and first_mod are M1 and A1, respectively
 
@@ -38,35 +39,50 @@ fn solve<'a>(elements: Box<dyn Iterator<Item=(u64, u64)> + 'a>) -> u64 {
         // is a valid solution to this equation.
         let new_offset = (0..modulus).find(|new_offset|
             (first_offset + new_offset*first_mod) % modulus == offset
-        ).expect("no solution");
+        )?;
 
         // The rewritten equation is n mod <modulus> = <new_offset>.
-        (new_offset, modulus)
-    });
+        Some((new_offset, modulus))
+    }).collect::<Option<Vec<_>>>()?;
 
     // Recursively solve for n.
-    let n = solve(Box::new(remaining));
+    let n = solve(Box::new(remaining.into_iter()))?;
 
     // Substitute it into the original equation.
-    n*first_mod + first_offset
+    Some(n*first_mod + first_offset)
 }
 
-pub fn run() {
-    let stdin = io::stdin();
-    let mut lines = stdin.lock().lines();
+pub struct Part2;
 
-    let schedule = lines.nth(1).expect("unexpected EOF").expect("read error")
-        .split(',').enumerate().filter_map(|(index, entry)| {
-            let (index, entry) = (index as i32, entry.parse::<i32>().ok()?);
-            let index = (-index).rem_euclid(entry);
-            Some((index as u64, entry as u64))
-        }).collect::<Vec<_>>();
+impl Solution for Part2 {
+    /// A list of (offset, modulus) pairs.
+    type Input = Vec<(u64, u64)>;
 
-    let solution = solve(Box::new(schedule.iter().cloned()));
-    println!("{}", solution);
-    
-    // Verify the solution is valid.
-    schedule.iter().for_each(|&(offset, modulus)|
-        assert!(solution % modulus == offset)
-    );
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        let mut lines = input.lines();
+
+        let line = lines.nth(1).ok_or_else(|| ParseError::after(input, input, ErrorKind::UnexpectedEof))?;
+        let schedule: Vec<_> = common::collect_all(
+            line.split(',').enumerate().filter(|(_, entry)| *entry != "x").map(|(index, entry)| {
+                let (index, modulus) = (index as i32, common::parse_number::<i32>(input, entry)?);
+                if modulus <= 0 { return Err(ParseError::at(input, entry, ErrorKind::OutOfRange)); }
+                let index = (-index).rem_euclid(modulus);
+                Ok((index as u64, modulus as u64))
+            }))?;
+        if schedule.is_empty() {
+            return Err(ParseError::at(input, line, ErrorKind::Expected("at least one bus")).into());
+        }
+        Ok(schedule)
+    }
+
+    fn solve(schedule: Self::Input) -> Result<Answer, Error> {
+        let solution = solve(Box::new(schedule.iter().cloned())).ok_or(Error::NoSolution)?;
+
+        // Verify the solution is valid.
+        schedule.iter().for_each(|&(offset, modulus)|
+            assert!(solution % modulus == offset)
+        );
+
+        Ok(solution.into())
+    }
 }
@@ -0,0 +1,11 @@
+use common::Example;
+
+pub mod part1;
+pub mod part2;
+
+/// Example inputs with known answers.
+pub const EXAMPLES: &[Example] = &[
+    // Each part has its own example; part 2 would take far too long on part 1's.
+    Example { input: include_str!("../test"), answers: [Some("165"), None] },
+    Example { input: include_str!("../test2"), answers: [None, Some("208")] },
+];
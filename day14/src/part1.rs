@@ -1,8 +1,8 @@
-use std::io::{self, BufRead};
 use regex::Regex;
+use common::{Answer, Error, ErrorKind, ParseError, Solution};
 
 #[derive(Debug, Copy, Clone)]
-struct Mask {
+pub struct Mask {
     and: u64,
     or: u64
 }
@@ -37,26 +37,47 @@ impl Mask {
     }
 }
 
-pub fn run() {
-    let mask_regex = Regex::new(r"^mask = ([01X]{36})$").unwrap();
-    let mem_regex = Regex::new(r"^mem\[(\d+)\] = (\d+)").unwrap();
-    let mut current_mask = Mask { and: 0, or: 0 };
-    let mut memory = std::collections::HashMap::<u64, u64>::new();
-
-    for line in io::stdin().lock().lines() {
-        let line = line.expect("read error");
-
-        if let Some(mask) = mask_regex.captures_iter(&line).next() {
-            let mask = &mask[1];
-            current_mask = Mask::parse(mask).unwrap_or_else(|| panic!("invalid mask {}", mask));
-        } else if let Some(write) = mem_regex.captures_iter(&line).next() {
-            let addr = write[1].parse::<u64>().expect("invalid addr");
-            let val = write[2].parse::<u64>().expect("invalid val");
-            memory.insert(addr, current_mask.apply(val));
-        } else {
-            panic!("invalid input");
-        }
+/// A line of the initialization program.
+pub enum Command {
+    SetMask(Mask),
+    Write { addr: u64, val: u64 }
+}
+
+pub struct Part1;
+
+impl Solution for Part1 {
+    type Input = Vec<Command>;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        let mask_regex = Regex::new(r"^mask = ([01X]{36})$").unwrap();
+        let mem_regex = Regex::new(r"^mem\[(\d+)\] = (\d+)$").unwrap();
+
+        Ok(common::collect_all(input.lines().map(|line| {
+            if let Some(mask) = mask_regex.captures_iter(line).next() {
+                let mask = mask.get(1).unwrap().as_str();
+                Ok(Command::SetMask(Mask::parse(mask)
+                    .ok_or_else(|| ParseError::at(input, mask, ErrorKind::Expected("a 36-bit mask")))?))
+            } else if let Some(write) = mem_regex.captures_iter(line).next() {
+                let addr = common::parse_number::<u64>(input, write.get(1).unwrap().as_str())?;
+                let val = common::parse_number::<u64>(input, write.get(2).unwrap().as_str())?;
+                Ok(Command::Write { addr, val })
+            } else {
+                Err(ParseError::at(input, line, ErrorKind::Expected("'mask = <mask>' or 'mem[<addr>] = <value>'")))
+            }
+        }))?)
     }
 
-    println!("{}", memory.iter().map(|(_, &val)| val).sum::<u64>());
+    fn solve(input: Self::Input) -> Result<Answer, Error> {
+        let mut current_mask = Mask { and: 0, or: 0 };
+        let mut memory = std::collections::HashMap::<u64, u64>::new();
+
+        for command in input {
+            match command {
+                Command::SetMask(mask) => current_mask = mask,
+                Command::Write { addr, val } => { memory.insert(addr, current_mask.apply(val)); }
+            }
+        }
+
+        Ok(memory.values().sum::<u64>().into())
+    }
 }
@@ -1,8 +1,8 @@
-use std::io::{self, BufRead};
 use regex::Regex;
+use common::{Answer, Error, ErrorKind, ParseError, Solution};
 
 #[derive(Debug, Copy, Clone)]
-struct Mask {
+pub struct Mask {
     and: u64,
     or: u64
 }
@@ -46,28 +46,49 @@ impl Mask {
     }
 }
 
-pub fn run() {
-    let mask_regex = Regex::new(r"^mask = ([01X]{36})$").unwrap();
-    let mem_regex = Regex::new(r"^mem\[(\d+)\] = (\d+)").unwrap();
-    let mut current_masks = Vec::new();
-    let mut memory = std::collections::HashMap::<u64, u64>::new();
+/// A line of the initialization program.
+pub enum Command {
+    SetMask(Vec<Mask>),
+    Write { addr: u64, val: u64 }
+}
 
-    for line in io::stdin().lock().lines() {
-        let line = line.expect("read error");
+pub struct Part2;
 
-        if let Some(mask) = mask_regex.captures_iter(&line).next() {
-            let mask = &mask[1];
-            current_masks = Mask::parse(mask).unwrap_or_else(|| panic!("invalid mask {}", mask));
-        } else if let Some(write) = mem_regex.captures_iter(&line).next() {
-            let addr = write[1].parse::<u64>().expect("invalid addr");
-            let val = write[2].parse::<u64>().expect("invalid val");
-            Mask::apply(&current_masks, addr).for_each(|addr|
-                { memory.insert(addr, val); }
-            );
-        } else {
-            panic!("invalid input");
-        }
+impl Solution for Part2 {
+    type Input = Vec<Command>;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        let mask_regex = Regex::new(r"^mask = ([01X]{36})$").unwrap();
+        let mem_regex = Regex::new(r"^mem\[(\d+)\] = (\d+)$").unwrap();
+
+        Ok(common::collect_all(input.lines().map(|line| {
+            if let Some(mask) = mask_regex.captures_iter(line).next() {
+                let mask = mask.get(1).unwrap().as_str();
+                Ok(Command::SetMask(Mask::parse(mask)
+                    .ok_or_else(|| ParseError::at(input, mask, ErrorKind::Expected("a 36-bit mask")))?))
+            } else if let Some(write) = mem_regex.captures_iter(line).next() {
+                let addr = common::parse_number::<u64>(input, write.get(1).unwrap().as_str())?;
+                let val = common::parse_number::<u64>(input, write.get(2).unwrap().as_str())?;
+                Ok(Command::Write { addr, val })
+            } else {
+                Err(ParseError::at(input, line, ErrorKind::Expected("'mask = <mask>' or 'mem[<addr>] = <value>'")))
+            }
+        }))?)
     }
 
-    println!("{}", memory.iter().map(|(_, &val)| val).sum::<u64>());
+    fn solve(input: Self::Input) -> Result<Answer, Error> {
+        let mut current_masks = Vec::new();
+        let mut memory = std::collections::HashMap::<u64, u64>::new();
+
+        for command in input {
+            match command {
+                Command::SetMask(masks) => current_masks = masks,
+                Command::Write { addr, val } => Mask::apply(&current_masks, addr).for_each(|addr|
+                    { memory.insert(addr, val); }
+                )
+            }
+        }
+
+        Ok(memory.values().sum::<u64>().into())
+    }
 }
@@ -1,5 +1,5 @@
-use std::io::{self, BufRead};
 use std::iter;
+use common::{Answer, Error, Solution};
 
 /// An iterator which produces items in the elf sequence.
 struct ElfIterator<S: Iterator<Item=u32>> {
@@ -43,13 +43,19 @@ impl<S: Iterator<Item=u32>> Iterator for ElfIterator<S> {
     }
 }
 
-pub fn run() {
-    let stdin = io::stdin();
-    let starting_numbers = 
-        stdin.lock().split(b',').map(|item| 
-            std::str::from_utf8(&item.expect("read error")[..]).expect("invalid input")
-            .trim().parse::<u32>().expect("invalid input"));
+pub struct Part1;
 
-    let mut sequence = ElfIterator::new(starting_numbers.fuse());
-    println!("{}", sequence.nth(2019).unwrap());
+impl Solution for Part1 {
+    /// The starting numbers.
+    type Input = Vec<u32>;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        // split always yields at least one item, so this never produces an empty list.
+        Ok(common::input::separated(input, input, ',')?)
+    }
+
+    fn solve(starting_numbers: Self::Input) -> Result<Answer, Error> {
+        let mut sequence = ElfIterator::new(starting_numbers.into_iter());
+        Ok(sequence.nth(2019).unwrap().into())
+    }
 }
@@ -0,0 +1,11 @@
+use common::Example;
+
+pub mod part1;
+pub mod part2;
+
+/// Example inputs with known answers.
+pub const EXAMPLES: &[Example] = &[
+    // The puzzle's part 2 example has no departure fields, so test2 renames two of its fields.
+    Example { input: include_str!("../test"), answers: [Some("71"), None] },
+    Example { input: include_str!("../test2"), answers: [None, Some("143")] },
+];
@@ -1,9 +1,9 @@
-use std::io::{self, BufRead};
 use regex::Regex;
 use lazy_static::lazy_static;
+use common::{Answer, Error, ErrorKind, ParseError, Solution};
 
 #[derive(Debug)]
-struct TicketSchema {
+pub struct TicketSchema {
     fields: Vec<(String, FieldSchema)>
 }
 
@@ -16,60 +16,77 @@ impl TicketSchema {
 }
 
 #[derive(Debug, Clone)]
-struct FieldSchema {
+pub struct FieldSchema {
     ranges: Vec<std::ops::RangeInclusive<i32>>
 }
 
 impl FieldSchema {
     fn can_contain(&self, value: i32) -> bool {
-        self.ranges.iter().find(|range| range.contains(&value)) != None
+        self.ranges.iter().find(|range| range.contains(&value)).is_some()
     }
 
-    fn parse(text: &str) -> Option<(String, FieldSchema)> {
+    /// Parses a field from `text`, which must be a slice of `input`.
+    fn parse(input: &str, text: &str) -> Result<(String, FieldSchema), ParseError> {
         lazy_static! {
             static ref REGEX: Regex = Regex::new("^([^:]+): (.*)$").unwrap();
         }
-        let captures = REGEX.captures(text)?;
+        let captures = REGEX.captures(text)
+            .ok_or_else(|| ParseError::at(input, text, ErrorKind::Expected("'<field>: <ranges>'")))?;
         let name = captures.get(1).unwrap();
         let values = captures.get(2).unwrap().as_str()
             .split(" or ")
             .map(|range| {
                let mut split = range.splitn(2, '-');
-               let lower = split.next()?.parse::<i32>().ok()?;
-               let upper = split.next()?.parse::<i32>().ok()?;
-               if lower <= upper { Some(lower..=upper) }
-               else { dbg!(None) }
-            }).collect::<Option<Vec<_>>>()?;
+               let (lower, upper) = match (split.next(), split.next()) {
+                   (Some(lower), Some(upper)) => (lower, upper),
+                   _ => return Err(ParseError::at(input, range, ErrorKind::Expected("'<lower>-<upper>'")))
+               };
+               let lower = common::parse_number::<i32>(input, lower)?;
+               let upper = common::parse_number::<i32>(input, upper)?;
+               if lower <= upper { Ok(lower..=upper) }
+               else { Err(ParseError::at(input, range, ErrorKind::OutOfRange)) }
+            }).collect::<Result<Vec<_>, _>>()?;
         
-        Some((name.as_str().to_string(), FieldSchema { ranges: values }))
+        Ok((name.as_str().to_string(), FieldSchema { ranges: values }))
     }
 }
 
-pub fn run() {
-    let stdin = io::stdin();
-    let mut lines = stdin.lock().lines().map(|line| line.expect("read error"));
-    
-    let schema = lines.by_ref()
-        .take_while(|line| !line.is_empty())
-        .map(|line| FieldSchema::parse(&line))
-        .collect::<Option<Vec<(String, FieldSchema)>>>()
-        .expect("could not parse schema");
-    let schema = TicketSchema { fields: schema };
+/// Parses a comma-separated list of values from `line`, which must be a slice of `input`.
+fn parse_ticket(input: &str, line: &str) -> Result<Vec<i32>, Vec<ParseError>> {
+    common::input::separated(input, line, ',')
+}
+
+pub struct Part1;
 
-    // parse "your ticket" to /dev/null
-    let _ = lines.by_ref()
-        .take_while(|line| !line.is_empty())
-        .for_each(std::mem::drop);
+impl Solution for Part1 {
+    /// The ticket schema, and the nearby tickets.
+    type Input = (TicketSchema, Vec<Vec<i32>>);
 
-    let tickets =
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        let mut lines = input.lines();
+        
+        let schema = common::collect_all(lines.by_ref()
+            .take_while(|line| !line.is_empty())
+            .map(|line| FieldSchema::parse(input, line)));
+
+        // parse "your ticket" to /dev/null
         lines.by_ref()
-        .skip(1) // skip "nearby tickets" line
-        .take_while(|line| !line.is_empty())
-        .map(|line|
-            line.split(',').map(str::parse::<i32>)
-                .collect::<Result<Vec<i32>,_>>()
-                .expect("could not parse ticket")
-        );
+            .take_while(|line| !line.is_empty())
+            .for_each(std::mem::drop);
+
+        let tickets = common::collect_all(lines.by_ref()
+            .skip(1) // skip "nearby tickets" line
+            .take_while(|line| !line.is_empty())
+            .map(|line| parse_ticket(input, line)));
 
-    println!("{}", tickets.map(|ticket| schema.sum_invalid(&ticket)).sum::<i32>());
+        // Report problems in both sections, not just the first one.
+        match (schema, tickets) {
+            (Ok(fields), Ok(tickets)) => Ok((TicketSchema { fields }, tickets)),
+            (schema, tickets) => Err(schema.err().into_iter().chain(tickets.err()).flatten().collect::<Vec<_>>().into())
+        }
+    }
+
+    fn solve((schema, tickets): Self::Input) -> Result<Answer, Error> {
+        Ok(tickets.iter().map(|ticket| schema.sum_invalid(ticket)).sum::<i32>().into())
+    }
 }
@@ -1,10 +1,10 @@
-use std::io::{self, BufRead};
 use std::collections::{HashSet, HashMap};
 use regex::Regex;
 use lazy_static::lazy_static;
+use common::{Answer, Error, ErrorKind, ParseError, Solution};
 
 #[derive(Debug)]
-struct TicketSchema {
+pub struct TicketSchema {
     fields: Vec<(String, FieldSchema)>
 }
 
@@ -13,7 +13,7 @@ impl TicketSchema {
         let mut invalid = values.iter().filter(|&&value|
             self.fields.iter().find(|(_, field)| field.can_contain(value)).is_none()
         );
-        invalid.next() == None
+        invalid.next().is_none()
     }
 
     // Determines the index of each field.
@@ -64,88 +64,115 @@ impl TicketSchema {
 }
 
 #[derive(Debug, Clone)]
-struct FieldSchema {
+pub struct FieldSchema {
     ranges: Vec<std::ops::RangeInclusive<i32>>,
     possible_indices: HashSet<usize>,
 }
 
 impl FieldSchema {
     fn can_contain(&self, value: i32) -> bool {
-        self.ranges.iter().find(|range| range.contains(&value)) != None
+        self.ranges.iter().find(|range| range.contains(&value)).is_some()
     }
 
-    fn parse(text: &str, num_fields: usize) -> Option<(String, FieldSchema)> {
+    /// Parses a field from `text`, which must be a slice of `input`.
+    fn parse(input: &str, text: &str, num_fields: usize) -> Result<(String, FieldSchema), ParseError> {
         lazy_static! {
             static ref REGEX: Regex = Regex::new("^([^:]+): (.*)$").unwrap();
         }
-        let captures = REGEX.captures(text)?;
+        let captures = REGEX.captures(text)
+            .ok_or_else(|| ParseError::at(input, text, ErrorKind::Expected("'<field>: <ranges>'")))?;
         let name = captures.get(1).unwrap();
         let values = captures.get(2).unwrap().as_str()
             .split(" or ")
             .map(|range| {
                let mut split = range.splitn(2, '-');
-               let lower = split.next()?.parse::<i32>().ok()?;
-               let upper = split.next()?.parse::<i32>().ok()?;
-               if lower <= upper { Some(lower..=upper) }
-               else { dbg!(None) }
-            }).collect::<Option<Vec<_>>>()?;
+               let (lower, upper) = match (split.next(), split.next()) {
+                   (Some(lower), Some(upper)) => (lower, upper),
+                   _ => return Err(ParseError::at(input, range, ErrorKind::Expected("'<lower>-<upper>'")))
+               };
+               let lower = common::parse_number::<i32>(input, lower)?;
+               let upper = common::parse_number::<i32>(input, upper)?;
+               if lower <= upper { Ok(lower..=upper) }
+               else { Err(ParseError::at(input, range, ErrorKind::OutOfRange)) }
+            }).collect::<Result<Vec<_>, _>>()?;
         
-        Some((name.as_str().to_string(), FieldSchema { 
+        Ok((name.as_str().to_string(), FieldSchema { 
             ranges: values, possible_indices: (0..num_fields).collect()
         }))
     }
 }
 
-fn parse_ticket(line: &str) -> Option<Vec<i32>> {
-    line.split(',').map(str::parse::<i32>)
-        .collect::<Result<Vec<i32>,_>>()
-        .ok()
+/// Parses a comma-separated list of values from `line`, which must be a slice of `input`.
+fn parse_ticket(input: &str, line: &str) -> Result<Vec<i32>, Vec<ParseError>> {
+    common::input::separated(input, line, ',')
 }
 
-#[allow(clippy::clippy::needless_collect)]
-// Clippy is wrong about the "needless" collect of 'tickets';
-// he doesn't recognize that the filter call borrows schema.
-// https://github.com/rust-lang/rust-clippy/issues/6066
-pub fn run() {
-    let stdin = io::stdin();
-    let mut lines = stdin.lock().lines().map(|line| line.expect("read error"));
-    
-    let schema = lines.by_ref()
-        .take_while(|line| !line.is_empty())
-        .collect::<Vec<String>>();
-
-    // parse "your ticket"
-    let mut mine_section = lines.by_ref()
-        .take_while(|line| !line.is_empty());
-    let mine = mine_section.nth(1) // skip "your ticket" line
-        .and_then(|line| parse_ticket(&line))
-        .expect("invalid ticket");
-    mine_section.for_each(std::mem::drop);
-
-    let num_fields = mine.len();
-
-    // now that we know how many fields per ticket,
-    // finish parsing the schema
-    let schema = schema.into_iter()
-        .map(|line| FieldSchema::parse(&line, num_fields))
-        .collect::<Option<Vec<(String, FieldSchema)>>>()
-        .expect("could not parse schema");
-    let mut schema = TicketSchema { fields: schema };
-
-    let tickets =
-        lines.by_ref()
-        .skip(1) // skip "nearby tickets" line
-        .take_while(|line| !line.is_empty())
-        .map(|line| parse_ticket(&line).expect("invalid ticket"))
-        .filter(|ticket| schema.is_valid(ticket))
-        .collect::<Vec<_>>();
-
-    let schema = schema.solve(tickets.into_iter())
-        .expect("no solution");
-    
-    let result = schema.iter()
-        .filter(|(name, _)| name.starts_with("departure"))
-        .map(|(_, &index)| mine[index] as u64)
-        .product::<u64>();
-    println!("{}", result);
+pub struct Part2;
+
+impl Solution for Part2 {
+    /// The ticket schema, your ticket, and the nearby tickets.
+    type Input = (TicketSchema, Vec<i32>, Vec<Vec<i32>>);
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        let mut lines = input.lines();
+        
+        let schema = lines.by_ref()
+            .take_while(|line| !line.is_empty())
+            .collect::<Vec<&str>>();
+
+        // parse "your ticket"
+        let mut mine_section = lines.by_ref()
+            .take_while(|line| !line.is_empty());
+        let mine = mine_section.nth(1) // skip "your ticket" line
+            .ok_or_else(|| ParseError::after(input, input, ErrorKind::Expected("your ticket")))?;
+        let mine = parse_ticket(input, mine);
+        mine_section.for_each(std::mem::drop);
+
+        // If your ticket is malformed, the other sections can still be checked
+        // for problems, but not against the number of fields.
+        let num_fields = mine.as_ref().ok().map(Vec::len);
+
+        // now that we know how many fields per ticket,
+        // finish parsing the schema
+        let schema = common::collect_all(schema.into_iter()
+            .map(|line| FieldSchema::parse(input, line, num_fields.unwrap_or(0))));
+
+        let tickets = common::collect_all(lines.by_ref()
+            .skip(1) // skip "nearby tickets" line
+            .take_while(|line| !line.is_empty())
+            .map(|line| {
+                let ticket = parse_ticket(input, line)?;
+                if num_fields.is_some_and(|num_fields| ticket.len() != num_fields) {
+                    return Err(vec![ParseError::at(input, line, ErrorKind::Expected("a ticket with as many values as yours"))]);
+                }
+                Ok(ticket)
+            }));
+
+        // Report problems in every section, not just the first one.
+        match (schema, mine, tickets) {
+            (Ok(fields), Ok(mine), Ok(tickets)) => Ok((TicketSchema { fields }, mine, tickets)),
+            (schema, mine, tickets) => Err(
+                schema.err().into_iter().chain(mine.err()).chain(tickets.err()).flatten().collect::<Vec<_>>().into()
+            )
+        }
+    }
+
+    #[allow(clippy::needless_collect)]
+    // Clippy is wrong about the "needless" collect of 'tickets';
+    // he doesn't recognize that the filter call borrows schema.
+    // https://github.com/rust-lang/rust-clippy/issues/6066
+    fn solve((mut schema, mine, tickets): Self::Input) -> Result<Answer, Error> {
+        let tickets = tickets.into_iter()
+            .filter(|ticket| schema.is_valid(ticket))
+            .collect::<Vec<_>>();
+
+        let schema = schema.solve(tickets.into_iter())
+            .ok_or(Error::NoSolution)?;
+        
+        let result = schema.iter()
+            .filter(|(name, _)| name.starts_with("departure"))
+            .map(|(_, &index)| mine[index] as u64)
+            .product::<u64>();
+        Ok(result.into())
+    }
 }
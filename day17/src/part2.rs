@@ -1,7 +1,8 @@
-use std::io::{self, BufRead};
+use common::{Answer, Error, Solution};
+use grid::{ExpandingGrid, Grid, Neighborhood};
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
-enum Cell {
+pub enum Cell {
     Inactive,
     Active
 }
@@ -16,175 +17,44 @@ impl Cell {
     }
 }
 
-/// A ~3D~ 4D "infinite" expandable array.
-#[derive(Clone)]
-struct Grid<T> {
-    cells: Vec<T>,
-    size: i32     // The size along each axis.
+/// Runs a cycle, returning the new state of the pocket dimension.
+fn step(cubes: &ExpandingGrid<Cell, 4>) -> ExpandingGrid<Cell, 4> {
+    // Cubes just outside the current bounds may become active too.
+    let (mut min, mut max) = cubes.bounds();
+    min.iter_mut().for_each(|x| *x -= 1);
+    max.iter_mut().for_each(|x| *x += 1);
+
+    let mut result = ExpandingGrid::new(Cell::Inactive);
+    result.reserve(min, max);
+    for point in grid::points(min, max) {
+        let active_neighbors = grid::neighbors(point, Neighborhood::Moore)
+            .filter(|&neighbor| *cubes.get(neighbor) == Cell::Active)
+            .count();
+
+        let new = match (*cubes.get(point), active_neighbors) {
+            (Cell::Inactive, 3) => Cell::Active,
+            (Cell::Active, adjacent) =>
+                if adjacent == 2 || adjacent == 3 { Cell::Active }
+                else { Cell::Inactive },
+            (cell, _) => cell
+        };
+        result.set(point, new);
+    }
+    result
 }
 
-impl<T> Grid<T> {
-    /// Converts an X/Y index pair to a one-dimensional index in self.cells.
-    /// Panics if an index is out of bounds.
-    fn index(&self, x: i32, y: i32, z: i32, w: i32) -> usize {
-        let min = -self.size;
-        let max = self.size;
-        let range = min..max;
-        assert!(
-            range.contains(&x) &&
-            range.contains(&y) &&
-            range.contains(&z) &&
-            range.contains(&w),
-            "indices out of bounds"
-        );
-        let w = (w-min) as usize;
-        let z = (z-min) as usize;
-        let y = (y-min) as usize;
-        let x = (x-min) as usize;
-        let stride = (self.size*2) as usize;
-        (((w*stride + z)*stride) + y)*stride + x
-    }
+pub struct Part2;
 
-    /// Returns a reference to the element at the given indices.
-    /// Panics if an index is out of bounds.
-    fn get(&self, x: i32, y: i32, z: i32, w: i32) -> &T {
-        self.cells.get(self.index(x, y, z, w)).unwrap()
-    }
-    /// Returns a mutable reference to the element at the gien indices.
-    /// Panics if an index is out of bounds.
-    fn get_mut(&mut self, x: i32, y: i32, z: i32, w: i32) -> &mut T {
-        let index = self.index(x, y, z, w);
-        self.cells.get_mut(index).unwrap()
-    }
+impl Solution for Part2 {
+    type Input = ExpandingGrid<Cell, 4>;
 
-    /// Applies an offset to the given x/y coordinate pair
-    /// and bounds-checks the result.
-    fn offset(&self, xy: (i32, i32, i32, i32), dxy: (i32, i32, i32, i32)) -> Option<(i32, i32, i32, i32)> {
-        /// Helper function to compute the one-dimensional offset
-        fn offset(x: i32, dx: i32, size: i32) -> Option<i32> {
-            let result = x + dx;
-            let min = -size;
-            let max = size-1;
-            if (min..=max).contains(&result) { Some(result) } 
-            else { None }
-        }
-        Some((
-            offset(xy.0, dxy.0, self.size)?,
-            offset(xy.1, dxy.1, self.size)?,
-            offset(xy.2, dxy.2, self.size)?,
-            offset(xy.3, dxy.3, self.size)?
-        ))
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        let plane = Grid::parse(input, Cell::from_char)?;
+        Ok(ExpandingGrid::from_plane(&plane, Cell::Inactive))
     }
-}
-
-impl Grid<Cell> {
-    /// Expands the grid in each direction.
-    fn expand(&mut self, by: i32) {
-        let old_size = self.size;
-        let new_size = self.size + by;
-        assert!(new_size >= old_size);
-
-        let mut result  = Self::new_size(new_size);
-
-        let min = -(self.size);
-        let max = self.size - 1;
 
-        let iter = (min..=max)
-            .flat_map(|w| (min..=max).map(move |z| (z, w)))
-            .flat_map(|(z, w)| (min..=max).map(move |y| (y, z, w)))
-            .flat_map(|(y, z, w)| (min..=max).map(move |x| (x, y, z, w)));
-        for (x, y, z, w) in iter {
-            *result.get_mut(x, y, z, w) = *self.get(x, y, z, w);
-        }
-        *self = result;
-    }
-
-    fn new_size(size: i32) -> Self {
-        Self { 
-            cells: std::iter::repeat(Cell::Inactive).take((size*size*size*size*2*2*2*2) as usize).collect(),
-            size
-        }
+    fn solve(mut cubes: Self::Input) -> Result<Answer, Error> {
+        (0..6).for_each(|_| cubes = step(&cubes));
+        Ok(cubes.iter().filter(|(_, &c)| c == Cell::Active).count().into())
     }
 }
-
-impl Grid<Cell> {
-    /// Runs an iteration. Returns false if we updated any
-    /// cells, or true if we've stabalized.
-    fn run_iter(&mut self) -> bool {
-        dbg!("run_iter");
-        let original = self.clone();
-        let mut stable = true;
-        
-        // True if any cells on the very edge are active.
-        let mut active_boundary = false;
-
-        let min = -(self.size);
-        let max = self.size - 1;
-
-        let iter = (min..=max)
-            .flat_map(|w| (min..=max).map(move |z| (z, w)))
-            .flat_map(|(z, w)| (min..=max).map(move |y| (y, z, w)))
-            .flat_map(|(y, z, w)| (min..=max).map(move |x| (x, y, z, w)));
-        for (x, y, z, w) in iter {
-            // By Duncan, iterates from -1..1 in 3 dimensions (excluding the origin)
-            let neighbors_iter = (-1..=1)
-                .flat_map(move |y| (-1..=1).map(move |x| (x, y)))
-                .flat_map(move |(x, y)| (-1..=1).map(move |z| (x, y, z)))
-                .flat_map(move |(x, y, z)| (-1..=1).map(move |w| (x, y, z, w)))
-                .filter(|xyz| !matches!(xyz, (0, 0, 0, 0)))
-                .filter_map(|dxyz| self.offset((x, y, z, w), dxyz));
-
-            let active_neighbors = neighbors_iter
-                .filter(|(x, y, z, w)| (original.get(*x, *y, *z, *w)) == &Cell::Active)
-                .count();
-            //dbg!(active_neighbors);
-
-            let current = self.get_mut(x, y, z, w);
-            let new = match (*current, active_neighbors) {
-                (Cell::Inactive, 3) => Cell::Active,
-                (Cell::Active, adjacent) =>
-                    if adjacent == 2 || adjacent == 3 { Cell::Active }
-                    else { Cell::Inactive },
-                (cell, _) => cell
-            };
-
-            if *current != new {
-                *current = new;
-                stable = false;
-                if new == Cell::Active && [x, y, z].iter().find(|x| [min, max].contains(x)) != None {
-                    active_boundary = true;
-                }
-            }
-        }
-
-        if active_boundary { self.expand(1); }
-
-        stable
-    }
-}
-
-pub fn run() {
-    let mut initial_grid = Vec::<Cell>::new();
-    let mut width = None;
-    for line in io::stdin().lock().lines() {
-        let line = line.expect("read error");
-        let mut items: Vec<Cell> = line.chars().map(|c| 
-            Cell::from_char(c).expect("invalid character in line")
-        ).collect();
-
-        assert!(width == None || width == Some(items.len()));
-        width = Some(items.len());
-        initial_grid.append(&mut items);
-    }
-
-    let width = width.expect("no input") as i32;
-    let size = (width + 1)/2;
-    let mut grid = Grid::<Cell>::new_size(size);
-    let min = -size;
-    let min_index = grid.index(min, min, 0, 0);
-    let max_index = grid.index(min, min, 1, 0);
-    grid.cells[min_index..max_index].copy_from_slice(&initial_grid);
-
-    (0..6).for_each(|_| { grid.run_iter(); });
-    println!("{}", grid.cells.iter().filter(|&&c| c == Cell::Active).count());
-}
@@ -0,0 +1,115 @@
+//! The kinds of numbers homework can be evaluated with. Plain `i64`s are the fastest, and every
+//! operation on them is checked; big integers never overflow; and rationals never overflow and
+//! divide exactly, instead of rounding towards zero.
+
+use std::{fmt, str::FromStr};
+use num_traits::Zero;
+pub use num_bigint::BigInt;
+pub use num_rational::BigRational;
+use crate::expr::{EvalError, Operator};
+
+/// A kind of number an expression can be evaluated with.
+pub trait Number: Clone + Ord + fmt::Display + Sized {
+    fn from_i64(value: i64) -> Self;
+
+    /// Parses a whole number written in decimal, failing with [`EvalError::Overflow`] if it's
+    /// too big to be represented.
+    fn parse(text: &str) -> Result<Self, EvalError>;
+
+    /// Computes 'lhs op rhs'.
+    fn apply(op: Operator, lhs: Self, rhs: Self) -> Result<Self, EvalError>;
+
+    /// Computes '-self'.
+    fn negate(self) -> Result<Self, EvalError>;
+}
+
+impl Number for i64 {
+    fn from_i64(value: i64) -> Self {
+        value
+    }
+
+    fn parse(text: &str) -> Result<Self, EvalError> {
+        text.parse().map_err(|_| EvalError::Overflow)
+    }
+
+    fn apply(op: Operator, lhs: Self, rhs: Self) -> Result<Self, EvalError> {
+        op.apply(lhs, rhs)
+    }
+
+    fn negate(self) -> Result<Self, EvalError> {
+        self.checked_neg().ok_or(EvalError::Overflow)
+    }
+}
+
+impl Number for BigInt {
+    fn from_i64(value: i64) -> Self {
+        value.into()
+    }
+
+    fn parse(text: &str) -> Result<Self, EvalError> {
+        Ok(BigInt::from_str(text).expect("the lexer only accepts digits"))
+    }
+
+    fn apply(op: Operator, lhs: Self, rhs: Self) -> Result<Self, EvalError> {
+        Ok(match op {
+            Operator::Add => lhs + rhs,
+            Operator::Subtract => lhs - rhs,
+            Operator::Multiply => lhs * rhs,
+            Operator::Divide if rhs.is_zero() => return Err(EvalError::DivisionByZero),
+            // Rounds towards zero, like i64.
+            Operator::Divide => lhs / rhs
+        })
+    }
+
+    fn negate(self) -> Result<Self, EvalError> {
+        Ok(-self)
+    }
+}
+
+impl Number for BigRational {
+    fn from_i64(value: i64) -> Self {
+        BigRational::from_integer(value.into())
+    }
+
+    fn parse(text: &str) -> Result<Self, EvalError> {
+        BigInt::parse(text).map(BigRational::from_integer)
+    }
+
+    fn apply(op: Operator, lhs: Self, rhs: Self) -> Result<Self, EvalError> {
+        Ok(match op {
+            Operator::Add => lhs + rhs,
+            Operator::Subtract => lhs - rhs,
+            Operator::Multiply => lhs * rhs,
+            Operator::Divide if rhs.is_zero() => return Err(EvalError::DivisionByZero),
+            Operator::Divide => lhs / rhs
+        })
+    }
+
+    fn negate(self) -> Result<Self, EvalError> {
+        Ok(-self)
+    }
+}
+
+/// Which kind of number to evaluate with, chosen at runtime.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Arithmetic {
+    /// `i64`, failing on overflow.
+    Checked,
+
+    /// `BigInt`.
+    Big,
+
+    /// `BigRational`.
+    Rational
+}
+
+impl Arithmetic {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "checked" => Some(Arithmetic::Checked),
+            "big" => Some(Arithmetic::Big),
+            "rational" => Some(Arithmetic::Rational),
+            _ => None
+        }
+    }
+}
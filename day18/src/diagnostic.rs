@@ -0,0 +1,91 @@
+//! Friendlier error reports for homework, which point at the problem in the line it's on:
+//!
+//! ```text
+//! error: expected a value (found '*')
+//!  --> line 2, column 5
+//!   |
+//! 2 | 1 + * 2
+//!   |     ^
+//!   = hint: a value is a number, or an expression in parentheses
+//! ```
+
+use std::{collections::HashMap, fmt::Write, ops::Range};
+use common::ErrorKind;
+use crate::{arithmetic::Number, expr::{EvalError, Expr}, lexer::SyntaxError, parser::{self, Spans}, rules::Rules};
+
+/// A problem with one line of the homework.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// The line the problem is on, starting from 1.
+    pub line: usize,
+
+    /// The bytes of the line the problem is with. Empty if it's between two characters,
+    /// such as at the end of the line.
+    pub span: Range<usize>,
+
+    pub message: String,
+
+    /// A suggestion for how to fix the problem.
+    pub hint: Option<&'static str>
+}
+
+impl Diagnostic {
+    pub(crate) fn syntax(line: usize, text: &str, e: SyntaxError) -> Self {
+        let start = e.text.as_ptr() as usize - text.as_ptr() as usize;
+        // An unexpected character already names the offending text.
+        let found = match e.kind {
+            ErrorKind::UnexpectedChar(_) => String::new(),
+            _ if e.text.is_empty() => String::new(),
+            _ => format!(" (found '{}')", e.text)
+        };
+        Diagnostic { line, span: start..start + e.text.len(), message: format!("{}{}", e.kind, found), hint: e.hint }
+    }
+
+    /// Evaluates `expr`, pointing at the part of it which couldn't be evaluated if that fails.
+    pub(crate) fn eval<N: Number>(line: usize, expr: &Expr, spans: &Spans, variables: &HashMap<String, N>) -> Result<N, Self> {
+        let mut path = Vec::new();
+        expr.eval_traced(variables, &mut path).map_err(|e| Diagnostic::eval_error(line, spans.find(&path), e))
+    }
+
+    fn eval_error(line: usize, span: Range<usize>, e: EvalError) -> Self {
+        let hint = match &e {
+            EvalError::Overflow => "values and intermediate results have to fit in 64 bits, unless big integers are used",
+            EvalError::DivisionByZero => "something on the right of a '/' works out to zero",
+            EvalError::Undefined(_) => "give it a value first, with 'let'"
+        };
+        Diagnostic { line, span, message: e.to_string(), hint: Some(hint) }
+    }
+
+    /// Renders the diagnostic, showing the line from `input` with the problem underlined.
+    pub fn render(&self, input: &str) -> String {
+        let text = input.lines().nth(self.line - 1).unwrap_or("");
+        let number = self.line.to_string();
+        let margin = " ".repeat(number.len());
+
+        // Columns are counted in characters, so that the carets line up with what's printed.
+        let column = text[..self.span.start].chars().count();
+        let width = text[self.span.clone()].chars().count().max(1);
+
+        let mut result = String::new();
+        // Writing to a String never fails.
+        writeln!(result, "error: {}", self.message).unwrap();
+        writeln!(result, "{}--> line {}, column {}", margin, self.line, column + 1).unwrap();
+        writeln!(result, "{} |", margin).unwrap();
+        writeln!(result, "{} | {}", number, text).unwrap();
+        writeln!(result, "{} | {}{}", margin, " ".repeat(column), "^".repeat(width)).unwrap();
+        if let Some(hint) = self.hint {
+            writeln!(result, "{} = hint: {}", margin, hint).unwrap();
+        }
+        result
+    }
+}
+
+/// Parses and evaluates each line of the homework on its own, so that a problem on one line doesn't
+/// stop the rest from being evaluated. Returns each line's expression and value, or what went wrong with it.
+pub fn evaluate_all<N: Number>(input: &str, rules: &Rules) -> Vec<Result<(Expr, N), Diagnostic>> {
+    input.lines().enumerate().map(|(index, text)| {
+        let (expr, spans) = parser::parse_line(text, rules).map_err(|e| Diagnostic::syntax(index + 1, text, e))?;
+        let value = Diagnostic::eval(index + 1, &expr, &spans, &HashMap::new())?;
+        Ok((expr, value))
+    }).collect()
+}
@@ -0,0 +1,238 @@
+//! Expression trees, which both parts' parsers produce, so that an expression can be inspected
+//! after it's been parsed instead of only evaluated. Variables, negation and function calls only
+//! come from the calculator's extended syntax, never from homework.
+
+use std::{collections::HashMap, fmt};
+use common::{Answer, Error};
+use crate::{arithmetic::Number, rules::{Associativity, Rules}};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Operator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide
+}
+
+/// A built-in function.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Function {
+    Min,
+    Max,
+    Abs
+}
+
+/// Something that went wrong while evaluating an expression.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EvalError {
+    Overflow,
+    DivisionByZero,
+
+    /// A variable which hasn't been given a value.
+    Undefined(String)
+}
+
+impl Operator {
+    /// Converts a character to an operator.
+    pub fn parse(c: char) -> Option<Operator> {
+        match c {
+            '+' => Some(Operator::Add),
+            '-' => Some(Operator::Subtract),
+            '*' => Some(Operator::Multiply),
+            '/' => Some(Operator::Divide),
+            _ => None
+        }
+    }
+
+    /// Computes 'lhs op rhs'.
+    pub fn apply(self, lhs: i64, rhs: i64) -> Result<i64, EvalError> {
+        match self {
+            Operator::Add => lhs.checked_add(rhs),
+            Operator::Subtract => lhs.checked_sub(rhs),
+            Operator::Multiply => lhs.checked_mul(rhs),
+            Operator::Divide if rhs == 0 => return Err(EvalError::DivisionByZero),
+            Operator::Divide => lhs.checked_div(rhs)
+        }.ok_or(EvalError::Overflow)
+    }
+}
+
+impl fmt::Display for Operator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Operator::Add => "+",
+            Operator::Subtract => "-",
+            Operator::Multiply => "*",
+            Operator::Divide => "/"
+        })
+    }
+}
+
+impl Function {
+    pub fn parse(name: &str) -> Option<Function> {
+        match name {
+            "min" => Some(Function::Min),
+            "max" => Some(Function::Max),
+            "abs" => Some(Function::Abs),
+            _ => None
+        }
+    }
+
+    /// Calls the function. `min` and `max` need at least one argument, and `abs` exactly one.
+    pub fn apply<N: Number>(self, arguments: Vec<N>) -> Result<N, EvalError> {
+        let mut arguments = arguments.into_iter();
+        let missing = "the parser checks the number of arguments";
+        match self {
+            Function::Min => Ok(arguments.min().expect(missing)),
+            Function::Max => Ok(arguments.max().expect(missing)),
+            Function::Abs => match arguments.next().expect(missing) {
+                value if value < N::from_i64(0) => value.negate(),
+                value => Ok(value)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Function {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Function::Min => "min",
+            Function::Max => "max",
+            Function::Abs => "abs"
+        })
+    }
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EvalError::Overflow => write!(f, "overflow"),
+            EvalError::DivisionByZero => write!(f, "division by zero"),
+            EvalError::Undefined(name) => write!(f, "unknown variable '{}'", name)
+        }
+    }
+}
+
+/// A parsed expression. Parentheses aren't kept, since the shape of the tree says how it's grouped.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Expr {
+    /// A number, as it's written. It's only parsed when it's evaluated, since whether it fits
+    /// depends on the kind of number it's evaluated with.
+    Value(String),
+    Binary(Operator, Box<Expr>, Box<Expr>),
+    Variable(String),
+    Negate(Box<Expr>),
+
+    /// A call to a function, with the arguments it takes (see [`Function::apply`]).
+    Call(Function, Vec<Expr>)
+}
+
+impl Expr {
+    /// Creates the expression 'lhs op rhs'.
+    pub fn binary(op: Operator, lhs: Expr, rhs: Expr) -> Self {
+        Expr::Binary(op, Box::new(lhs), Box::new(rhs))
+    }
+
+    pub fn eval(&self) -> Result<i64, EvalError> {
+        self.eval_as()
+    }
+
+    /// Evaluates the expression with any kind of number.
+    pub fn eval_as<N: Number>(&self) -> Result<N, EvalError> {
+        self.eval_in(&HashMap::new())
+    }
+
+    /// Evaluates the expression with any kind of number, looking up variables in `variables`.
+    pub fn eval_in<N: Number>(&self, variables: &HashMap<String, N>) -> Result<N, EvalError> {
+        self.eval_traced(variables, &mut Vec::new())
+    }
+
+    /// Evaluates the expression like [`Expr::eval_in`]. If that fails, leaves `path` leading to the
+    /// part of the expression which failed: the index of the operand or argument to go into, at each
+    /// step down from this one.
+    pub(crate) fn eval_traced<N: Number>(&self, variables: &HashMap<String, N>, path: &mut Vec<usize>) -> Result<N, EvalError> {
+        let mut child = |index: usize, child: &Expr| {
+            path.push(index);
+            let value = child.eval_traced(variables, path)?;
+            path.pop();
+            Ok(value)
+        };
+        match self {
+            Expr::Value(value) => N::parse(value),
+            Expr::Binary(op, lhs, rhs) => N::apply(*op, child(0, lhs)?, child(1, rhs)?),
+            Expr::Variable(name) => variables.get(name).cloned().ok_or_else(|| EvalError::Undefined(name.clone())),
+            Expr::Negate(operand) => child(0, operand)?.negate(),
+            Expr::Call(function, arguments) => function.apply(arguments.iter().enumerate()
+                .map(|(index, argument)| child(index, argument))
+                .collect::<Result<_, _>>()?)
+        }
+    }
+
+    /// Replaces every subexpression that can be evaluated with its value. Subexpressions which
+    /// overflow or divide by zero, or use variables, are left as they are.
+    pub fn fold(&self) -> Expr {
+        let folded = match self {
+            Expr::Value(_) | Expr::Variable(_) => return self.clone(),
+            Expr::Binary(op, lhs, rhs) => Expr::binary(*op, lhs.fold(), rhs.fold()),
+            Expr::Negate(operand) => Expr::Negate(Box::new(operand.fold())),
+            Expr::Call(function, arguments) => Expr::Call(*function, arguments.iter().map(Expr::fold).collect())
+        };
+        // Anything that can be evaluated has already been folded into a value,
+        // so this only succeeds if every operand is a value.
+        folded.eval().map(|value| Expr::Value(value.to_string())).unwrap_or(folded)
+    }
+
+    /// Displays the expression with as few parentheses as possible, for a reader who expects
+    /// operators to follow the given rules.
+    pub fn pretty<'a>(&'a self, rules: &'a Rules) -> Pretty<'a> {
+        Pretty { expr: self, rules }
+    }
+}
+
+/// An expression formatted with minimal parentheses; see [`Expr::pretty`].
+pub struct Pretty<'a> {
+    expr: &'a Expr,
+    rules: &'a Rules
+}
+
+impl fmt::Display for Pretty<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (op, lhs, rhs) = match self.expr {
+            Expr::Value(value) => return write!(f, "{}", value),
+            Expr::Variable(name) => return write!(f, "{}", name),
+            // Negation binds more tightly than any operator.
+            Expr::Negate(operand) => return match **operand {
+                Expr::Binary(..) => write!(f, "-({})", operand.pretty(self.rules)),
+                _ => write!(f, "-{}", operand.pretty(self.rules))
+            },
+            Expr::Call(function, arguments) => {
+                let arguments = arguments.iter().map(|argument| argument.pretty(self.rules).to_string());
+                return write!(f, "{}({})", function, arguments.collect::<Vec<_>>().join(", "));
+            }
+            Expr::Binary(op, lhs, rhs) => (*op, lhs, rhs)
+        };
+
+        // A child needs parentheses if it would otherwise be grouped with a neighbouring operand.
+        // That includes operators of the same precedence on the side they don't group towards.
+        let rules = self.rules;
+        let operand = |f: &mut fmt::Formatter<'_>, child: &Expr, side: Associativity| {
+            let pretty = child.pretty(rules);
+            match child {
+                Expr::Binary(child_op, _, _) if rules.precedence(*child_op) < rules.precedence(op)
+                    || (rules.precedence(*child_op) == rules.precedence(op) && rules.associativity(op) != side) =>
+                    write!(f, "({})", pretty),
+                _ => write!(f, "{}", pretty)
+            }
+        };
+        operand(f, lhs, Associativity::Left)?;
+        write!(f, " {} ", op)?;
+        operand(f, rhs, Associativity::Right)
+    }
+}
+
+/// Adds up the values of every line of the homework.
+pub fn sum(expressions: &[Expr]) -> Result<Answer, Error> {
+    expressions.iter().enumerate().try_fold(0i64, |total, (index, expr)| {
+        expr.eval().and_then(|value| Operator::Add.apply(total, value))
+            .map_err(|e| Error::InvalidInput(format!("{} on line {}", e, index + 1)))
+    }).map(Answer::from)
+}
@@ -0,0 +1,158 @@
+//! Splits a line of homework into tokens, for both parts' parsers.
+//!
+//! Homework only has numbers, parentheses and the four operators. The calculator's extended syntax
+//! adds names (of variables and functions), commas between function arguments, and `=` for `let`.
+
+use std::ops::Range;
+use common::ErrorKind;
+use crate::expr::Operator;
+
+/// A token in the input stream.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Token {
+    Operator(Operator),
+    OpenParen,
+    CloseParen,
+    Value,
+
+    // Only in the extended syntax:
+    Identifier,
+    Comma,
+    Equals
+}
+
+/// An error that can occur during lexing or parsing, along with the text where it occurred.
+#[derive(Debug)]
+pub(crate) struct SyntaxError<'a> {
+    pub kind: ErrorKind,
+    pub text: &'a str,
+
+    /// A suggestion for how to fix the error.
+    pub hint: Option<&'static str>
+}
+
+impl<'a> SyntaxError<'a> {
+    pub fn new(kind: ErrorKind, text: &'a str) -> Self {
+        Self { kind, text, hint: None }
+    }
+
+    /// Adds a suggestion for how to fix the error.
+    pub fn hint(self, hint: &'static str) -> Self {
+        Self { hint: Some(hint), ..self }
+    }
+}
+
+/// An iterator which converts a line of text into a stream of tokens, along with the text of each token.
+pub(crate) struct TokenStream<'a> {
+    line: &'a str,
+    stream: std::iter::Peekable<std::str::CharIndices<'a>>,
+
+    /// Whether to accept the extended syntax.
+    pub extended: bool,
+
+    /// A token which has been peeked at, but not consumed.
+    peeked: Option<Option<<Self as Iterator>::Item>>
+}
+
+impl<'a> Iterator for TokenStream<'a> {
+    type Item = Result<(Token, &'a str), SyntaxError<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(peeked) = self.peeked.take() {
+            return peeked;
+        }
+
+        // Skip whitespace characters
+        while self.stream.peek().map(|(_, c)| c.is_whitespace()).unwrap_or(false) {
+            self.stream.next();
+        }
+
+        let &(start, c) = self.stream.peek()?;
+
+        // Is this a special character?
+        if let Some(special) = self.is_special(c) {
+            self.stream.next();
+            Some(Ok((special, &self.line[start..start + c.len_utf8()])))
+        }
+        // Is this a valid value?
+        else { Some(self.read_value()) }
+    }
+}
+
+impl<'a> TokenStream<'a> {
+    /// Creates a new TokenStream for homework.
+    pub fn new(line: &'a str) -> Self {
+        Self { line, stream: line.char_indices().peekable(), extended: false, peeked: None }
+    }
+
+    /// Creates a new TokenStream which accepts the extended syntax.
+    pub fn extended(line: &'a str) -> Self {
+        Self { extended: true, ..Self::new(line) }
+    }
+
+    /// Returns the next token without consuming it.
+    pub fn peek(&mut self) -> Option<&<Self as Iterator>::Item> {
+        if self.peeked.is_none() {
+            self.peeked = Some(self.next());
+        }
+        self.peeked.as_ref().unwrap().as_ref()
+    }
+
+    /// The (empty) text at the end of the line, for errors which occur there.
+    pub fn end(&self) -> &'a str {
+        &self.line[self.line.len()..]
+    }
+
+    /// Where `text`, which must be a slice of the line, is in it.
+    pub fn span(&self, text: &str) -> Range<usize> {
+        let start = text.as_ptr() as usize - self.line.as_ptr() as usize;
+        start..start + text.len()
+    }
+
+    /// The index of the next character, or the length of the line if there are none left.
+    fn position(&mut self) -> usize {
+        self.stream.peek().map_or(self.line.len(), |&(index, _)| index)
+    }
+
+    /// Reads an integer (or in the extended syntax, a name) into a token. Integers are left as
+    /// text, since how big they can be depends on the kind of number they're evaluated with.
+    fn read_value(&mut self) -> Result<(Token, &'a str), SyntaxError<'a>> {
+        let start = self.position();
+
+        // while we have more input...
+        while let Some(&(_, c)) = self.stream.peek() {
+            if c.is_whitespace() || self.is_special(c).is_some() {
+                // We've hit a delimiter; stop
+                break;
+            } else {
+                // Add it to the value.
+                self.stream.next();
+            }
+        }
+
+        let text = &self.line[start..self.position()];
+        if self.extended && text.starts_with(|c: char| c.is_alphabetic() || c == '_') {
+            return if text.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                Ok((Token::Identifier, text))
+            } else {
+                Err(SyntaxError::new(ErrorKind::Expected("a name"), text)
+                    .hint("names are made of letters, digits and underscores"))
+            };
+        }
+
+        if !text.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(SyntaxError::new(ErrorKind::InvalidNumber, text).hint("values are whole numbers"));
+        }
+        Ok((Token::Value, text))
+    }
+
+    /// Is this character an operator, parenthesis, or other punctuation?
+    fn is_special(&self, c: char) -> Option<Token> {
+        if let Some(op) = Operator::parse(c) { Some(Token::Operator(op)) }
+        else if c == '(' { Some(Token::OpenParen) }
+        else if c == ')' { Some(Token::CloseParen) }
+        else if c == ',' && self.extended { Some(Token::Comma) }
+        else if c == '=' && self.extended { Some(Token::Equals) }
+        else { None }
+    }
+}
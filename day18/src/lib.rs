@@ -0,0 +1,16 @@
+use common::Example;
+
+pub mod arithmetic;
+pub mod diagnostic;
+pub mod expr;
+mod lexer;
+pub mod parser;
+pub mod part1;
+pub mod part2;
+pub mod repl;
+pub mod rules;
+
+/// The examples from the puzzle description.
+pub const EXAMPLES: &[Example] = &[
+    Example { input: include_str!("../test"), answers: [Some("26457"), Some("694173")] },
+];
@@ -0,0 +1,208 @@
+//! The expression engine shared by both parts, which groups operators according to a table of rules.
+//! The calculator uses it too, with the extended syntax described in the `lexer` module.
+
+use std::ops::Range;
+use common::{ErrorKind, ParseError};
+use crate::expr::{Expr, Function, Operator};
+use crate::lexer::{SyntaxError, Token, TokenStream};
+use crate::rules::{Associativity, Rules};
+
+/// A line of input to the calculator.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Statement {
+    /// `let name = expression`
+    Let(String, Expr),
+    Expr(Expr)
+}
+
+/// Where each part of an expression was written in its line, arranged like the expression itself:
+/// one child for each operand or argument, in order. A part in parentheses includes them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Spans {
+    pub span: Range<usize>,
+    pub children: Vec<Spans>
+}
+
+impl Spans {
+    fn binary(lhs: Spans, rhs: Spans) -> Spans {
+        Spans { span: lhs.span.start..rhs.span.end, children: vec![lhs, rhs] }
+    }
+
+    /// The span of the part reached by following `path` down from here, one child index at a time.
+    pub fn find(&self, path: &[usize]) -> Range<usize> {
+        path.iter().fold(self, |spans, &index| &spans.children[index]).span.clone()
+    }
+}
+
+/// Where an expression is nested, which decides what can end it.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Context {
+    /// At the top level, where the expression ends at the end of the line.
+    Root,
+
+    /// Inside parentheses.
+    Parens,
+
+    /// An argument of a function, which can be followed by another.
+    Arguments
+}
+
+/// Parses every line of the homework, reporting every problem in it.
+pub fn parse(input: &str, rules: &Rules) -> Result<Vec<Expr>, Vec<ParseError>> {
+    common::collect_all(input.lines()
+        .map(|line| parse_line(line, rules).map(|(expr, _)| expr).map_err(|e| ParseError::at(input, e.text, e.kind))))
+}
+
+/// Parses a single expression, along with where each part of it is in the line.
+pub(crate) fn parse_line<'a>(line: &'a str, rules: &Rules) -> Result<(Expr, Spans), SyntaxError<'a>> {
+    let (expr, spans, _) = parse_(&mut TokenStream::new(line), rules, Context::Root)?;
+    Ok((expr, spans))
+}
+
+/// Parses a line of calculator input, which uses the extended syntax, along with where each part
+/// of its expression is in the line.
+pub(crate) fn parse_statement<'a>(line: &'a str, rules: &Rules) -> Result<(Statement, Spans), SyntaxError<'a>> {
+    let stream = &mut TokenStream::extended(line);
+    if let Some(Ok((Token::Identifier, "let"))) = stream.peek() {
+        stream.next();
+        let name = match stream.next().transpose()? {
+            Some((Token::Identifier, name)) if name != "let" && Function::parse(name).is_none() => name,
+            Some((_, text)) => return Err(SyntaxError::new(ErrorKind::Expected("a variable name"), text)
+                .hint("names are made of letters, digits and underscores, and can't be 'let' or a function")),
+            None => return Err(SyntaxError::new(ErrorKind::Expected("a variable name"), stream.end()))
+        };
+        match stream.next().transpose()? {
+            Some((Token::Equals, _)) => (),
+            Some((_, text)) => return Err(SyntaxError::new(ErrorKind::Expected("'='"), text)),
+            None => return Err(SyntaxError::new(ErrorKind::Expected("'='"), stream.end()))
+        }
+        let (expr, spans, _) = parse_(stream, rules, Context::Root)?;
+        Ok((Statement::Let(name.to_string(), expr), spans))
+    } else {
+        let (expr, spans, _) = parse_(stream, rules, Context::Root)?;
+        Ok((Statement::Expr(expr), spans))
+    }
+}
+
+/// Parses a value: a number, or an expression in parentheses. The extended syntax also
+/// allows variables, function calls, and values negated with a '-'.
+fn parse_value<'a>(stream: &mut TokenStream<'a>, rules: &Rules) -> Result<(Expr, Spans), SyntaxError<'a>> {
+    // If we find the end of the expression instead, return an error.
+    let (value_tok, text) = stream.next()
+        .unwrap_or_else(|| Err(SyntaxError::new(ErrorKind::UnexpectedEof, stream.end())
+            .hint("there should be a value here, after the last operator or '('")))?;
+    let opens_call = |stream: &mut TokenStream<'a>| matches!(stream.peek(), Some(Ok((Token::OpenParen, _))));
+    let span = stream.span(text);
+    let start = span.start;
+    // A value which doesn't have anything inside it to point at.
+    let leaf = |expr| (expr, Spans { span: span.clone(), children: Vec::new() });
+
+    Ok(match value_tok {
+        Token::Value => leaf(Expr::Value(text.to_string())),
+        Token::OpenParen => {
+            let (expr, spans, end) = parse_(stream, rules, Context::Parens)?;
+            let end = stream.span(end.expect("parentheses end with a ')'").1).end;
+            (expr, Spans { span: start..end, ..spans })
+        }
+        Token::Operator(Operator::Subtract) if stream.extended => {
+            let (operand, spans) = parse_value(stream, rules)?;
+            (Expr::Negate(Box::new(operand)), Spans { span: start..spans.span.end, children: vec![spans] })
+        }
+
+        Token::Identifier => match Function::parse(text) {
+            Some(function) if opens_call(stream) => {
+                stream.next();
+                let mut arguments = Vec::new();
+                let mut children = Vec::new();
+                let end = loop {
+                    let (argument, spans, end) = parse_(stream, rules, Context::Arguments)?;
+                    arguments.push(argument);
+                    children.push(spans);
+                    if let Some((Token::CloseParen, text)) = end { break stream.span(text).end; }
+                };
+                if function == Function::Abs && arguments.len() != 1 {
+                    return Err(SyntaxError::new(ErrorKind::Expected("exactly one argument"), text)
+                        .hint("abs takes one argument"));
+                }
+                (Expr::Call(function, arguments), Spans { span: start..end, children })
+            }
+            Some(_) => return Err(SyntaxError::new(ErrorKind::Expected("'(' and the function's arguments"), &text[text.len()..])),
+            None if opens_call(stream) => return Err(SyntaxError::new(ErrorKind::Unknown("function"), text)
+                .hint("the functions are min, max and abs")),
+            None => leaf(Expr::Variable(text.to_string()))
+        },
+
+        _ => return Err(SyntaxError::new(ErrorKind::Expected("a value"), text)
+            .hint("a value is a number, or an expression in parentheses")),
+    })
+}
+
+/// An expression, where its parts are, and the token that ended it, along with that token's text.
+type Parsed<'a> = (Expr, Spans, Option<(Token, &'a str)>);
+
+/// Parses an expression nested in the given context, and returns it along with where its parts are,
+/// and the token that ended it: a ')' or ',', or None at the end of the line.
+fn parse_<'a>(stream: &mut TokenStream<'a>, rules: &Rules, context: Context) -> Result<Parsed<'a>, SyntaxError<'a>> {
+
+    // A chain of 'value operator' sequences of increasing precedence, which only
+    // repeats a precedence for right-associative operators.
+    // When we see an inversion in the sequence such as A*B + C (or A*B*C),
+    // we immediately group A*B to preserve the increasing order.
+    let mut operator_stack = Vec::<(Expr, Spans, Operator)>::new();
+
+    let (final_value, final_spans, end) = loop {
+        let (value, spans) = parse_value(stream, rules)?;
+
+        // (Try to) parse an operator token.
+        // If we find the end the expression instead, return the last value.
+        let operator = match stream.next() {
+            Some(Ok((Token::Operator(op), _))) => op,
+
+            Some(Ok((Token::CloseParen, text))) => // This is the end of a parenthetical expression.
+                // Was it *supposed* to be a parenthetical expression?
+                // If so, stop parsing. and return the last value.
+                if context != Context::Root { break (value, spans, Some((Token::CloseParen, text))); }
+                else { return Err(SyntaxError::new(ErrorKind::UnexpectedChar(')'), text)
+                    .hint("this ')' has no '(' to match it")) },
+
+            // This is the end of a function argument, and there's another one to come.
+            Some(Ok((Token::Comma, text))) if context == Context::Arguments => break (value, spans, Some((Token::Comma, text))),
+
+            None =>     // This is the end of the input. Was it supposed to end here?
+                if context != Context::Root { return Err(SyntaxError::new(ErrorKind::UnexpectedEof, stream.end())
+                    .hint("a '(' is never closed; add a ')' to match it")) }
+                else { break (value, spans, None); },
+
+            Some(Ok((_, text))) => return Err(SyntaxError::new(ErrorKind::Expected("an operator"), text)
+                .hint("values need an operator between them, like '+' or '*'")),
+            Some(Err(e)) => return Err(e)
+        };
+
+        // We have a (value, operator) pair; add it to the stack.
+        // If we have a pattern of the form '... A*B +', group A*B.
+        let (mut value, mut spans) = (value, spans);
+        while let Some(&(_, _, prev_op)) = operator_stack.last() {
+            let (prev, next) = (rules.precedence(prev_op), rules.precedence(operator));
+            if prev > next || (prev == next && rules.associativity(operator) == Associativity::Left) {
+                let (prev_value, prev_spans, _) = operator_stack.pop().unwrap();
+                value = Expr::binary(prev_op, prev_value, value);
+                spans = Spans::binary(prev_spans, spans);
+            } else {
+                break;
+            }
+        }
+
+        operator_stack.push((value, spans, operator));
+    };
+
+    let (mut rhs, mut rhs_spans) = (final_value, final_spans);
+    // We've successfully reached the end of the expression.
+    // The operators left on the stack are in order of
+    // increasing precedence, or right-associative, so we
+    // can just group everything from right-to-left.
+    for (lhs, lhs_spans, op) in operator_stack.into_iter().rev() {
+        rhs = Expr::binary(op, lhs, rhs);
+        rhs_spans = Spans::binary(lhs_spans, rhs_spans);
+    }
+    Ok((rhs, rhs_spans, end))
+}
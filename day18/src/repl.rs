@@ -0,0 +1,109 @@
+//! A calculator which evaluates expressions as they're typed in, under whichever precedence rules
+//! are selected. On top of the homework's syntax, it has variables (`let x = 1 + 2`), negation,
+//! and the functions `min`, `max` and `abs`.
+
+use std::{collections::HashMap, io::{self, BufRead, Write}};
+use crate::{arithmetic::Number, diagnostic::Diagnostic, expr::Expr, parser::{self, Statement}, rules::{Rules, PRESETS}};
+
+/// The result of running one line of input.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Evaluated<N> {
+    /// The variable the value was stored in, if the line was a `let`.
+    pub name: Option<String>,
+    pub expr: Expr,
+    pub value: N
+}
+
+/// The calculator's state: the rules it uses, and the variables defined so far.
+pub struct Calculator<N> {
+    rules: Rules,
+    variables: HashMap<String, N>
+}
+
+impl<N: Number> Calculator<N> {
+    pub fn new(rules: Rules) -> Self {
+        Calculator { rules, variables: HashMap::new() }
+    }
+
+    pub fn rules(&self) -> &Rules {
+        &self.rules
+    }
+
+    /// Changes the rules used from now on. Variables keep the values they already have.
+    pub fn set_rules(&mut self, rules: Rules) {
+        self.rules = rules;
+    }
+
+    /// Every variable defined so far, in alphabetical order.
+    pub fn variables(&self) -> Vec<(&str, &N)> {
+        let mut variables = self.variables.iter().map(|(name, value)| (name.as_str(), value)).collect::<Vec<_>>();
+        variables.sort_unstable_by_key(|&(name, _)| name);
+        variables
+    }
+
+    /// Evaluates an expression or a `let`. Problems are reported as if `line` were line 1.
+    pub fn run(&mut self, line: &str) -> Result<Evaluated<N>, Diagnostic> {
+        let (statement, spans) = parser::parse_statement(line, &self.rules).map_err(|e| Diagnostic::syntax(1, line, e))?;
+        let (name, expr) = match statement {
+            Statement::Let(name, expr) => (Some(name), expr),
+            Statement::Expr(expr) => (None, expr)
+        };
+        let value = Diagnostic::eval(1, &expr, &spans, &self.variables)?;
+        if let Some(name) = &name {
+            self.variables.insert(name.clone(), value.clone());
+        }
+        Ok(Evaluated { name, expr, value })
+    }
+}
+
+const HELP: &str = "\
+type an expression to evaluate it, or 'let <name> = <expression>' to store it in a variable
+commands:
+  :rules             show the current precedence rules
+  :rules <rules>     switch to a preset (left-to-right, puzzle or standard), or to rules
+                     written out like 'left + -; left * /'
+  :vars              show every variable
+  :help              show this message
+  :quit              exit the calculator";
+
+/// Reads lines from `input` until it ends or a `:quit` command, writing results to `output`.
+/// Each result is shown with the parentheses it would need under the usual precedence rules,
+/// so that it's clear how the current rules grouped it.
+pub fn repl<N: Number>(calculator: &mut Calculator<N>, input: impl BufRead, mut output: impl Write) -> io::Result<()> {
+    writeln!(output, "type ':help' for a list of commands")?;
+    write!(output, "(day18) ")?;
+    output.flush()?;
+
+    for line in input.lines() {
+        let line = line?;
+        match line.trim().split_once(' ').map_or((line.trim(), ""), |(command, rest)| (command, rest.trim())) {
+            ("", _) => (),
+            (":rules", "") => {
+                let name = PRESETS.iter().find(|(_, rules)| rules == calculator.rules()).map(|(name, _)| *name);
+                writeln!(output, "{}", name.unwrap_or("custom"))?;
+            }
+            (":rules", rules) => match Rules::preset(rules).map_or_else(|| Rules::parse(rules), Ok) {
+                Ok(rules) => calculator.set_rules(rules),
+                Err(errors) => for e in errors {
+                    writeln!(output, "in the rules: {}", e)?;
+                }
+            },
+            (":vars", "") => for (name, value) in calculator.variables() {
+                writeln!(output, "{} = {}", name, value)?;
+            },
+            (":help", "") => writeln!(output, "{}", HELP)?,
+            (":quit", "") => return Ok(()),
+            (command, _) if command.starts_with(':') =>
+                writeln!(output, "unknown command '{}'; type ':help' for a list of commands", line.trim())?,
+            _ => match calculator.run(&line) {
+                Ok(Evaluated { name: Some(name), value, .. }) => writeln!(output, "{} = {}", name, value)?,
+                Ok(Evaluated { name: None, expr, value }) =>
+                    writeln!(output, "{} = {}", expr.pretty(&Rules::STANDARD), value)?,
+                Err(diagnostic) => write!(output, "{}", diagnostic.render(&line))?
+            }
+        }
+        write!(output, "(day18) ")?;
+        output.flush()?;
+    }
+    Ok(())
+}
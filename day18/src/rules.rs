@@ -0,0 +1,132 @@
+//! Tables of operator precedence and associativity, which decide how expressions are grouped.
+//!
+//! A table can be written out as text, one precedence level per line (or separated by `;`),
+//! from the loosest-binding level to the tightest. Each level names its associativity and then its
+//! operators, and every operator has to be in exactly one level. `#` starts a comment. The usual
+//! rules, where multiplication comes first, look like this:
+//!
+//! ```text
+//! left + -
+//! left * /
+//! ```
+
+use common::{ErrorKind, ParseError};
+use crate::expr::Operator;
+
+/// Which way a chain of operators with the same precedence is grouped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Associativity {
+    /// `a - b - c` means `(a - b) - c`.
+    Left,
+
+    /// `a - b - c` means `a - (b - c)`.
+    Right
+}
+
+/// The precedence and associativity of every operator.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rules {
+    /// For each operator, in the order they're declared, its precedence and associativity.
+    /// Operators with higher precedence are grouped first.
+    levels: [(u32, Associativity); 4]
+}
+
+/// The name of each preset, along with its rules.
+pub const PRESETS: &[(&str, Rules)] = &[
+    ("left-to-right", Rules::LEFT_TO_RIGHT),
+    ("puzzle", Rules::PUZZLE),
+    ("standard", Rules::STANDARD)
+];
+
+const OPERATORS: [Operator; 4] = [Operator::Add, Operator::Subtract, Operator::Multiply, Operator::Divide];
+
+impl Rules {
+    /// Every operator has the same precedence, so everything is grouped from left to right (part 1).
+    pub const LEFT_TO_RIGHT: Rules = Rules::new(0, 0);
+
+    /// Addition and subtraction come before multiplication and division (part 2).
+    pub const PUZZLE: Rules = Rules::new(1, 0);
+
+    /// Multiplication and division come before addition and subtraction, as usual.
+    pub const STANDARD: Rules = Rules::new(0, 1);
+
+    /// Left-associative rules with the given precedence for `+`/`-` and `*`/`/`.
+    const fn new(add: u32, multiply: u32) -> Self {
+        use Associativity::Left;
+        Rules { levels: [(add, Left), (add, Left), (multiply, Left), (multiply, Left)] }
+    }
+
+    /// Returns an integer representing the precedence of an operator.
+    /// Operators with higher `precedence()` should be evaluated first.
+    pub fn precedence(&self, op: Operator) -> u32 {
+        self.levels[op as usize].0
+    }
+
+    pub fn associativity(&self, op: Operator) -> Associativity {
+        self.levels[op as usize].1
+    }
+
+    /// Looks up one of the `PRESETS` by name.
+    pub fn preset(name: &str) -> Option<Rules> {
+        PRESETS.iter().find(|(preset, _)| *preset == name).map(|(_, rules)| rules.clone())
+    }
+
+    /// Parses a table of rules written out as text, reporting every problem in it.
+    pub fn parse(input: &str) -> Result<Rules, Vec<ParseError>> {
+        let mut errors = Vec::new();
+        let mut levels = [None; 4];
+        let lines = input.split(['\n', ';'])
+            .map(|line| line.split('#').next().unwrap())
+            .filter(|line| !line.trim().is_empty());
+        for (precedence, line) in lines.enumerate() {
+            let mut words = line.split_whitespace();
+            let word = words.next().unwrap();
+            let associativity = match word {
+                "left" => Associativity::Left,
+                "right" => Associativity::Right,
+                _ => {
+                    errors.push(ParseError::at(input, word, ErrorKind::Expected("'left' or 'right'")));
+                    continue;
+                }
+            };
+
+            let mut empty = true;
+            for word in words {
+                empty = false;
+                let mut chars = word.chars();
+                let op = match (chars.next().and_then(Operator::parse), chars.next()) {
+                    (Some(op), None) => op,
+                    _ => {
+                        errors.push(ParseError::at(input, word, ErrorKind::Expected("'+', '-', '*' or '/'")));
+                        continue;
+                    }
+                };
+                if levels[op as usize].replace((precedence as u32, associativity)).is_some() {
+                    errors.push(ParseError::at(input, word, ErrorKind::Duplicate("operator")));
+                }
+            }
+            if empty {
+                errors.push(ParseError::after(input, line.trim_end(), ErrorKind::Expected("an operator")));
+            }
+        }
+
+        // Only complain about missing operators if they weren't just written wrong.
+        if errors.is_empty() {
+            let end = &input[input.len()..];
+            errors.extend(OPERATORS.iter().filter(|&&op| levels[op as usize].is_none()).map(|op| {
+                ParseError::at(input, end, ErrorKind::Expected(match op {
+                    Operator::Add => "a level for '+'",
+                    Operator::Subtract => "a level for '-'",
+                    Operator::Multiply => "a level for '*'",
+                    Operator::Divide => "a level for '/'"
+                }))
+            }));
+        }
+
+        match levels {
+            [Some(add), Some(subtract), Some(multiply), Some(divide)] if errors.is_empty() =>
+                Ok(Rules { levels: [add, subtract, multiply, divide] }),
+            _ => Err(errors)
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use day18::{arithmetic::{BigInt, BigRational}, expr::{EvalError, Expr}, parser, rules::Rules};
+
+fn parse(line: &str) -> Expr {
+    parser::parse(line, &Rules::STANDARD).unwrap().remove(0)
+}
+
+#[test]
+fn backends_agree_when_nothing_overflows() {
+    for line in day18::EXAMPLES[0].input.lines() {
+        let expr = parse(line);
+        let value = expr.eval().unwrap();
+        assert_eq!(expr.eval_as::<BigInt>().unwrap(), BigInt::from(value));
+        assert_eq!(expr.eval_as::<BigRational>().unwrap(), BigRational::from_integer(value.into()));
+    }
+}
+
+#[test]
+fn overflow() {
+    let expr = parse("9223372036854775807 * 4 - 9223372036854775807 * 3");
+    assert_eq!(expr.eval(), Err(EvalError::Overflow));
+    assert_eq!(expr.eval_as::<BigInt>().unwrap().to_string(), "9223372036854775807");
+}
+
+#[test]
+fn big_literals() {
+    // Only 64-bit integers limit how big a number can be written.
+    let expr = parse("100000000000000000000 / 3 - 9223372036854775808");
+    assert_eq!(expr.eval(), Err(EvalError::Overflow));
+    assert_eq!(expr.eval_as::<BigInt>().unwrap().to_string(), "24109961296478557525");
+    assert_eq!(expr.eval_as::<BigRational>().unwrap().to_string(), "72329883889435672576/3");
+}
+
+#[test]
+fn division() {
+    let expr = parse("7 / 2 * 2 - 1 / 3");
+    assert_eq!(expr.eval(), Ok(6));
+    assert_eq!(expr.eval_as::<BigInt>().unwrap(), BigInt::from(6));
+    assert_eq!(expr.eval_as::<BigRational>().unwrap().to_string(), "20/3");
+
+    let expr = parse("1 / (3 - 3)");
+    assert_eq!(expr.eval(), Err(EvalError::DivisionByZero));
+    assert_eq!(expr.eval_as::<BigInt>(), Err(EvalError::DivisionByZero));
+    assert_eq!(expr.eval_as::<BigRational>(), Err(EvalError::DivisionByZero));
+}
@@ -0,0 +1,56 @@
+use day18::{diagnostic::{self, Diagnostic}, rules::Rules};
+
+#[test]
+fn keeps_going() {
+    let input = "1 + 2\n2 * (3 +\n 8 / (1 - 1) \n4 * 4";
+    let results = diagnostic::evaluate_all::<i64>(input, &Rules::PUZZLE);
+    let values = results.iter().map(|result| result.as_ref().ok().map(|(_, value)| *value)).collect::<Vec<_>>();
+    assert_eq!(values, vec![Some(3), None, None, Some(16)]);
+
+    let errors = results.into_iter().filter_map(Result::err).collect::<Vec<_>>();
+    assert_eq!(errors, vec![
+        Diagnostic {
+            line: 2, span: 8..8, message: "unexpected end of input".to_string(),
+            hint: Some("there should be a value here, after the last operator or '('")
+        },
+        Diagnostic {
+            line: 3, span: 1..12, message: "division by zero".to_string(),
+            hint: Some("something on the right of a '/' works out to zero")
+        }
+    ]);
+}
+
+#[test]
+fn points_at_the_failing_operation() {
+    // Under the puzzle's rules the addition happens first, and under the usual ones the multiplication.
+    let input = "2 * (1 + 9223372036854775807) * 3\n1 + 9223372036854775807 * 2 + 3";
+    let spans = |rules| diagnostic::evaluate_all::<i64>(input, rules).into_iter()
+        .map(|result| result.unwrap_err().span).collect::<Vec<_>>();
+    assert_eq!(spans(&Rules::PUZZLE), vec![4..29, 0..23]);
+    assert_eq!(spans(&Rules::STANDARD), vec![4..29, 4..27]);
+
+    let diagnostic = diagnostic::evaluate_all::<i64>(input, &Rules::STANDARD).remove(1).unwrap_err();
+    assert_eq!(diagnostic.render(input), concat!(
+        "error: overflow\n",
+        " --> line 2, column 5\n",
+        "  |\n",
+        "2 | 1 + 9223372036854775807 * 2 + 3\n",
+        "  |     ^^^^^^^^^^^^^^^^^^^^^^^\n",
+        "  = hint: values and intermediate results have to fit in 64 bits, unless big integers are used\n"
+    ));
+}
+
+#[test]
+fn rendering() {
+    let input = "1 + 2\n(3 × 4) + 5";
+    let diagnostic = diagnostic::evaluate_all::<i64>(input, &Rules::STANDARD).remove(1).unwrap_err();
+    assert_eq!(diagnostic.span, 3..5);
+    assert_eq!(diagnostic.render(input), concat!(
+        "error: invalid number (found '×')\n",
+        " --> line 2, column 4\n",
+        "  |\n",
+        "2 | (3 × 4) + 5\n",
+        "  |    ^\n",
+        "  = hint: values are whole numbers\n"
+    ));
+}
@@ -0,0 +1,40 @@
+use common::Solution;
+use day18::{expr::{EvalError, Expr}, part1::Part1, part2::Part2, rules::Rules};
+
+fn parse<S: Solution<Input = Vec<Expr>>>(line: &str) -> Expr {
+    S::parse(line).unwrap().remove(0)
+}
+
+#[test]
+fn grouping() {
+    let line = "1 + 2 * 3 + 4 * 5 + 6";
+    let standard = &Rules::STANDARD;
+    assert_eq!(parse::<Part1>(line).pretty(standard).to_string(), "((1 + 2) * 3 + 4) * 5 + 6");
+    assert_eq!(parse::<Part2>(line).pretty(standard).to_string(), "(1 + 2) * (3 + 4) * (5 + 6)");
+    assert_eq!(parse::<Part2>(line).pretty(&Rules::PUZZLE).to_string(), line);
+
+    // Redundant parentheses are dropped, and needed ones kept.
+    let line = "((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2";
+    assert_eq!(parse::<Part1>(line).pretty(standard).to_string(),
+        "((2 + 4) * 9 * ((6 + 9) * 8 + 6) + 6 + 2 + 4) * 2");
+    assert_eq!(parse::<Part1>("8 - (3 - 1) / (4 / 2)").pretty(standard).to_string(), "(8 - (3 - 1)) / (4 / 2)");
+}
+
+#[test]
+fn pretty_printing_round_trips() {
+    for line in day18::EXAMPLES[0].input.lines() {
+        for expr in &[parse::<Part1>(line), parse::<Part2>(line)] {
+            let printed = expr.pretty(&Rules::PUZZLE).to_string();
+            assert_eq!(&parse::<Part2>(&printed), expr, "{}", printed);
+        }
+    }
+}
+
+#[test]
+fn folding() {
+    let expr = parse::<Part1>("(1 + 2) * 3 + (4 / (2 - 2)) * 5");
+    assert_eq!(expr.eval(), Err(EvalError::DivisionByZero));
+    assert_eq!(expr.fold().pretty(&Rules::STANDARD).to_string(), "(9 + 4 / 0) * 5");
+    assert_eq!(parse::<Part2>("2 * 3 + 4").fold(), Expr::Value("14".to_string()));
+    assert!(Part1::solve(Part1::parse("9223372036854775807 + 1").unwrap()).is_err());
+}
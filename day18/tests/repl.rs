@@ -0,0 +1,49 @@
+use day18::{arithmetic::BigRational, expr::EvalError, repl::{self, Calculator}, rules::Rules};
+
+#[test]
+fn variables_and_functions() {
+    let mut calculator = Calculator::<i64>::new(Rules::STANDARD);
+    assert_eq!(calculator.run("let x = 2 + 3 * 4").unwrap().value, 14);
+    assert_eq!(calculator.run("let y = -x + 4").unwrap().value, -10);
+    assert_eq!(calculator.run("max(x, y) - min(x, y, 0) * abs(-2)").unwrap().value, 34);
+    assert_eq!(calculator.variables(), vec![("x", &14), ("y", &-10)]);
+
+    // Changing the rules doesn't change the values already stored.
+    calculator.set_rules(Rules::PUZZLE);
+    let result = calculator.run("x * 2 + 1").unwrap();
+    assert_eq!(result.value, 42);
+    assert_eq!(result.expr.pretty(&Rules::STANDARD).to_string(), "x * (2 + 1)");
+    assert_eq!(calculator.run("let x = x - 1").unwrap().value, 13);
+
+    let error = calculator.run("x + z").unwrap_err();
+    assert_eq!(error.message, EvalError::Undefined("z".to_string()).to_string());
+    assert_eq!(error.span, 4..5);
+    let error = calculator.run("1 + abs(-9223372036854775807 - 1)").unwrap_err();
+    assert_eq!((error.message.as_str(), error.span), ("overflow", 4..33));
+    assert_eq!(calculator.run("max(1, -(-9223372036854775807 - 1))").unwrap_err().span, 7..34);
+}
+
+#[test]
+fn syntax_errors() {
+    let mut calculator = Calculator::<i64>::new(Rules::STANDARD);
+    for (line, span) in &[("let = 3", 4..5), ("let x 3", 6..7), ("abs()", 4..5), ("abs(1, 2)", 0..3),
+                           ("max", 3..3), ("sqrt(4)", 0..4), ("min(1, 2", 8..8), ("1 + 2x", 4..6), ("(1, 2)", 2..3)] {
+        assert_eq!(&calculator.run(line).unwrap_err().span, span, "{}", line);
+    }
+}
+
+#[test]
+fn session() {
+    let input = "let half = 1 / 2\nhalf * 3 - 1\n:rules left-to-right\n1 + half * 4\n:rules\n:vars\n:nope\n:quit\n1\n";
+    let mut output = Vec::new();
+    repl::repl(&mut Calculator::<BigRational>::new(Rules::STANDARD), input.as_bytes(), &mut output).unwrap();
+    assert_eq!(String::from_utf8(output).unwrap(), "\
+type ':help' for a list of commands
+(day18) half = 1/2
+(day18) half * 3 - 1 = 1/2
+(day18) (day18) (1 + half) * 4 = 6
+(day18) left-to-right
+(day18) half = 1/2
+(day18) unknown command ':nope'; type ':help' for a list of commands
+(day18) ");
+}
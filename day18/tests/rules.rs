@@ -0,0 +1,50 @@
+use common::ErrorKind;
+use day18::{parser, rules::{Associativity, Rules, PRESETS}};
+
+fn eval(line: &str, rules: &Rules) -> i64 {
+    parser::parse(line, rules).unwrap()[0].eval().unwrap()
+}
+
+#[test]
+fn presets() {
+    let line = "2 + 3 * 4 - 6 / 2";
+    assert_eq!(eval(line, &Rules::LEFT_TO_RIGHT), 7);
+    assert_eq!(eval(line, &Rules::PUZZLE), -5);
+    assert_eq!(eval(line, &Rules::STANDARD), 11);
+    assert_eq!(PRESETS.len(), 3);
+    assert_eq!(Rules::preset("puzzle"), Some(Rules::PUZZLE));
+    assert_eq!(Rules::preset("nonsense"), None);
+}
+
+#[test]
+fn parsing_rules() {
+    let standard = Rules::parse("# the usual\nleft + -\nleft * /  # tighter\n").unwrap();
+    assert_eq!(standard, Rules::STANDARD);
+    assert_eq!(Rules::parse("left * /; left + -").unwrap(), Rules::PUZZLE);
+
+    // Right-associative levels group the other way, and print that way too.
+    let rules = Rules::parse("left + *; right - /").unwrap();
+    assert_eq!(rules.associativity(day18::expr::Operator::Subtract), Associativity::Right);
+    assert_eq!(eval("10 - 4 - 3", &rules), 9);
+    assert_eq!(eval("2 * 64 / 8 / 2", &rules), 32);
+    let expr = &parser::parse("(10 - 4) - 3 + 1 - 1", &rules).unwrap()[0];
+    assert_eq!(expr.pretty(&rules).to_string(), "(10 - 4) - 3 + 1 - 1");
+    assert_eq!(&parser::parse(&expr.pretty(&rules).to_string(), &rules).unwrap()[0], expr);
+}
+
+#[test]
+fn errors() {
+    let errors = Rules::parse("left + - +\nup * /\nright\nleft % -").unwrap_err();
+    let found = errors.iter().map(|e| (e.line, e.column, e.kind.clone())).collect::<Vec<_>>();
+    assert_eq!(found, vec![
+        (1, 10, ErrorKind::Duplicate("operator")),
+        (2, 1, ErrorKind::Expected("'left' or 'right'")),
+        (3, 6, ErrorKind::Expected("an operator")),
+        (4, 6, ErrorKind::Expected("'+', '-', '*' or '/'")),
+        (4, 8, ErrorKind::Duplicate("operator")),
+    ]);
+
+    let errors = Rules::parse("left + -\nleft *").unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].kind, ErrorKind::Expected("a level for '/'"));
+}
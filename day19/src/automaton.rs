@@ -0,0 +1,359 @@
+//! Compiles rules which aren't recursive into minimized DFAs, which check a message in one pass
+//! without looking anything up in the grammar.
+//!
+//! Rules are compiled bottom-up: each rule's DFA is built out of the DFAs of the rules it refers to,
+//! by joining them together into an NFA, converting that back into a DFA with the subset construction,
+//! and minimizing the result, so no automaton ever gets much bigger than the language it recognizes.
+//! A rule which refers to itself, directly or through other rules, can't be compiled (its language
+//! may not be regular), and neither can any rule which uses one.
+
+use std::{collections::HashMap, fmt::{self, Write}};
+use crate::grammar::{Grammar, Rule};
+
+/// The longest regular expression [`Dfa::to_regex`] will write.
+pub const REGEX_LIMIT: usize = 10_000;
+
+/// A deterministic finite automaton. State 0 is the start.
+#[derive(Clone, Debug)]
+pub struct Dfa {
+    /// Which column of `transitions` each byte uses, if it appears in the grammar at all.
+    columns: Vec<Option<usize>>,
+
+    /// The byte each column stands for.
+    alphabet: Vec<u8>,
+
+    /// For each state, where each column leads. A missing transition means the message can't match.
+    transitions: Vec<Vec<Option<usize>>>,
+
+    accepting: Vec<bool>
+}
+
+impl Dfa {
+    /// The number of states.
+    pub fn len(&self) -> usize {
+        self.transitions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.transitions.is_empty()
+    }
+
+    fn step(&self, state: usize, c: u8) -> Option<usize> {
+        self.columns[c as usize].and_then(|column| self.transitions[state][column])
+    }
+
+    /// Whether the whole of `message` is accepted.
+    pub fn matches(&self, message: &str) -> bool {
+        message.bytes().try_fold(0, |state, c| self.step(state, c)).is_some_and(|state| self.accepting[state])
+    }
+
+    /// The length of every prefix of `message` which is accepted, shortest first.
+    pub fn ends<'a>(&'a self, message: &'a [u8]) -> impl Iterator<Item = usize> + 'a {
+        let states = std::iter::once(Some(0)).chain(message.iter().scan(0, move |state, &c| {
+            *state = self.step(*state, c)?;
+            Some(Some(*state))
+        }));
+        states.enumerate().filter_map(move |(len, state)| state.filter(|&state| self.accepting[state]).map(|_| len))
+    }
+
+    /// A regular expression (without anchors) which matches exactly what the DFA accepts. Only
+    /// DFAs which never loop back on themselves can be written out, which is every compiled rule.
+    ///
+    /// Every way into a state has to spell out the rest of the regex from there separately, so when
+    /// many paths meet up again the regex can be exponentially longer than the DFA. Returns None if
+    /// it would be longer than [`REGEX_LIMIT`].
+    pub fn to_regex(&self) -> Option<String> {
+        fn escape(regex: &mut String, c: u8) {
+            match c {
+                b'\\' | b'.' | b'+' | b'*' | b'?' | b'(' | b')' | b'|' | b'[' | b']' | b'{' | b'}'
+                    | b'^' | b'$' | b'#' | b'&' | b'-' | b'~' => { regex.push('\\'); regex.push(c as char) }
+                _ if c.is_ascii_graphic() || c == b' ' => regex.push(c as char),
+                _ => write!(regex, "\\x{:02x}", c).unwrap()
+            }
+        }
+
+        fn state(dfa: &Dfa, from: usize, memo: &mut HashMap<usize, String>) -> Option<String> {
+            if let Some(regex) = memo.get(&from) { return Some(regex.clone()); }
+
+            // Group together the characters which lead to the same place.
+            let mut targets = Vec::<(usize, Vec<u8>)>::new();
+            for (column, to) in dfa.transitions[from].iter().enumerate() {
+                let to = match to { Some(to) => *to, None => continue };
+                match targets.iter_mut().find(|(target, _)| *target == to) {
+                    Some((_, chars)) => chars.push(dfa.alphabet[column]),
+                    None => targets.push((to, vec![dfa.alphabet[column]]))
+                }
+            }
+
+            let options = targets.into_iter().map(|(to, chars)| {
+                let mut option = String::new();
+                if let [c] = chars[..] {
+                    escape(&mut option, c);
+                } else {
+                    // Write runs of three or more characters in a row as ranges.
+                    option.push('[');
+                    let mut index = 0;
+                    while index < chars.len() {
+                        let run = chars[index..].iter().zip(chars[index] as u32..)
+                            .take_while(|&(&c, expected)| c as u32 == expected).count();
+                        escape(&mut option, chars[index]);
+                        if run >= 3 {
+                            option.push('-');
+                            escape(&mut option, chars[index + run - 1]);
+                        } else if run == 2 {
+                            escape(&mut option, chars[index + 1]);
+                        }
+                        index += run;
+                    }
+                    option.push(']');
+                }
+                Some(option + &state(dfa, to, memo)?)
+            }).collect::<Option<Vec<_>>>()?;
+
+            let regex = match (&options[..], dfa.accepting[from]) {
+                ([], _) => String::new(),
+                ([option], false) => option.clone(),
+                (_, accepting) => format!("({}){}", options.join("|"), if accepting { "?" } else { "" })
+            };
+            if regex.len() > REGEX_LIMIT { return None; }
+            memo.insert(from, regex.clone());
+            Some(regex)
+        }
+
+        state(self, 0, &mut HashMap::new())
+    }
+}
+
+/// Lists each state's transitions, one state per line, for when the regex is too long to show.
+impl fmt::Display for Dfa {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (from, transitions) in self.transitions.iter().enumerate() {
+            write!(f, "{}{}:", from, if self.accepting[from] { " (accepting)" } else { "" })?;
+            let targets = transitions.iter().enumerate().filter_map(|(column, to)| to.map(|to| (self.alphabet[column], to)));
+            for (index, (c, to)) in targets.enumerate() {
+                write!(f, "{} {} -> {}", if index == 0 { "" } else { "," }, c.escape_ascii(), to)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// An NFA, used to put DFAs together. State 0 is the start, and state 1 the only accepting state.
+struct Nfa {
+    /// For each state, where each column leads.
+    transitions: Vec<Vec<Option<usize>>>,
+
+    /// For each state, the states it also counts as being in.
+    epsilon: Vec<Vec<usize>>
+}
+
+impl Nfa {
+    fn new(columns: usize) -> Self {
+        let mut nfa = Nfa { transitions: Vec::new(), epsilon: Vec::new() };
+        nfa.add_state(columns);
+        nfa.add_state(columns);
+        nfa
+    }
+
+    fn add_state(&mut self, columns: usize) -> usize {
+        self.transitions.push(vec![None; columns]);
+        self.epsilon.push(Vec::new());
+        self.transitions.len() - 1
+    }
+
+    /// Copies `dfa` in after `from`, returning a new state which its accepting states lead to.
+    fn append(&mut self, from: usize, dfa: &Dfa) -> usize {
+        let offset = self.transitions.len();
+        self.transitions.extend(dfa.transitions.iter()
+            .map(|row| row.iter().map(|to| to.map(|to| to + offset)).collect()));
+        self.epsilon.extend(dfa.transitions.iter().map(|_| Vec::new()));
+        self.epsilon[from].push(offset);
+
+        let end = self.add_state(dfa.alphabet.len());
+        for state in (0..dfa.len()).filter(|&state| dfa.accepting[state]) {
+            self.epsilon[offset + state].push(end);
+        }
+        end
+    }
+
+    /// Adds every state reachable from `states` without reading anything, and sorts them.
+    fn closure(&self, mut states: Vec<usize>) -> Vec<usize> {
+        let mut index = 0;
+        while let Some(&state) = states.get(index) {
+            index += 1;
+            for &next in &self.epsilon[state] {
+                if !states.contains(&next) { states.push(next); }
+            }
+        }
+        states.sort_unstable();
+        states.dedup();
+        states
+    }
+
+    /// Converts the NFA into a minimized DFA over `alphabet`.
+    fn determinize(&self, alphabet: &[u8], columns: &[Option<usize>]) -> Dfa {
+        // Each DFA state is a set of NFA states. The empty set is where the message stops matching,
+        // which is kept as an ordinary state so that minimizing can merge anything equivalent to it.
+        let mut sets = vec![self.closure(vec![0])];
+        let mut index = HashMap::new();
+        index.insert(sets[0].clone(), 0);
+        let mut transitions = Vec::<Vec<usize>>::new();
+        while let Some(set) = sets.get(transitions.len()) {
+            let row = (0..alphabet.len()).map(|column| {
+                self.closure(set.iter().filter_map(|&state| self.transitions[state][column]).collect())
+            }).collect::<Vec<_>>();
+            let row = row.into_iter().map(|next| *index.entry(next.clone()).or_insert_with(|| {
+                sets.push(next);
+                sets.len() - 1
+            })).collect();
+            transitions.push(row);
+        }
+        let accepting = sets.iter().map(|set| set.contains(&1)).collect::<Vec<_>>();
+
+        minimize(&transitions, &accepting, alphabet, columns)
+    }
+}
+
+/// Merges the states of a complete DFA which accept the same things, and removes the ones which
+/// can't accept anything at all.
+fn minimize(transitions: &[Vec<usize>], accepting: &[bool], alphabet: &[u8], columns: &[Option<usize>]) -> Dfa {
+    // Start out by splitting accepting states from the rest, then keep splitting up groups
+    // whose states lead to different groups until that changes nothing.
+    let mut groups = accepting.iter().map(|&accepting| accepting as usize).collect::<Vec<_>>();
+    let mut count = 0;
+    loop {
+        let mut signatures = HashMap::new();
+        let next = (0..transitions.len()).map(|state| {
+            let signature = (groups[state], transitions[state].iter().map(|&to| groups[to]).collect::<Vec<_>>());
+            let len = signatures.len();
+            *signatures.entry(signature).or_insert(len)
+        }).collect::<Vec<_>>();
+        groups = next;
+        if signatures.len() == count { break; }
+        count = signatures.len();
+    }
+
+    // A group is live if it can reach an accepting state.
+    let mut live = vec![false; count];
+    for (state, &group) in groups.iter().enumerate() {
+        live[group] |= accepting[state];
+    }
+    loop {
+        let found = (0..transitions.len())
+            .filter(|&state| !live[groups[state]] && transitions[state].iter().any(|&to| live[groups[to]]))
+            .map(|state| groups[state])
+            .collect::<Vec<_>>();
+        if found.is_empty() { break; }
+        found.into_iter().for_each(|group| live[group] = true);
+    }
+
+    // Number the live groups in the order they're found from the start.
+    let mut representatives = vec![None; count];
+    for (state, &group) in groups.iter().enumerate() {
+        representatives[group].get_or_insert(state);
+    }
+    let representative = |group: usize| representatives[group].unwrap();
+    let mut numbers = HashMap::new();
+    let mut order = Vec::new();
+    if live[groups[0]] {
+        numbers.insert(groups[0], 0);
+        order.push(groups[0]);
+    }
+    let mut index = 0;
+    while let Some(&group) = order.get(index) {
+        index += 1;
+        for &to in &transitions[representative(group)] {
+            if live[groups[to]] && !numbers.contains_key(&groups[to]) {
+                numbers.insert(groups[to], order.len());
+                order.push(groups[to]);
+            }
+        }
+    }
+
+    Dfa {
+        columns: columns.to_vec(),
+        alphabet: alphabet.to_vec(),
+        transitions: order.iter().map(|&group| transitions[representative(group)].iter()
+            .map(|&to| numbers.get(&groups[to]).copied())
+            .collect()).collect(),
+        accepting: order.iter().map(|&group| accepting[representative(group)]).collect()
+    }
+}
+
+/// The state of a rule while compiling.
+enum Compiled {
+    /// We're compiling the rules it uses, so seeing it again means it's recursive.
+    InProgress,
+    Done(Option<Dfa>)
+}
+
+struct Compiler<'a> {
+    grammar: &'a Grammar,
+    alphabet: Vec<u8>,
+    columns: Vec<Option<usize>>,
+    rules: HashMap<usize, Compiled>
+}
+
+impl Compiler<'_> {
+    fn compile(&mut self, rule: usize) -> Option<&Dfa> {
+        match self.rules.get(&rule) {
+            Some(Compiled::InProgress) => return None,
+            Some(Compiled::Done(_)) => (),
+            None => {
+                self.rules.insert(rule, Compiled::InProgress);
+                let dfa = self.build(rule);
+                self.rules.insert(rule, Compiled::Done(dfa));
+            }
+        }
+        match &self.rules[&rule] {
+            Compiled::Done(dfa) => dfa.as_ref(),
+            Compiled::InProgress => unreachable!()
+        }
+    }
+
+    fn build(&mut self, rule: usize) -> Option<Dfa> {
+        let columns = self.alphabet.len();
+        let mut nfa = Nfa::new(columns);
+        match &self.grammar.rules[&rule] {
+            Rule::Verbatim(c) => {
+                nfa.transitions[0][self.columns[*c as usize].unwrap()] = Some(1);
+            }
+            Rule::Alternatives(alternatives) => for sequence in alternatives {
+                let mut end = 0;
+                for &part in sequence {
+                    let dfa = self.compile(part)?.clone();
+                    end = nfa.append(end, &dfa);
+                }
+                nfa.epsilon[end].push(1);
+            }
+        }
+        Some(nfa.determinize(&self.alphabet, &self.columns))
+    }
+}
+
+/// Compiles every rule which can be compiled, returning a DFA for each by number.
+pub fn compile(grammar: &Grammar) -> HashMap<usize, Dfa> {
+    let mut alphabet = grammar.rules.values()
+        .filter_map(|rule| match rule { Rule::Verbatim(c) => Some(*c), _ => None })
+        .collect::<Vec<_>>();
+    alphabet.sort_unstable();
+    alphabet.dedup();
+    let mut columns = vec![None; 256];
+    for (column, &c) in alphabet.iter().enumerate() {
+        columns[c as usize] = Some(column);
+    }
+
+    let mut compiler = Compiler { grammar, alphabet, columns, rules: HashMap::new() };
+    let mut numbers = grammar.rules.keys().copied().collect::<Vec<_>>();
+    numbers.sort_unstable();
+    for number in numbers {
+        compiler.compile(number);
+    }
+    compiler.rules.into_iter()
+        .filter_map(|(number, compiled)| match compiled {
+            Compiled::Done(Some(dfa)) => Some((number, dfa)),
+            _ => None
+        })
+        .collect()
+}
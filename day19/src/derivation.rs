@@ -0,0 +1,267 @@
+//! Explains how a message matched: the derivation trees the parser found for it, and how many
+//! distinct ones there are.
+//!
+//! Both are read back out of the parser's sets. A completed alternative of rule `r` which started at
+//! `i` in the set after character `j` means `r` matches the message from `i` to `j`, so working down
+//! from the start rule only ever splits the message at places where every part is known to match.
+//! Rules the parser compiled are treated like literal characters, since there's nothing in the sets
+//! to say how they matched.
+
+use std::{collections::{HashMap, HashSet}, fmt, ops::{Add, Mul, Range}};
+use crate::{earley::{Parse, START}, grammar::{Grammar, Rule}};
+
+/// How a rule matched part of a message.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Tree {
+    pub rule: usize,
+
+    /// Where in the message the rule matched.
+    pub span: Range<usize>,
+
+    /// How each rule of the alternative that matched did so, in order. Empty for a rule which
+    /// matches a literal character, or an alternative which matches nothing.
+    pub children: Vec<Tree>
+}
+
+impl Tree {
+    /// Shows the tree with each rule on its own line, indented under the rule it's part of,
+    /// along with the part of `message` it matched.
+    pub fn outline<'a>(&'a self, message: &'a str) -> Outline<'a> {
+        Outline { tree: self, message, grammar: None }
+    }
+}
+
+/// A tree, shown with [`Tree::outline`].
+pub struct Outline<'a> {
+    tree: &'a Tree,
+    message: &'a str,
+
+    /// Where to find the names of rules, if they should be shown.
+    grammar: Option<&'a Grammar>
+}
+
+impl<'a> Outline<'a> {
+    /// Shows rules which have a name by their name, instead of their number.
+    pub fn labelled(self, grammar: &'a Grammar) -> Self {
+        Outline { grammar: Some(grammar), ..self }
+    }
+}
+
+impl fmt::Display for Outline<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn write(f: &mut fmt::Formatter<'_>, outline: &Outline<'_>, tree: &Tree, depth: usize) -> fmt::Result {
+            let text = &outline.message[tree.span.clone()];
+            let label = outline.grammar.map_or_else(|| tree.rule.to_string(), |grammar| grammar.label(tree.rule));
+            writeln!(f, "{:indent$}{}: {}", "", label, if text.is_empty() { "(nothing)" } else { text },
+                indent = depth * 2)?;
+            tree.children.iter().try_for_each(|child| write(f, outline, child, depth + 1))
+        }
+        write(f, self, self.tree, 0)
+    }
+}
+
+/// How many distinct derivation trees a message has.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Count {
+    Finite(u64),
+
+    /// More than fit in a `u64`.
+    Overflow,
+
+    /// Rules which match nothing, or which match each other, can be repeated as many times as
+    /// we like without changing what's matched.
+    Infinite
+}
+
+impl Add for Count {
+    type Output = Count;
+    fn add(self, rhs: Count) -> Count {
+        match (self, rhs) {
+            (Count::Infinite, _) | (_, Count::Infinite) => Count::Infinite,
+            (Count::Finite(a), Count::Finite(b)) => a.checked_add(b).map_or(Count::Overflow, Count::Finite),
+            _ => Count::Overflow
+        }
+    }
+}
+
+impl Mul for Count {
+    type Output = Count;
+    fn mul(self, rhs: Count) -> Count {
+        match (self, rhs) {
+            (Count::Finite(0), _) | (_, Count::Finite(0)) => Count::Finite(0),
+            (Count::Infinite, _) | (_, Count::Infinite) => Count::Infinite,
+            (Count::Finite(a), Count::Finite(b)) => a.checked_mul(b).map_or(Count::Overflow, Count::Finite),
+            _ => Count::Overflow
+        }
+    }
+}
+
+impl fmt::Display for Count {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Count::Finite(count) => write!(f, "{}", count),
+            Count::Overflow => write!(f, "more than {}", u64::MAX),
+            Count::Infinite => write!(f, "infinitely many")
+        }
+    }
+}
+
+/// A part of the message a rule matched: (rule, start, end).
+type Span = (usize, usize, usize);
+
+/// The state shared while working down from the start rule.
+struct Derivations<'a> {
+    grammar: &'a Grammar,
+    message: &'a [u8],
+
+    /// Every part of the message each rule matched.
+    matched: HashSet<Span>,
+
+    /// The parts of the message that compiled rules matched.
+    compiled: &'a HashSet<Span>,
+
+    /// The rules we're in the middle of looking at, to catch a rule being used to match the
+    /// same part of the message inside itself.
+    active: HashSet<Span>,
+
+    /// How many ways each rule matched each part of the message.
+    counts: HashMap<Span, Count>,
+
+    /// How many ways the rest of an alternative (rule, alternative, dot) matched each part of the message.
+    sequences: HashMap<(usize, usize, usize, usize, usize), Count>
+}
+
+impl<'a> Derivations<'a> {
+    fn new(parse: &'a Parse) -> Self {
+        let matched = parse.sets.iter().enumerate()
+            .flat_map(|(end, set)| set.iter()
+                .filter(|item| item.rule != START && item.dot == parse.sequence(item).len())
+                .map(move |item| (item.rule, item.origin, end)))
+            .chain(parse.compiled.iter().copied())
+            .collect();
+        Derivations {
+            grammar: parse.grammar,
+            message: parse.message.as_bytes(),
+            matched,
+            compiled: &parse.compiled,
+            active: HashSet::new(),
+            counts: HashMap::new(),
+            sequences: HashMap::new()
+        }
+    }
+
+    fn alternatives(&self, rule: usize) -> &'a [Vec<usize>] {
+        match &self.grammar.rules[&rule] {
+            Rule::Alternatives(alternatives) => alternatives,
+            Rule::Verbatim(_) => &[]
+        }
+    }
+
+    /// The places the message could be split so that `rule` matches from `start` to the split.
+    /// Literal characters aren't in the parser's sets, so they're checked against the message.
+    fn splits(&self, rule: usize, start: usize, end: usize) -> Vec<usize> {
+        match self.grammar.rules[&rule] {
+            Rule::Verbatim(c) => if start < end && self.message[start] == c { vec![start + 1] } else { vec![] },
+            Rule::Alternatives(_) => (start..=end).filter(|&middle| self.matched.contains(&(rule, start, middle))).collect()
+        }
+    }
+
+    /// How many ways `rule` matches the message from `start` to `end`.
+    fn count(&mut self, rule: usize, start: usize, end: usize) -> Count {
+        let span = (rule, start, end);
+        if matches!(self.grammar.rules[&rule], Rule::Verbatim(_)) || self.compiled.contains(&span) {
+            return Count::Finite(1);
+        }
+        if let Some(&count) = self.counts.get(&span) { return count; }
+        // Every part of a derivation we follow matches something, so if we've come back around
+        // to the same rule matching the same thing, the loop can be repeated forever.
+        if !self.active.insert(span) { return Count::Infinite; }
+
+        let total = (0..self.alternatives(rule).len())
+            .fold(Count::Finite(0), |total, alternative| total + self.count_sequence(rule, alternative, 0, start, end));
+
+        self.active.remove(&span);
+        self.counts.insert(span, total);
+        total
+    }
+
+    /// How many ways the rules of an alternative from `dot` onwards match the message from `start` to `end`.
+    fn count_sequence(&mut self, rule: usize, alternative: usize, dot: usize, start: usize, end: usize) -> Count {
+        let first = match self.alternatives(rule)[alternative].get(dot) {
+            Some(&first) => first,
+            None => return Count::Finite((start == end).into())
+        };
+        let key = (rule, alternative, dot, start, end);
+        if let Some(&count) = self.sequences.get(&key) { return count; }
+
+        let mut total = Count::Finite(0);
+        for middle in self.splits(first, start, end) {
+            // Only look inside the first rule if the rest can match, so we don't report a loop
+            // which can't be part of a match.
+            let rest = self.count_sequence(rule, alternative, dot + 1, middle, end);
+            if rest != Count::Finite(0) {
+                total = total + self.count(first, start, middle) * rest;
+            }
+        }
+        self.sequences.insert(key, total);
+        total
+    }
+
+    /// Up to `limit` ways `rule` matches the message from `start` to `end`, leaving out any which
+    /// use a rule to match the same part of the message inside itself.
+    fn trees(&mut self, rule: usize, start: usize, end: usize, limit: usize) -> Vec<Tree> {
+        if matches!(self.grammar.rules[&rule], Rule::Verbatim(_)) || self.compiled.contains(&(rule, start, end)) {
+            return vec![Tree { rule, span: start..end, children: Vec::new() }];
+        }
+        if !self.active.insert((rule, start, end)) { return Vec::new(); }
+
+        let mut trees = Vec::new();
+        for sequence in self.alternatives(rule) {
+            let remaining = limit - trees.len();
+            trees.extend(self.sequence_trees(sequence, start, end, remaining).into_iter()
+                .map(|children| Tree { rule, span: start..end, children }));
+            if trees.len() == limit { break; }
+        }
+
+        self.active.remove(&(rule, start, end));
+        trees
+    }
+
+    /// Up to `limit` ways the rules in `sequence` match the message from `start` to `end`.
+    fn sequence_trees(&mut self, sequence: &[usize], start: usize, end: usize, limit: usize) -> Vec<Vec<Tree>> {
+        let (&first, rest) = match sequence.split_first() {
+            Some(split) => split,
+            None => return if start == end { vec![Vec::new()] } else { Vec::new() }
+        };
+
+        let mut sequences = Vec::new();
+        for middle in self.splits(first, start, end) {
+            let rests = self.sequence_trees(rest, middle, end, limit - sequences.len());
+            if rests.is_empty() { continue; }
+            for tree in self.trees(first, start, middle, limit - sequences.len()) {
+                for rest in &rests {
+                    if sequences.len() == limit { return sequences; }
+                    sequences.push(std::iter::once(tree.clone()).chain(rest.iter().cloned()).collect());
+                }
+            }
+            if sequences.len() == limit { break; }
+        }
+        sequences
+    }
+}
+
+impl Parse<'_, '_> {
+    /// How many distinct derivation trees the message has. Zero if it didn't match.
+    pub fn count(&self) -> Count {
+        if !self.matched() { return Count::Finite(0); }
+        Derivations::new(self).count(self.start, 0, self.message.len())
+    }
+
+    /// Up to `limit` of the message's derivation trees. If rules which match nothing, or which match
+    /// each other, give it infinitely many, only the ones without a rule matching the same part of
+    /// the message inside itself are listed.
+    pub fn trees(&self, limit: usize) -> Vec<Tree> {
+        if !self.matched() || limit == 0 { return Vec::new(); }
+        Derivations::new(self).trees(self.start, 0, self.message.len(), limit)
+    }
+}
@@ -0,0 +1,197 @@
+//! An Earley parser, which checks messages against any set of rules in polynomial time: rules can
+//! be ambiguous, recursive in any way (including on the left), and match nothing at all.
+//!
+//! The parser reads a message one character at a time, keeping a set of every partially-matched
+//! alternative which could still be part of a match (along with where it started) after each
+//! character. Alternatives which can match nothing are skipped over as soon as they're predicted,
+//! as described by Aycock and Horspool in "Practical Earley Parsing".
+//!
+//! A parser can also be built with the rules which aren't recursive compiled into DFAs (see the
+//! `automaton` module), which it steps over in one go like characters. Checking a message against a
+//! rule which was compiled doesn't need the sets at all.
+//!
+//! The sets are kept around after parsing, so that they can be used to explain the result:
+//! see the `derivation` module for how a message matched, and [`Parse::expected`] for why it didn't.
+
+use std::collections::{HashMap, HashSet};
+use crate::{automaton::{self, Dfa}, grammar::{Grammar, Rule}};
+
+/// Stands in for the number of a rule which matches just the rule being checked, so that
+/// checking a message always starts from an ordinary sequence of rules.
+pub(crate) const START: usize = usize::MAX;
+
+/// A partially-matched alternative of a rule.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct Item {
+    pub rule: usize,
+    pub alternative: usize,
+
+    /// How many rules in the alternative have been matched so far.
+    pub dot: usize,
+
+    /// Where in the message the alternative started matching.
+    pub origin: usize
+}
+
+impl Item {
+    fn advance(self) -> Item {
+        Item { dot: self.dot + 1, ..self }
+    }
+}
+
+/// Checks messages against a grammar.
+pub struct Parser<'a> {
+    pub(crate) grammar: &'a Grammar,
+
+    /// The rules which can match nothing at all.
+    nullable: HashSet<usize>,
+
+    /// The rules which have been compiled, other than ones which match a literal character.
+    automata: HashMap<usize, Dfa>
+}
+
+impl<'a> Parser<'a> {
+    pub fn new(grammar: &'a Grammar) -> Self {
+        Parser { grammar, nullable: grammar.nullable(), automata: HashMap::new() }
+    }
+
+    /// Creates a parser which uses DFAs for every rule that isn't recursive. It's much faster, but
+    /// compiled rules show up in derivation trees without anything under them, and are counted as
+    /// matching in only one way.
+    pub fn compiled(grammar: &'a Grammar) -> Self {
+        let mut automata = automaton::compile(grammar);
+        automata.retain(|number, _| matches!(grammar.rules[number], Rule::Alternatives(_)));
+        Parser { automata, ..Parser::new(grammar) }
+    }
+
+    /// The DFA for a rule, if it was compiled.
+    pub fn automaton(&self, rule: usize) -> Option<&Dfa> {
+        self.automata.get(&rule)
+    }
+
+    /// The rules an item's alternative consists of.
+    fn sequence<'s>(&self, item: &Item, start: &'s usize) -> &'s [usize] where 'a: 's {
+        sequence(self.grammar, item, start)
+    }
+
+    /// Whether all of `message` matches rule `start`.
+    pub fn matches(&self, start: usize, message: &str) -> bool {
+        match self.automata.get(&start) {
+            Some(dfa) => dfa.matches(message),
+            None => self.parse(start, message).matched()
+        }
+    }
+
+    /// Parses `message` as rule `start`.
+    pub fn parse<'m>(&self, start: usize, message: &'m str) -> Parse<'a, 'm> {
+        let initial = Item { rule: START, alternative: 0, dot: 0, origin: 0 };
+        let text = message;
+        let message = message.as_bytes();
+
+        // The items which could still be part of a match after reading each character.
+        let mut sets = vec![Vec::new(); message.len() + 1];
+        let mut seen = vec![HashSet::new(); message.len() + 1];
+        let mut compiled = HashSet::new();
+        sets[0].push(initial);
+        seen[0].insert(initial);
+
+        for position in 0..=message.len() {
+            let mut index = 0;
+            while let Some(&item) = sets[position].get(index) {
+                index += 1;
+                let mut add = |sets: &mut Vec<Vec<Item>>, at: usize, item: Item| {
+                    if seen[at].insert(item) { sets[at].push(item); }
+                };
+
+                match self.sequence(&item, &start).get(item.dot) {
+                    // The alternative is complete, so everything that was waiting for it can move on.
+                    None => {
+                        let waiting = sets[item.origin].iter()
+                            .filter(|parent| self.sequence(parent, &start).get(parent.dot) == Some(&item.rule))
+                            .map(|parent| parent.advance())
+                            .collect::<Vec<_>>();
+                        waiting.into_iter().for_each(|parent| add(&mut sets, position, parent));
+                    }
+                    Some(next) if self.automata.contains_key(next) => {
+                        for len in self.automata[next].ends(&message[position..]) {
+                            compiled.insert((*next, position, position + len));
+                            add(&mut sets, position + len, item.advance());
+                        }
+                    }
+                    Some(next) => match &self.grammar.rules[next] {
+                        Rule::Verbatim(c) => if message.get(position) == Some(c) {
+                            add(&mut sets, position + 1, item.advance());
+                        },
+                        Rule::Alternatives(alternatives) => {
+                            for alternative in 0..alternatives.len() {
+                                add(&mut sets, position, Item { rule: *next, alternative, dot: 0, origin: position });
+                            }
+                            if self.nullable.contains(next) {
+                                add(&mut sets, position, item.advance());
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        Parse { grammar: self.grammar, start, message: text, sets, compiled }
+    }
+}
+
+/// The rules an item's alternative consists of, where `start` is the rule being checked.
+fn sequence<'s>(grammar: &'s Grammar, item: &Item, start: &'s usize) -> &'s [usize] {
+    match grammar.rules.get(&item.rule) {
+        Some(Rule::Alternatives(alternatives)) => &alternatives[item.alternative],
+        _ => std::slice::from_ref(start)
+    }
+}
+
+/// The result of parsing a message, which remembers every way it could have been parsed.
+pub struct Parse<'g, 'm> {
+    pub(crate) grammar: &'g Grammar,
+    pub(crate) start: usize,
+    pub(crate) message: &'m str,
+
+    /// The items which could still be part of a match after reading each character.
+    pub(crate) sets: Vec<Vec<Item>>,
+
+    /// Each part of the message a compiled rule matched, as (rule, start, end).
+    pub(crate) compiled: HashSet<(usize, usize, usize)>
+}
+
+impl Parse<'_, '_> {
+    /// The rules an item's alternative consists of.
+    pub(crate) fn sequence(&self, item: &Item) -> &[usize] {
+        sequence(self.grammar, item, &self.start)
+    }
+
+    /// Whether all of the message matched.
+    pub fn matched(&self) -> bool {
+        self.prefix_matched(self.message.len())
+    }
+
+    /// Whether the first `len` characters of the message would have matched on their own.
+    pub fn prefix_matched(&self, len: usize) -> bool {
+        let end = Item { rule: START, alternative: 0, dot: 1, origin: 0 };
+        self.sets[len].contains(&end)
+    }
+
+    /// The length of the longest prefix of the message which is the start of something that matches.
+    /// If the message doesn't match, the problem is just after that.
+    pub fn longest_prefix(&self) -> usize {
+        self.sets.iter().rposition(|set| !set.is_empty()).expect("the first set always has the start item")
+    }
+
+    /// The rules which could have come next after the longest prefix, in order. If the prefix matches
+    /// on its own, the end of the message could have come next too, which isn't included here.
+    pub fn expected(&self) -> Vec<usize> {
+        let mut expected = self.sets[self.longest_prefix()].iter()
+            .filter(|item| item.rule != START)
+            .filter_map(|item| self.sequence(item).get(item.dot).copied())
+            .collect::<Vec<_>>();
+        expected.sort_unstable();
+        expected.dedup();
+        expected
+    }
+}
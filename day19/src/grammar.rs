@@ -0,0 +1,274 @@
+//! The rules messages are checked against, shared by both parts.
+//!
+//! Each rule is written `<rule>: <alternatives>`, where a rule is a number or a name made of letters,
+//! digits and `_`, and the alternatives are sequences separated by `|`. A sequence is made of
+//!
+//! - other rules, by number or name;
+//! - strings in quotes, like `"ab"`, where `\` makes the character after it stand for itself;
+//! - character classes in square brackets, like `[a-z_]`;
+//! - alternatives in parentheses, like `(a | b c)`;
+//!
+//! and any of those can be followed by `?` (once or not at all), `*` (any number of times) or `+`
+//! (at least once). The puzzle's rules only use numbers and single characters in quotes.
+//!
+//! Everything is turned into numbered rules of the two kinds below, so the rest of the crate only
+//! deals with those. Named rules are numbered after the highest number in the input, followed by the
+//! extra rules needed for strings, classes, parentheses and repetition, which are named after the
+//! text they came from. Repetition is written out with a rule that refers to itself, so it can't be
+//! compiled into a DFA.
+
+use std::collections::{HashMap, HashSet};
+use common::{Error, ErrorKind, ParseError};
+use crate::syntax::{self, Expr, Node, Repeat};
+
+pub enum Rule {
+    /// This rule matches a literal character.
+    Verbatim(u8),
+
+    /// This rule matches any one of several sequences of rules. An empty sequence matches nothing at all.
+    Alternatives(Vec<Vec<usize>>)
+}
+
+/// A set of rules, indexed by number.
+pub struct Grammar {
+    pub rules: HashMap<usize, Rule>,
+
+    /// The names of the rules which have one.
+    pub names: HashMap<usize, String>,
+
+    /// The extra rules made for strings, classes, parentheses and repetition, which aren't
+    /// written out in the input on their own.
+    pub extra: HashSet<usize>
+}
+
+/// Turns rules as they're written into numbered rules.
+struct Builder<'a> {
+    input: &'a str,
+
+    /// The number each named rule was given.
+    numbers: HashMap<&'a str, usize>,
+
+    /// Every rule which is defined in the input.
+    defined: HashSet<usize>,
+
+    /// Whether to report references to rules which aren't defined.
+    check_references: bool,
+
+    /// Whether to leave references to rules which aren't defined in place, instead of reporting them.
+    allow_undefined: bool,
+
+    rules: HashMap<usize, Rule>,
+    names: HashMap<usize, String>,
+
+    /// The extra rules made so far, by the text they were made from.
+    extra: HashMap<String, usize>,
+
+    /// The number the next extra rule will get.
+    next: usize,
+
+    errors: Vec<ParseError>
+}
+
+impl<'a> Builder<'a> {
+    /// The number of the rule a reference refers to, if it's defined, or if undefined rules are allowed.
+    fn reference(&mut self, name: &'a str) -> Option<usize> {
+        let number = name.parse().ok().or_else(|| self.numbers.get(name).copied());
+        if number.is_some_and(|number| self.defined.contains(&number)) { return number; }
+        if self.allow_undefined {
+            // Give an undefined name a number of its own, so it can be reported by name.
+            return Some(number.unwrap_or_else(|| {
+                let number = self.next;
+                self.next += 1;
+                self.numbers.insert(name, number);
+                self.names.insert(number, name.to_string());
+                number
+            }));
+        }
+        if self.check_references {
+            self.errors.push(ParseError::at(self.input, name, ErrorKind::Unknown("rule")));
+        }
+        None
+    }
+
+    /// The number of an extra rule made from `text`, building it with `build` if it hasn't been already.
+    fn extra(&mut self, text: &str, build: impl FnOnce(&mut Self, usize) -> Rule) -> usize {
+        if let Some(&number) = self.extra.get(text) { return number; }
+        let number = self.next;
+        self.next += 1;
+        self.extra.insert(text.to_string(), number);
+        self.names.insert(number, text.to_string());
+        let rule = build(self, number);
+        self.rules.insert(number, rule);
+        number
+    }
+
+    /// The number of a rule matching just `c`.
+    fn literal(&mut self, c: u8) -> usize {
+        self.extra(&format!("\"{}\"", c.escape_ascii()), |_, _| Rule::Verbatim(c))
+    }
+
+    fn alternatives(&mut self, alternatives: &[Vec<Node<'a>>]) -> Vec<Vec<usize>> {
+        alternatives.iter().map(|sequence| self.sequence(sequence)).collect()
+    }
+
+    fn sequence(&mut self, sequence: &[Node<'a>]) -> Vec<usize> {
+        sequence.iter().flat_map(|node| self.node(node)).collect()
+    }
+
+    /// The rules a node stands for, in order.
+    fn node(&mut self, node: &Node<'a>) -> Vec<usize> {
+        match &node.expr {
+            Expr::Reference(name) => self.reference(name).into_iter().collect(),
+            Expr::Literal(string) => string.iter().map(|&c| self.literal(c)).collect(),
+            Expr::Class(class) => match class[..] {
+                [c] => vec![self.literal(c)],
+                _ => vec![self.extra(node.text, |builder, _|
+                    Rule::Alternatives(class.iter().map(|&c| vec![builder.literal(c)]).collect()))]
+            },
+            Expr::Alternatives(alternatives) => match &alternatives[..] {
+                [sequence] => self.sequence(sequence),
+                _ => vec![self.extra(node.text, |builder, _| Rule::Alternatives(builder.alternatives(alternatives)))]
+            },
+            Expr::Repeat(repeated, repeat) => vec![self.extra(node.text, |builder, number| {
+                let once = builder.node(repeated);
+                let more = once.iter().copied().chain(std::iter::once(number)).collect();
+                Rule::Alternatives(match repeat {
+                    Repeat::Optional => vec![once, vec![]],
+                    Repeat::Any => vec![more, vec![]],
+                    Repeat::AtLeastOnce => vec![more, once]
+                })
+            })]
+        }
+    }
+}
+
+/// Calls `f` with every rule `node` refers to, including inside parentheses and repetition.
+fn for_each_reference<'a>(node: &Node<'a>, f: &mut impl FnMut(&'a str)) {
+    match &node.expr {
+        Expr::Reference(name) => f(name),
+        Expr::Alternatives(alternatives) => alternatives.iter().flatten().for_each(|node| for_each_reference(node, f)),
+        Expr::Repeat(node, _) => for_each_reference(node, f),
+        Expr::Literal(_) | Expr::Class(_) => ()
+    }
+}
+
+impl Grammar {
+    /// Parses the rules at the start of `input`, and the messages after them, checking that
+    /// every rule in `required` is defined.
+    pub fn parse(input: &str, required: &[usize]) -> Result<(Grammar, Vec<String>), Error> {
+        Grammar::build(input, required, false)
+    }
+
+    /// Parses like [`Grammar::parse`], but lets rules refer to rules which aren't defined, so that
+    /// [`lint`](crate::lint::lint) can report them along with everything else. The grammar is only
+    /// good for linting, since the parser expects every rule to be defined.
+    pub fn parse_lenient(input: &str, required: &[usize]) -> Result<(Grammar, Vec<String>), Error> {
+        Grammar::build(input, required, true)
+    }
+
+    fn build(input: &str, required: &[usize], allow_undefined: bool) -> Result<(Grammar, Vec<String>), Error> {
+        let mut lines = input.lines();
+
+        let mut definitions = Vec::new();
+        let mut errors = Vec::new();
+        for line in lines.by_ref().take_while(|line| !line.is_empty()) {
+            match syntax::parse_definition(input, line) {
+                Ok(definition) => definitions.push((line, definition)),
+                Err(e) => errors.push(e)
+            }
+        }
+        // A rule which failed to parse would show up as unknown everywhere it's referenced.
+        let check_references = errors.is_empty();
+
+        // Named rules are numbered after the highest number anywhere in the input, so they
+        // can't be mistaken for an undefined numbered rule.
+        let mut written = Vec::new();
+        for (_, definition) in &definitions {
+            written.push(definition.name);
+            definition.alternatives.iter().flatten().for_each(|node| for_each_reference(node, &mut |name| written.push(name)));
+        }
+        let mut next = written.iter().filter_map(|name| name.parse::<usize>().ok()).max().map_or(0, |max| max + 1);
+        let mut numbers = HashMap::new();
+        let mut names = HashMap::new();
+        let mut defined = HashSet::new();
+        let definitions = definitions.into_iter().map(|(line, definition)| {
+            let number = definition.name.parse().unwrap_or_else(|_| *numbers.entry(definition.name).or_insert_with(|| {
+                names.insert(next, definition.name.to_string());
+                next += 1;
+                next - 1
+            }));
+            if !defined.insert(number) {
+                errors.push(ParseError::at(input, line, ErrorKind::Duplicate("rule")));
+            }
+            (number, definition)
+        }).collect::<Vec<_>>();
+
+        let mut builder = Builder {
+            input, numbers, defined, check_references, allow_undefined, names, next, errors,
+            rules: HashMap::new(),
+            extra: HashMap::new()
+        };
+        for (number, definition) in definitions {
+            let rule = match &definition.alternatives[..] {
+                [sequence] => match &sequence[..] {
+                    [Node { expr: Expr::Literal(string), .. }] if string.len() == 1 => Rule::Verbatim(string[0]),
+                    _ => Rule::Alternatives(vec![builder.sequence(sequence)])
+                },
+                alternatives => Rule::Alternatives(builder.alternatives(alternatives))
+            };
+            builder.rules.insert(number, rule);
+        }
+        if !builder.errors.is_empty() { return Err(builder.errors.into()); }
+        let Builder { rules, names, extra, .. } = builder;
+        let extra = extra.into_values().collect();
+
+        if let Some(missing) = required.iter().find(|index| !rules.contains_key(index)) {
+            return Err(Error::InvalidInput(format!("no rule {}", missing)));
+        }
+
+        let messages = lines.map(str::to_string).collect();
+
+        Ok((Grammar { rules, names, extra }, messages))
+    }
+
+    /// The rules which can match nothing at all.
+    pub fn nullable(&self) -> HashSet<usize> {
+        self.closure(false)
+    }
+
+    /// The rules which can match something, as opposed to needing to match themselves first.
+    pub fn productive(&self) -> HashSet<usize> {
+        self.closure(true)
+    }
+
+    /// The rules with an alternative made only of rules found so far, starting from the ones
+    /// which match a literal character if `literals` is set. Keeps looking until that finds no more.
+    fn closure(&self, literals: bool) -> HashSet<usize> {
+        let mut found = HashSet::new();
+        loop {
+            let more = self.rules.iter()
+                .filter(|(number, _)| !found.contains(*number))
+                .filter(|(_, rule)| match rule {
+                    Rule::Verbatim(_) => literals,
+                    Rule::Alternatives(alternatives) => alternatives.iter()
+                        .any(|sequence| sequence.iter().all(|number| found.contains(number)))
+                })
+                .map(|(&number, _)| number)
+                .collect::<Vec<_>>();
+            if more.is_empty() { return found; }
+            found.extend(more);
+        }
+    }
+
+    /// The name of a rule, or its number if it doesn't have one.
+    pub fn label(&self, rule: usize) -> String {
+        self.names.get(&rule).cloned().unwrap_or_else(|| rule.to_string())
+    }
+
+    /// The number of a rule, given its number or name.
+    pub fn number(&self, rule: &str) -> Option<usize> {
+        rule.parse().ok()
+            .or_else(|| self.names.iter().find(|(_, name)| *name == rule).map(|(&number, _)| number))
+            .filter(|number| self.rules.contains_key(number))
+    }
+}
@@ -0,0 +1,16 @@
+use common::Example;
+
+pub mod automaton;
+pub mod derivation;
+pub mod earley;
+pub mod grammar;
+pub mod lint;
+pub mod part1;
+pub mod part2;
+mod syntax;
+
+/// Example inputs with known answers.
+pub const EXAMPLES: &[Example] = &[
+    Example { input: include_str!("../test"), answers: [Some("2"), None] },
+    Example { input: include_str!("../test2"), answers: [Some("3"), Some("12")] },
+];
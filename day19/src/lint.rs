@@ -0,0 +1,203 @@
+//! Checks a grammar for mistakes before any messages are matched against it: rules which refer to
+//! rules that don't exist, rules which can never be used or never match anything, and rules which
+//! can refer back to themselves before matching anything.
+//!
+//! None of these stop the parser from working, but they're almost always a sign that the grammar
+//! doesn't say what was meant. Left recursion in particular is fine for the Earley parser, but
+//! would send a simpler recursive matcher around in circles forever.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use crate::grammar::{Grammar, Rule};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Problem {
+    /// `rule` refers to `reference`, which isn't defined. Only a grammar read with
+    /// [`Grammar::parse_lenient`] can have these, since [`Grammar::parse`] rejects them.
+    Undefined { rule: usize, reference: usize },
+
+    /// The rule can't be reached from the start rule, so it's never used.
+    Unreachable(usize),
+
+    /// The rule can never match anything, because every alternative needs it (or another such rule)
+    /// to match first. Rules which need an undefined rule aren't checked for this.
+    Unproductive(usize),
+
+    /// Each of these rules can start with the next one, and the last with the first.
+    LeftRecursion(Vec<usize>)
+}
+
+impl Problem {
+    /// Describes the problem, calling rules by their names where they have one.
+    pub fn describe(&self, grammar: &Grammar) -> String {
+        match self {
+            Problem::Undefined { rule, reference } =>
+                format!("rule {} refers to rule {}, which isn't defined", grammar.label(*rule), grammar.label(*reference)),
+            Problem::Unreachable(rule) => format!("rule {} is never used", grammar.label(*rule)),
+            Problem::Unproductive(rule) => format!("rule {} can never match anything", grammar.label(*rule)),
+            Problem::LeftRecursion(cycle) => {
+                let rules = cycle.iter().chain(cycle.first()).map(|&rule| grammar.label(rule)).collect::<Vec<_>>();
+                format!("rules are left-recursive: {}", rules.join(" -> "))
+            }
+        }
+    }
+}
+
+/// The rules each rule refers to, in order, leaving out any which aren't defined.
+fn references(grammar: &Grammar, rule: usize) -> impl Iterator<Item = usize> + '_ {
+    let sequences = match grammar.rules.get(&rule) {
+        Some(Rule::Alternatives(alternatives)) => &alternatives[..],
+        _ => &[]
+    };
+    sequences.iter().flatten().copied().filter(move |reference| grammar.rules.contains_key(reference))
+}
+
+/// Every problem with `grammar`, checked from rule `start`. Problems of each kind are listed
+/// together, in order of rule number. The extra rules made for strings, classes, parentheses
+/// and repetition are only mentioned if they're part of left recursion, since any other problem
+/// with one is also a problem with the rule it was made for.
+pub fn lint(grammar: &Grammar, start: usize) -> Vec<Problem> {
+    let mut numbers = grammar.rules.keys().copied().collect::<Vec<_>>();
+    numbers.sort_unstable();
+    let written = numbers.iter().copied().filter(|number| !grammar.extra.contains(number)).collect::<Vec<_>>();
+    let mut problems = Vec::new();
+
+    for &rule in &numbers {
+        if let Some(Rule::Alternatives(alternatives)) = grammar.rules.get(&rule) {
+            let mut undefined = alternatives.iter().flatten()
+                .filter(|reference| !grammar.rules.contains_key(reference))
+                .collect::<Vec<_>>();
+            undefined.sort_unstable();
+            undefined.dedup();
+            problems.extend(undefined.into_iter().map(|&reference| Problem::Undefined { rule, reference }));
+        }
+    }
+
+    let mut reachable = HashSet::new();
+    let mut stack = vec![start];
+    while let Some(rule) = stack.pop() {
+        if grammar.rules.contains_key(&rule) && reachable.insert(rule) {
+            stack.extend(references(grammar, rule));
+        }
+    }
+    problems.extend(written.iter().filter(|rule| !reachable.contains(rule)).map(|&rule| Problem::Unreachable(rule)));
+
+    // Whether a rule can match anything isn't known if it needs an undefined rule, directly or
+    // through others, and the undefined rule has already been reported.
+    let mut incomplete = problems.iter()
+        .filter_map(|problem| match problem { Problem::Undefined { rule, .. } => Some(*rule), _ => None })
+        .collect::<HashSet<_>>();
+    loop {
+        let more = numbers.iter().copied()
+            .filter(|rule| !incomplete.contains(rule))
+            .filter(|&rule| references(grammar, rule).any(|reference| incomplete.contains(&reference)))
+            .collect::<Vec<_>>();
+        if more.is_empty() { break; }
+        incomplete.extend(more);
+    }
+
+    let productive = grammar.productive();
+    problems.extend(written.iter().filter(|rule| !productive.contains(rule) && !incomplete.contains(rule))
+        .map(|&rule| Problem::Unproductive(rule)));
+
+    // A rule can start with each rule in its alternatives up to and including the first
+    // one which can't match nothing.
+    let nullable = grammar.nullable();
+    let starts = numbers.iter().map(|&rule| {
+        let sequences = match &grammar.rules[&rule] {
+            Rule::Alternatives(alternatives) => &alternatives[..],
+            Rule::Verbatim(_) => &[]
+        };
+        let mut starts = sequences.iter()
+            .flat_map(|sequence| {
+                let len = sequence.iter().position(|number| !nullable.contains(number)).map_or(sequence.len(), |index| index + 1);
+                sequence[..len].iter().copied()
+            })
+            .filter(|number| grammar.rules.contains_key(number))
+            .collect::<Vec<_>>();
+        starts.sort_unstable();
+        starts.dedup();
+        (rule, starts)
+    }).collect::<HashMap<_, _>>();
+    problems.extend(cycles(&numbers, &starts).into_iter().map(Problem::LeftRecursion));
+
+    problems
+}
+
+/// Finds a cycle through each group of rules which can all lead to each other by following `edges`,
+/// starting from the lowest numbered rule in the group.
+fn cycles(numbers: &[usize], edges: &HashMap<usize, Vec<usize>>) -> Vec<Vec<usize>> {
+    // Tarjan's algorithm finds the groups.
+    struct Search<'a> {
+        edges: &'a HashMap<usize, Vec<usize>>,
+        index: HashMap<usize, usize>,
+        low: HashMap<usize, usize>,
+        stack: Vec<usize>,
+        on_stack: HashSet<usize>,
+        groups: Vec<Vec<usize>>
+    }
+
+    impl Search<'_> {
+        fn visit(&mut self, rule: usize) {
+            let index = self.index.len();
+            self.index.insert(rule, index);
+            self.low.insert(rule, index);
+            self.stack.push(rule);
+            self.on_stack.insert(rule);
+
+            for &next in &self.edges[&rule] {
+                if !self.index.contains_key(&next) {
+                    self.visit(next);
+                    let low = self.low[&rule].min(self.low[&next]);
+                    self.low.insert(rule, low);
+                } else if self.on_stack.contains(&next) {
+                    let low = self.low[&rule].min(self.index[&next]);
+                    self.low.insert(rule, low);
+                }
+            }
+
+            if self.low[&rule] == index {
+                let mut group = Vec::new();
+                while let Some(member) = self.stack.pop() {
+                    self.on_stack.remove(&member);
+                    group.push(member);
+                    if member == rule { break; }
+                }
+                self.groups.push(group);
+            }
+        }
+    }
+
+    let mut search = Search {
+        edges, index: HashMap::new(), low: HashMap::new(), stack: Vec::new(), on_stack: HashSet::new(), groups: Vec::new()
+    };
+    for &rule in numbers {
+        if !search.index.contains_key(&rule) { search.visit(rule); }
+    }
+
+    let mut cycles = search.groups.into_iter()
+        .filter(|group| group.len() > 1 || edges[&group[0]].contains(&group[0]))
+        .map(|group| {
+            // Walk the shortest way from the first rule back around to itself, inside the group.
+            let first = *group.iter().min().unwrap();
+            let mut previous = HashMap::new();
+            let mut queue = VecDeque::from(vec![first]);
+            'search: while let Some(rule) = queue.pop_front() {
+                for &next in &edges[&rule] {
+                    if next == first { previous.insert(first, rule); break 'search; }
+                    if group.contains(&next) && !previous.contains_key(&next) {
+                        previous.insert(next, rule);
+                        queue.push_back(next);
+                    }
+                }
+            }
+            let mut cycle = vec![previous[&first]];
+            while *cycle.last().unwrap() != first {
+                cycle.push(previous[cycle.last().unwrap()]);
+            }
+            cycle.reverse();
+            cycle
+        })
+        .collect::<Vec<_>>();
+    cycles.sort_unstable();
+    cycles
+}
@@ -1,65 +1,19 @@
-use std::io::{self, BufRead};
-use std::collections::HashMap;
+use common::{Answer, Error, Solution};
+use crate::{earley::Parser, grammar::Grammar};
 
-enum Rule {
-    Verbatim(u8),
-    Ruleset(Vec<Vec<usize>>)
-}
+pub struct Part1;
 
-impl Rule {
-    fn parse(line: &str) -> Option<(usize, Rule)> {
-        let mut splits = line.splitn(2, ':');
-        let index: usize = splits.next()?.parse().ok()?;
-        let rule = splits.next()?.trim();
+impl Solution for Part1 {
+    /// The rules, and the messages to check against them.
+    type Input = (Grammar, Vec<String>);
 
-        let mut chars = rule.bytes();
-        if chars.next() == Some(b'"') { Some((index, Rule::Verbatim(chars.next()?))) }
-        else {
-            let disjunctions = 
-                rule.split('|').map(|alternative|
-                    alternative.trim().split_whitespace()
-                        .map(|requirement| requirement.parse())
-                        .collect::<Result<Vec<usize>, _>>()
-                ).collect::<Result<Vec<Vec<usize>>, _>>().ok()?;
-            Some((index, Rule::Ruleset(disjunctions)))
-        }
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        Grammar::parse(input, &[0])
     }
 
-    fn evaluate<'a>(&self, input: &'a str, ruleset: &HashMap<usize, Rule>) -> Option<&'a str> {
-        match self {
-            Rule::Verbatim(c) => 
-                if input.bytes().next() == Some(*c) {
-                    Some(&input[1..])
-                } else {
-                    None
-                },
-            Rule::Ruleset(disjunctions) => disjunctions.iter()
-                .filter_map(|alternative| {
-                    let mut input = input;
-
-                    for rule in alternative {
-                        input = ruleset.get(rule).and_then(|rule| rule.evaluate(input, ruleset))?;
-                    }
-                    Some(input)
-                }).next()
-        }
+    fn solve((grammar, messages): Self::Input) -> Result<Answer, Error> {
+        let parser = Parser::compiled(&grammar);
+        let matching = messages.iter().filter(|message| parser.matches(0, message));
+        Ok(matching.count().into())
     }
 }
-
-pub fn run() {
-    let stdin = io::stdin();
-    let mut lines = stdin.lock().lines()
-        .map(|line| line.expect("read error"));
-
-    let rules = lines.by_ref().take_while(|line| !line.is_empty())
-        .map(|line| Rule::parse(&line))
-        .collect::<Option<HashMap<usize, _>>>().expect("invalid input");
-
-    let inputs = lines;
-
-    let first_rule = rules.get(&0).expect("no rule 0");
-    let matching = inputs.filter(|input| first_rule.evaluate(input, &rules)
-        .map(|remainder| remainder.is_empty()).unwrap_or(false)
-    );
-    println!("{}", matching.count());
-}
@@ -0,0 +1,248 @@
+//! Reads rules as they're written, before they're turned into numbered rules by the `grammar` module.
+//! See there for the syntax.
+
+use common::{ErrorKind, ParseError};
+
+/// How many times something can be repeated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Repeat {
+    /// `?`: once or not at all.
+    Optional,
+
+    /// `*`: any number of times, including none.
+    Any,
+
+    /// `+`: at least once.
+    AtLeastOnce
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Expr<'a> {
+    /// Another rule, by number or name.
+    Reference(&'a str),
+
+    /// A string in quotes, with any escapes taken out.
+    Literal(Vec<u8>),
+
+    /// Any one of a set of characters, in square brackets.
+    Class(Vec<u8>),
+
+    /// Sequences separated by `|`, either making up a whole rule or in parentheses.
+    Alternatives(Vec<Vec<Node<'a>>>),
+
+    Repeat(Box<Node<'a>>, Repeat)
+}
+
+/// An expression, along with the text it was written as.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Node<'a> {
+    pub expr: Expr<'a>,
+    pub text: &'a str
+}
+
+/// A rule definition: `<name or number>: <alternatives>`.
+pub(crate) struct Definition<'a> {
+    pub name: &'a str,
+    pub alternatives: Vec<Vec<Node<'a>>>
+}
+
+/// Whether `text` is a rule number or name.
+fn is_name(text: &str) -> bool {
+    !text.is_empty() && text.bytes().all(|c| c.is_ascii_alphanumeric() || c == b'_')
+}
+
+/// Reads the definition on `line`, which must be a slice of `input`.
+pub(crate) fn parse_definition<'a>(input: &str, line: &'a str) -> Result<Definition<'a>, ParseError> {
+    let (name, body) = match line.split_once(':') {
+        Some((name, body)) => (name.trim(), body),
+        None => return Err(ParseError::at(input, line, ErrorKind::Expected("'<rule>: <alternatives>'")))
+    };
+    if name.starts_with(|c: char| c.is_ascii_digit()) {
+        common::parse_number::<usize>(input, name)?;
+    } else if !is_name(name) {
+        return Err(ParseError::at(input, name, ErrorKind::Expected("a rule number, or a name made of letters, digits and '_'")));
+    }
+
+    let mut parser = Parser { input, text: body, position: 0 };
+    let alternatives = parser.alternatives()?;
+    match parser.peek() {
+        None => Ok(Definition { name, alternatives }),
+        Some(_) => Err(parser.error(ErrorKind::UnexpectedChar(')')))
+    }
+}
+
+/// Reads the body of a rule, one character at a time.
+struct Parser<'a, 'i> {
+    input: &'i str,
+    text: &'a str,
+    position: usize
+}
+
+impl<'a> Parser<'a, '_> {
+    /// Skips whitespace, and returns the next character.
+    fn peek(&mut self) -> Option<char> {
+        let rest = &self.text[self.position..];
+        self.position += rest.len() - rest.trim_start().len();
+        self.text[self.position..].chars().next()
+    }
+
+    /// An error at the next character, or at the end of the line.
+    fn error(&self, kind: ErrorKind) -> ParseError {
+        let rest = &self.text[self.position..];
+        ParseError::at(self.input, &rest[..rest.chars().next().map_or(0, char::len_utf8)], kind)
+    }
+
+    /// Reads sequences separated by `|`, up to the end of the line or a `)`.
+    fn alternatives(&mut self) -> Result<Vec<Vec<Node<'a>>>, ParseError> {
+        let mut alternatives = vec![Vec::new()];
+        loop {
+            match self.peek() {
+                None | Some(')') => return Ok(alternatives),
+                Some('|') => {
+                    self.position += 1;
+                    alternatives.push(Vec::new());
+                }
+                Some(_) => {
+                    let node = self.item()?;
+                    alternatives.last_mut().unwrap().push(node);
+                }
+            }
+        }
+    }
+
+    /// Reads something to match, along with any repetition operators after it.
+    fn item(&mut self) -> Result<Node<'a>, ParseError> {
+        let start = self.position;
+        let mut node = self.term()?;
+        while let Some(repeat) = match self.peek() {
+            Some('?') => Some(Repeat::Optional),
+            Some('*') => Some(Repeat::Any),
+            Some('+') => Some(Repeat::AtLeastOnce),
+            _ => None
+        } {
+            self.position += 1;
+            node = Node { expr: Expr::Repeat(Box::new(node), repeat), text: &self.text[start..self.position] };
+        }
+        Ok(node)
+    }
+
+    fn term(&mut self) -> Result<Node<'a>, ParseError> {
+        let next = self.peek();
+        let start = self.position;
+        let rest = &self.text[start..];
+        let expr = match next {
+            Some('"') => {
+                self.position += 1;
+                Expr::Literal(self.string()?)
+            }
+            Some('[') => {
+                self.position += 1;
+                let class = self.class()?;
+                if class.is_empty() {
+                    return Err(ParseError::at(self.input, &self.text[start..self.position],
+                        ErrorKind::Expected("at least one character in the class")));
+                }
+                Expr::Class(class)
+            }
+            Some('(') => {
+                self.position += 1;
+                let alternatives = self.alternatives()?;
+                if self.peek() != Some(')') {
+                    return Err(self.error(ErrorKind::Expected("')' to match the '('")));
+                }
+                self.position += 1;
+                Expr::Alternatives(alternatives)
+            }
+            Some(c) if c.is_ascii_alphanumeric() || c == '_' => {
+                let len = rest.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_')).unwrap_or(rest.len());
+                let name = &rest[..len];
+                if name.starts_with(|c: char| c.is_ascii_digit()) {
+                    common::parse_number::<usize>(self.input, name)?;
+                }
+                self.position += len;
+                Expr::Reference(name)
+            }
+            Some('?' | '*' | '+') => return Err(self.error(ErrorKind::Expected("something to repeat before this"))),
+            Some(c) => return Err(self.error(ErrorKind::UnexpectedChar(c))),
+            None => unreachable!("alternatives() stops at the end of the line")
+        };
+        Ok(Node { expr, text: &self.text[start..self.position] })
+    }
+
+    /// Reads the rest of a string, where `\` makes the character after it stand for itself.
+    fn string(&mut self) -> Result<Vec<u8>, ParseError> {
+        let mut characters = Vec::new();
+        let mut escaped = false;
+        for (index, c) in self.text[self.position..].char_indices() {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => { escaped = true; continue }
+                '"' => {
+                    self.position += index + 1;
+                    return Ok(characters);
+                }
+                _ => ()
+            }
+            let mut buffer = [0; 4];
+            characters.extend_from_slice(c.encode_utf8(&mut buffer).as_bytes());
+        }
+        self.position = self.text.len();
+        Err(self.error(ErrorKind::Expected("'\"' to end the string")))
+    }
+
+    /// Reads the inside of a character class, with ranges like `a-z` expanded, sorted and without repeats.
+    fn class(&mut self) -> Result<Vec<u8>, ParseError> {
+        let start = self.position;
+        // Each character, and whether it was escaped.
+        let mut characters = Vec::new();
+        let mut chars = self.text[start..].char_indices();
+        loop {
+            let (index, c, escaped) = match chars.next() {
+                Some((index, ']')) => {
+                    self.position = start + index + 1;
+                    break;
+                }
+                Some((_, '\\')) => match chars.next() {
+                    Some((index, c)) => (index, c, true),
+                    None => break
+                },
+                Some((index, c)) => (index, c, false),
+                None => break
+            };
+            if !c.is_ascii() {
+                let at = start + index;
+                return Err(ParseError::at(self.input, &self.text[at..at + c.len_utf8()],
+                    ErrorKind::Expected("only ASCII characters in a character class")));
+            }
+            characters.push((c as u8, escaped));
+        }
+        if self.position == start {
+            self.position = self.text.len();
+            return Err(self.error(ErrorKind::Expected("']' to end the character class")));
+        }
+
+        // An unescaped '-' between two characters makes a range.
+        let mut class = Vec::new();
+        let mut index = 0;
+        while index < characters.len() {
+            match characters[index..] {
+                [(low, _), (b'-', false), (high, _), ..] => {
+                    if low > high {
+                        return Err(ParseError::at(self.input, &self.text[start..self.position - 1],
+                            ErrorKind::Expected("ranges from a lower character to a higher one")));
+                    }
+                    class.extend(low..=high);
+                    index += 3;
+                }
+                [(c, _), ..] => {
+                    class.push(c);
+                    index += 1;
+                }
+                [] => unreachable!()
+            }
+        }
+        class.sort_unstable();
+        class.dedup();
+        Ok(class)
+    }
+}
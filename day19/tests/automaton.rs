@@ -0,0 +1,101 @@
+use day19::{automaton, earley::Parser, grammar::{Grammar, Rule}};
+use regex::Regex;
+
+fn grammar(rules: &str) -> Grammar {
+    Grammar::parse(rules, &[0]).unwrap().0
+}
+
+/// Every string of 'a's and 'b's up to `len` characters long.
+fn messages(len: usize) -> Vec<String> {
+    let mut messages = vec![String::new()];
+    let mut index = 0;
+    while let Some(message) = messages.get(index).cloned() {
+        index += 1;
+        if message.len() < len {
+            messages.push(message.clone() + "a");
+            messages.push(message + "b");
+        }
+    }
+    messages
+}
+
+/// Checks that the compiled DFA and regex for rule 0 accept exactly what the grammar does.
+fn check(grammar: &Grammar, len: usize) {
+    let dfa = &automaton::compile(grammar)[&0];
+    let regex = Regex::new(&format!("^{}$", dfa.to_regex().unwrap())).unwrap();
+    let parser = Parser::new(grammar);
+    for message in messages(len) {
+        let expected = parser.matches(0, &message);
+        assert_eq!(dfa.matches(&message), expected, "{:?}", message);
+        assert_eq!(regex.is_match(&message), expected, "{:?} against {}", message, regex);
+    }
+}
+
+#[test]
+fn compiles() {
+    check(&grammar(include_str!("../test").split("\n\n").next().unwrap()), 8);
+    check(&grammar("0: 1 2\n1: 3 |\n2: 4 | 4 4 1\n3: \"a\"\n4: \"b\"\n"), 6);
+    check(&grammar("0: 1 2 1\n1: 3 3 | 3 4 | 4 3 | 4 4\n2: 1 | 3\n3: \"a\"\n4: \"b\"\n"), 7);
+}
+
+#[test]
+fn minimizes() {
+    // Both alternatives are the same, and so are rules 1 and 2.
+    let grammar = grammar("0: 1 3 | 2 3\n1: 3 3\n2: 3 3\n3: \"a\"\n");
+    let compiled = automaton::compile(&grammar);
+    assert_eq!(compiled[&0].len(), 4);
+    assert_eq!(compiled[&0].to_regex().unwrap(), "aaa");
+
+    // Any two characters, then an 'a'.
+    let grammar = self::grammar("0: 1 1 2\n1: 2 | 3\n2: \"a\"\n3: \"b\"\n");
+    let compiled = automaton::compile(&grammar);
+    assert_eq!(compiled[&0].len(), 4);
+    assert_eq!(compiled[&0].to_regex().unwrap(), "[ab][ab]a");
+
+    // Character classes are written with ranges where they can be.
+    let grammar = self::grammar("0: [a-e] [ac] (\"x\" | \"y\" | \"z\")\n");
+    assert_eq!(automaton::compile(&grammar)[&0].to_regex().unwrap(), "[a-e][ac][x-z]");
+}
+
+#[test]
+fn wide_grammars() {
+    // Each "ab" or "b" ends up in the same state, but the regex has to spell out everything after
+    // it twice, so it doubles in length with every copy of rule 1.
+    let grammar = |copies: usize| self::grammar(&format!("0:{}\n1: \"ab\" | \"b\"\n", " 1".repeat(copies)));
+    check(&grammar(6), 12);
+
+    let dfa = &automaton::compile(&grammar(40))[&0];
+    assert_eq!(dfa.len(), 81);
+    assert_eq!(dfa.to_regex(), None);
+    assert!(dfa.matches(&format!("{}{}", "ab".repeat(20), "b".repeat(20))));
+    let listing = dfa.to_string();
+    assert_eq!(listing.lines().count(), 81);
+    assert!(listing.starts_with("0: a -> ") && listing.contains(" (accepting):"), "{}", listing);
+}
+
+#[test]
+fn ends() {
+    let grammar = grammar("0: 1 | 1 1 | 1 1 1 1\n1: \"a\"\n");
+    let dfa = &automaton::compile(&grammar)[&0];
+    assert_eq!(dfa.ends(b"aaaaab").collect::<Vec<_>>(), vec![1, 2, 4]);
+    assert_eq!(dfa.ends(b"ba").count(), 0);
+}
+
+#[test]
+fn recursive_rules() {
+    // Only the rules which don't lead back to 8 or 11 can be compiled.
+    let mut grammar = grammar("0: 8 11\n8: 42\n11: 42 31\n42: 1 1 | 2\n31: 1 2\n1: \"a\"\n2: \"b\"\n");
+    grammar.rules.insert(8, Rule::Alternatives(vec![vec![42], vec![42, 8]]));
+    grammar.rules.insert(11, Rule::Alternatives(vec![vec![42, 31], vec![42, 11, 31]]));
+    let mut compiled = automaton::compile(&grammar).into_keys().collect::<Vec<_>>();
+    compiled.sort_unstable();
+    assert_eq!(compiled, vec![1, 2, 31, 42]);
+
+    // The compiled parser falls back to the sets for those, and agrees with the plain one.
+    let (plain, compiled) = (Parser::new(&grammar), Parser::compiled(&grammar));
+    assert!(compiled.automaton(0).is_none() && compiled.automaton(42).is_some());
+    for message in messages(10) {
+        assert_eq!(compiled.matches(0, &message), plain.matches(0, &message), "{:?}", message);
+    }
+    assert!(compiled.matches(0, "baaab") && compiled.matches(0, "aabbabab"));
+}
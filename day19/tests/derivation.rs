@@ -0,0 +1,101 @@
+use day19::{derivation::{Count, Tree}, earley::Parser, grammar::{Grammar, Rule}};
+
+fn grammar(rules: &str) -> Grammar {
+    Grammar::parse(rules, &[0]).unwrap().0
+}
+
+fn leaf(rule: usize, at: usize) -> Tree {
+    Tree { rule, span: at..at + 1, children: Vec::new() }
+}
+
+#[test]
+fn trees() {
+    let grammar = grammar("0: 4 1 5\n1: 2 3 | 3 2\n2: 4 4 | 5 5\n3: 4 5 | 5 4\n4: \"a\"\n5: \"b\"\n");
+    let parse = Parser::new(&grammar).parse(0, "ababbb");
+    assert_eq!(parse.count(), Count::Finite(1));
+
+    let node = |rule, span, children| Tree { rule, span, children };
+    let expected = node(0, 0..6, vec![
+        leaf(4, 0),
+        node(1, 1..5, vec![
+            node(3, 1..3, vec![leaf(5, 1), leaf(4, 2)]),
+            node(2, 3..5, vec![leaf(5, 3), leaf(5, 4)])
+        ]),
+        leaf(5, 5)
+    ]);
+    assert_eq!(parse.trees(10), vec![expected.clone()]);
+    assert_eq!(expected.outline("ababbb").to_string(),
+        "0: ababbb\n  4: a\n  1: babb\n    3: ba\n      5: b\n      4: a\n    2: bb\n      5: b\n      5: b\n  5: b\n");
+}
+
+#[test]
+fn counts() {
+    // Every way of bracketing a sequence: the Catalan numbers.
+    let grammar = self::grammar("0: 0 0 | 1\n1: \"a\"\n");
+    let parser = Parser::new(&grammar);
+    let catalan = [1, 1, 2, 5, 14, 42, 132, 429];
+    for (len, &count) in catalan.iter().enumerate() {
+        let message = "a".repeat(len + 1);
+        let parse = parser.parse(0, &message);
+        assert_eq!(parse.count(), Count::Finite(count));
+        assert_eq!(parse.trees(1000).len(), count as usize);
+        assert_eq!(parse.trees(3).len(), count.min(3) as usize);
+    }
+    assert_eq!(parser.parse(0, &"a".repeat(200)).count(), Count::Overflow);
+    assert_eq!(parser.parse(0, &"a".repeat(200)).trees(2).len(), 2);
+    assert_eq!(parser.parse(0, "ab").count(), Count::Finite(0));
+
+    // Part 2's replacement rules make messages ambiguous when 42 and 31 can match the same thing:
+    // here 'aaaaa' is 8 matching 'a' and 11 'aaaa', or 8 'aaa' and 11 'aa'.
+    let mut grammar = self::grammar("0: 8 11\n8: 42\n11: 42 31\n42: \"a\"\n31: \"a\"\n");
+    grammar.rules.insert(8, Rule::Alternatives(vec![vec![42], vec![42, 8]]));
+    grammar.rules.insert(11, Rule::Alternatives(vec![vec![42, 31], vec![42, 11, 31]]));
+    assert_eq!(Parser::new(&grammar).parse(0, "aaaaa").count(), Count::Finite(2));
+}
+
+#[test]
+fn infinite_ambiguity() {
+    // Rule 2 matches nothing, as many times as we like.
+    let grammar = self::grammar("0: 1 2 0 | 1\n1: \"a\"\n2: 2 2 |\n");
+    let parse = Parser::new(&grammar).parse(0, "aa");
+    assert_eq!(parse.count(), Count::Infinite);
+    assert!(!parse.trees(5).is_empty());
+    assert!(parse.trees(5).iter().all(|tree| tree.rule == 0 && tree.span == (0..2)));
+
+    // Rules 0 and 1 match each other.
+    let grammar = self::grammar("0: 1 | 2\n1: 0\n2: \"a\"\n");
+    let parse = Parser::new(&grammar).parse(0, "a");
+    assert_eq!(parse.count(), Count::Infinite);
+    assert_eq!(parse.trees(5), vec![Tree { rule: 0, span: 0..1, children: vec![leaf(2, 0)] }]);
+
+    // A loop which can't be part of a match doesn't count.
+    let grammar = self::grammar("0: 1 3 | 2\n1: 0\n2: \"a\"\n3: \"b\"\n");
+    assert_eq!(Parser::new(&grammar).parse(0, "a").count(), Count::Finite(1));
+}
+
+#[test]
+fn failures() {
+    let grammar = grammar("0: 4 1 5\n1: 2 3 | 3 2\n2: 4 4 | 5 5\n3: 4 5 | 5 4\n4: \"a\"\n5: \"b\"\n");
+    let parser = Parser::new(&grammar);
+
+    // 'abbb' could be the start of 'abbbab', but nothing starts 'abbbb'.
+    let parse = parser.parse(0, "abbbbbb");
+    assert!(!parse.matched() && parse.trees(1).is_empty() && parse.count() == Count::Finite(0));
+    assert_eq!(parse.longest_prefix(), 4);
+    assert_eq!(parse.expected(), vec![4]);
+
+    let parse = parser.parse(0, "bab");
+    assert_eq!(parse.longest_prefix(), 0);
+    assert_eq!(parse.expected(), vec![4]);
+
+    // The message stops short.
+    let parse = parser.parse(0, "abab");
+    assert_eq!(parse.longest_prefix(), 4);
+    assert_eq!(parse.expected(), vec![5]);
+    assert!(!parse.prefix_matched(4));
+
+    // The message goes on too long.
+    let parse = parser.parse(0, "aaaabbb");
+    assert_eq!(parse.longest_prefix(), 6);
+    assert!(parse.expected().is_empty() && parse.prefix_matched(6));
+}
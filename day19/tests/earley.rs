@@ -0,0 +1,52 @@
+use day19::{earley::Parser, grammar::{Grammar, Rule}};
+
+fn grammar(rules: &str) -> Grammar {
+    Grammar::parse(rules, &[0]).unwrap().0
+}
+
+#[test]
+fn left_recursion() {
+    // 0 is one or more 'a's followed by a 'b', written the way a backtracking parser can't handle.
+    let grammar = grammar("0: 1 2\n1: 1 3 | 3\n2: \"b\"\n3: \"a\"\n");
+    let parser = Parser::new(&grammar);
+    assert!(parser.matches(0, "ab") && parser.matches(0, "aaaab"));
+    assert!(!parser.matches(0, "b") && !parser.matches(0, "aaba") && !parser.matches(0, ""));
+}
+
+#[test]
+fn empty_alternatives() {
+    // 1 is any number of 'a's, including none; 2 is balanced 'a's and 'b's.
+    let grammar = grammar("0: 1 4 2\n1: 3 1 |\n2: 3 2 4 |\n3: \"a\"\n4: \"b\"\n");
+    let parser = Parser::new(&grammar);
+    for message in &["b", "ab", "aaab", "bab", "aabaabb", "abaabb"] {
+        assert!(parser.matches(0, message), "{}", message);
+    }
+    for message in &["", "a", "bb", "baab", "abaab"] {
+        assert!(!parser.matches(0, message), "{}", message);
+    }
+
+    // A rule that only ever matches nothing, recursively.
+    let grammar = self::grammar("0: 0 0 | 0 |\n");
+    assert!(Parser::new(&grammar).matches(0, ""));
+    assert!(!Parser::new(&grammar).matches(0, "a"));
+}
+
+#[test]
+fn ambiguity() {
+    // Every way of splitting up a long message is a match, which takes exponential time to list.
+    let grammar = grammar("0: 0 0 | 1\n1: \"a\"\n");
+    let parser = Parser::new(&grammar);
+    assert!(parser.matches(0, &"a".repeat(200)));
+    assert!(!parser.matches(0, &format!("{}b", "a".repeat(200))));
+}
+
+#[test]
+fn replacement_rules() {
+    // Part 2's replacement rules, with 42 and 31 matching more than one character.
+    let mut grammar = grammar("0: 8 11\n8: 42\n11: 42 31\n42: 1 1\n31: 1 2\n1: \"a\"\n2: \"b\"\n");
+    grammar.rules.insert(8, Rule::Alternatives(vec![vec![42], vec![42, 8]]));
+    grammar.rules.insert(11, Rule::Alternatives(vec![vec![42, 31], vec![42, 11, 31]]));
+    let parser = Parser::new(&grammar);
+    assert!(parser.matches(0, "aaaaab") && parser.matches(0, "aaaaaaaaabab"));
+    assert!(!parser.matches(0, "aaaaabab") && !parser.matches(0, "aaaab"));
+}
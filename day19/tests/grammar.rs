@@ -0,0 +1,85 @@
+use day19::{earley::Parser, grammar::{Grammar, Rule}};
+
+fn grammar(rules: &str) -> Grammar {
+    Grammar::parse(rules, &[]).unwrap().0
+}
+
+/// The errors from parsing `rules`, one per line.
+fn errors(rules: &str) -> String {
+    Grammar::parse(rules, &[]).err().unwrap().to_string()
+}
+
+/// Checks which messages match `rule`, with and without compiling.
+fn check(grammar: &Grammar, rule: &str, matching: &[&str], other: &[&str]) {
+    let rule = grammar.number(rule).unwrap();
+    for parser in &[Parser::new(grammar), Parser::compiled(grammar)] {
+        for message in matching {
+            assert!(parser.matches(rule, message), "{:?}", message);
+        }
+        for message in other {
+            assert!(!parser.matches(rule, message), "{:?}", message);
+        }
+    }
+}
+
+#[test]
+fn puzzle_syntax() {
+    let grammar = grammar(include_str!("../test"));
+    assert_eq!(grammar.rules.len(), 6);
+    assert!(grammar.names.is_empty());
+    assert!(matches!(grammar.rules[&4], Rule::Verbatim(b'a')));
+    assert!(matches!(&grammar.rules[&1], Rule::Alternatives(alternatives) if *alternatives == vec![vec![2, 3], vec![3, 2]]));
+}
+
+#[test]
+fn names_and_strings() {
+    let grammar = grammar("0: greeting \" \" name\ngreeting: \"hello\" | \"hi\"\nname: \"world\" | \"\\\"you\\\"\"\n");
+    check(&grammar, "0", &["hello world", "hi \"you\""], &["hello", "hi you", "hello  world"]);
+
+    // Names are numbered after the highest number, and the extra rules after them.
+    assert_eq!(grammar.number("greeting"), Some(1));
+    assert_eq!(grammar.number("name"), Some(2));
+    assert_eq!(grammar.label(1), "greeting");
+    assert_eq!(grammar.label(0), "0");
+    assert_eq!(grammar.number("\"h\"").map(|number| number > 2), Some(true));
+    assert_eq!(grammar.number("nobody"), None);
+}
+
+#[test]
+fn classes() {
+    let grammar = grammar("hex: \"0x\" [0-9a-fA-F] [0-9a-fA-F]\nsign: [+\\-]\nrange: [a\\-c]\n");
+    check(&grammar, "hex", &["0x00", "0xfF", "0xA9"], &["0x0", "0xfg", "0X00"]);
+    check(&grammar, "sign", &["+", "-"], &[",", ""]);
+    check(&grammar, "range", &["a", "-", "c"], &["b"]);
+}
+
+#[test]
+fn repetition() {
+    let grammar = grammar("number: \"-\"? [0-9]+ (\".\" [0-9]*)?\nlist: \"[\" (number (\",\" number)*)? \"]\"\n");
+    check(&grammar, "number", &["0", "-12", "3.", "3.25", "-0.5"], &["", "-", ".5", "1.2.3", "1-"]);
+    check(&grammar, "list", &["[]", "[1]", "[1,-2.5,3]"], &["[", "[,]", "[1,]", "[1 2]"]);
+
+    // The same repetition written twice is only made into a rule once.
+    let grammar = self::grammar("0: \"a\"* 1\n1: \"a\"*\n");
+    assert_eq!(grammar.rules.len(), 4);
+}
+
+#[test]
+fn errors_in_rules() {
+    assert_eq!(errors("0: 1 |\n1 2\n"), "line 2, column 1: expected '<rule>: <alternatives>' (found '1 2')");
+    assert_eq!(errors("0x: \"a\"\n"), "line 1, column 1: invalid number (found '0x')");
+    assert_eq!(errors("my rule: \"a\"\n"),
+        "line 1, column 1: expected a rule number, or a name made of letters, digits and '_' (found 'my rule')");
+    assert_eq!(errors("0: \"ab\n"), "line 1, column 7: expected '\"' to end the string");
+    assert_eq!(errors("0: [a-z\n"), "line 1, column 8: expected ']' to end the character class");
+    assert_eq!(errors("0: []\n"), "line 1, column 4: expected at least one character in the class (found '[]')");
+    assert_eq!(errors("0: [z-a]\n"), "line 1, column 5: expected ranges from a lower character to a higher one (found 'z-a')");
+    assert_eq!(errors("0: (1 | 2\n1: \"a\"\n2: \"b\"\n"), "line 1, column 10: expected ')' to match the '('");
+    assert_eq!(errors("0: 1 2)\n"), "line 1, column 7: unexpected character ')'");
+    assert_eq!(errors("0: * 1\n"), "line 1, column 4: expected something to repeat before this (found '*')");
+    assert_eq!(errors("0: 1 ; 2\n"), "line 1, column 6: unexpected character ';'");
+
+    // Every problem is reported, including references to rules which don't exist.
+    assert_eq!(errors("0: 1 two\n1: \"a\"\n1: \"b\"\n"),
+        "line 3, column 1: duplicate rule (found '1: \"b\"')\nline 1, column 6: unknown rule (found 'two')");
+}
@@ -0,0 +1,76 @@
+use day19::{grammar::Grammar, lint::{lint, Problem}};
+
+fn grammar(rules: &str) -> Grammar {
+    Grammar::parse(rules, &[0]).unwrap().0
+}
+
+#[test]
+fn puzzle_rules() {
+    assert!(lint(&grammar(include_str!("../test")), 0).is_empty());
+    assert!(lint(&grammar(include_str!("../test2")), 0).is_empty());
+}
+
+#[test]
+fn undefined() {
+    let input = "0: 1 2\n1: \"a\"\n2: 3 1 | missing\n";
+    assert!(Grammar::parse(input, &[0]).is_err());
+    let grammar = Grammar::parse_lenient(input, &[0]).unwrap().0;
+    let problems = lint(&grammar, 0);
+    assert_eq!(problems, vec![
+        Problem::Undefined { rule: 2, reference: 3 },
+        Problem::Undefined { rule: 2, reference: 4 }
+    ]);
+    assert_eq!(problems[0].describe(&grammar), "rule 2 refers to rule 3, which isn't defined");
+    assert_eq!(problems[1].describe(&grammar), "rule 2 refers to rule missing, which isn't defined");
+
+    // Rules which need an undefined one aren't also said to never match anything, but others still are.
+    let grammar = Grammar::parse_lenient("0: 1 | 2\n1: 1 3\n2: 3 missing\n3: \"a\"\n", &[0]).unwrap().0;
+    assert_eq!(lint(&grammar, 0), vec![
+        Problem::Undefined { rule: 2, reference: 4 },
+        Problem::Unproductive(1),
+        Problem::LeftRecursion(vec![1])
+    ]);
+
+    // Part 2's replacement rules refer to rules 42 and 31, which might not be there either.
+    let mut grammar = Grammar::parse_lenient("0: 8 11\n8: 1\n11: 1 1\n1: \"a\"\n", &[0]).unwrap().0;
+    day19::part2::replace_rules(&mut grammar);
+    assert!(lint(&grammar, 0).contains(&Problem::Undefined { rule: 8, reference: 42 }));
+    assert!(lint(&grammar, 0).contains(&Problem::Undefined { rule: 11, reference: 31 }));
+}
+
+#[test]
+fn unreachable() {
+    let grammar = grammar("0: 1\n1: \"a\"\n2: 3 \"b\"*\n3: \"c\"\nunused: 1 | 2\n");
+    assert_eq!(lint(&grammar, 0), vec![Problem::Unreachable(2), Problem::Unreachable(3), Problem::Unreachable(4)]);
+    assert_eq!(lint(&grammar, 0)[2].describe(&grammar), "rule unused is never used");
+    assert_eq!(lint(&grammar, 2), vec![Problem::Unreachable(0), Problem::Unreachable(1), Problem::Unreachable(4)]);
+}
+
+#[test]
+fn unproductive() {
+    // Rule 1 always needs another 1 first, and rule 0 can't do without it. Rule 2 is fine,
+    // because it can stop.
+    let grammar = grammar("0: 1 2 | 2 1\n1: 3 1 | 1 3\n2: 3 2 | 3\n3: \"a\"\n");
+    assert_eq!(lint(&grammar, 0), vec![
+        Problem::Unproductive(0),
+        Problem::Unproductive(1),
+        Problem::LeftRecursion(vec![1])
+    ]);
+    assert_eq!(lint(&grammar, 0)[0].describe(&grammar), "rule 0 can never match anything");
+}
+
+#[test]
+fn left_recursion() {
+    // 1 starts with 2, which starts with 1 after something that can match nothing.
+    let grammar = grammar("0: 1 | 4\n1: 2 \"a\" | \"b\"\n2: 3 1 \"c\"\n3: \"d\" |\n4: 4 \"e\" | \"f\"\n");
+    let problems = lint(&grammar, 0);
+    assert_eq!(problems, vec![Problem::LeftRecursion(vec![1, 2]), Problem::LeftRecursion(vec![4])]);
+    assert_eq!(problems[0].describe(&grammar), "rules are left-recursive: 1 -> 2 -> 1");
+    assert_eq!(problems[1].describe(&grammar), "rules are left-recursive: 4 -> 4");
+
+    // Recursion on the right is fine, and so is repetition, unless what's repeated can match nothing.
+    assert!(lint(&self::grammar("0: 1 0 | 1\n1: \"a\"+\n"), 0).is_empty());
+    let grammar = self::grammar("0: \"a\"?*\n");
+    assert_eq!(lint(&grammar, 0).iter().map(|problem| problem.describe(&grammar)).collect::<Vec<_>>(),
+        vec!["rules are left-recursive: \"a\"?* -> \"a\"?*"]);
+}
@@ -1,32 +1,59 @@
-use std::io::{self, BufRead};
-
-pub fn run() {
-    let count = io::stdin().lock().lines()
-        .filter(|line|
-            is_valid(&line.as_ref().expect("read error"))
-        ).count();
-    println!("{}", count);
+use common::{Answer, Error, ErrorKind, ParseError, Solution};
+
+pub struct Part1;
+
+impl Solution for Part1 {
+    type Input = Vec<Entry>;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        Ok(common::collect_all(input.lines()
+            .map(|line| Entry::parse(input, line)))?)
+    }
+
+    fn solve(input: Self::Input) -> Result<Answer, Error> {
+        Ok(input.iter().filter(|entry| entry.is_valid()).count().into())
+    }
 }
 
-fn is_valid(input: &str) -> bool { 
-    // use nested function to simplify error handling
-    (|| {
+/// A password, along with the policy it must satisfy.
+pub struct Entry {
+    min: usize,
+    max: usize,
+    letter: char,
+    password: String
+}
+
+impl Entry {
+    /// Parses an entry from `line`, which must be a slice of `input`.
+    fn parse(input: &str, line: &str) -> Result<Entry, ParseError> {
+        let expected = |text, what| move || ParseError::at(input, text, ErrorKind::Expected(what));
+
         // password will have a leading space, but that's fine
-        let (policy, password) = partition(input, ':')?;
+        let (policy, password) = partition(line, ':')
+            .ok_or_else(expected(line, "'<policy>: <password>'"))?;
 
-        let (occurences, letter) = partition(policy, ' ')?;
-        assert!(letter.chars().count() == 1, "{} is not a single character", letter);
-        let letter = letter.chars().next()?;
+        let (occurences, letter) = partition(policy, ' ')
+            .ok_or_else(expected(policy, "'<min>-<max> <letter>'"))?;
+        let mut chars = letter.chars();
+        let letter = match (chars.next(), chars.next()) {
+            (Some(c), None) => c,
+            _ => return Err(expected(letter, "a single letter")())
+        };
 
-        let (min, max) = partition(occurences, '-')?;
+        let (min, max) = partition(occurences, '-')
+            .ok_or_else(expected(occurences, "'<min>-<max>'"))?;
         let (min, max): (usize, usize) = (
-            min.parse().ok()?,
-            max.parse().ok()?
+            common::parse_number(input, min)?,
+            common::parse_number(input, max)?
         );
 
-        let count = password.chars().filter(|c| c == &letter).count();
-        Some(count >= min && count <= max)
-    })().expect(&format!("invalid input {}", input))
+        Ok(Entry { min, max, letter, password: password.to_string() })
+    }
+
+    fn is_valid(&self) -> bool {
+        let count = self.password.chars().filter(|c| c == &self.letter).count();
+        count >= self.min && count <= self.max
+    }
 }
 
 /// Divides a string into exactly two halves separated by a character.
@@ -1,33 +1,60 @@
-use std::io::{self, BufRead};
-
-pub fn run() {
-    let count = io::stdin().lock().lines()
-        .filter(|line|
-            is_valid(&line.as_ref().expect("read error"))
-        ).count();
-    println!("{}", count);
+use common::{Answer, Error, ErrorKind, ParseError, Solution};
+
+pub struct Part2;
+
+impl Solution for Part2 {
+    type Input = Vec<Entry>;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        Ok(common::collect_all(input.lines()
+            .map(|line| Entry::parse(input, line)))?)
+    }
+
+    fn solve(input: Self::Input) -> Result<Answer, Error> {
+        Ok(input.iter().filter(|entry| entry.is_valid()).count().into())
+    }
 }
 
-fn is_valid(input: &str) -> bool { 
-    // use nested function to simplify error handling
-    (|| {
+/// A password, along with the policy it must satisfy.
+pub struct Entry {
+    first: usize,
+    second: usize,
+    letter: char,
+    password: String
+}
+
+impl Entry {
+    /// Parses an entry from `line`, which must be a slice of `input`.
+    fn parse(input: &str, line: &str) -> Result<Entry, ParseError> {
+        let expected = |text, what| move || ParseError::at(input, text, ErrorKind::Expected(what));
+
         // password will have a leading space, but that's fine
-        let (policy, password) = partition(input, ':')?;
+        let (policy, password) = partition(line, ':')
+            .ok_or_else(expected(line, "'<policy>: <password>'"))?;
 
-        let (occurences, letter) = partition(policy, ' ')?;
-        assert!(letter.chars().count() == 1, "{} is not a single character", letter);
-        let letter = letter.chars().next()?;
+        let (occurences, letter) = partition(policy, ' ')
+            .ok_or_else(expected(policy, "'<first>-<second> <letter>'"))?;
+        let mut chars = letter.chars();
+        let letter = match (chars.next(), chars.next()) {
+            (Some(c), None) => c,
+            _ => return Err(expected(letter, "a single letter")())
+        };
 
-        let (first, second) = partition(occurences, '-')?;
+        let (first, second) = partition(occurences, '-')
+            .ok_or_else(expected(occurences, "'<first>-<second>'"))?;
         let (first, second): (usize, usize) = (
-            first.parse().ok()?,
-            second.parse().ok()?
+            common::parse_number(input, first)?,
+            common::parse_number(input, second)?
         );
 
-        let first = password.chars().nth(first)? == letter;
-        let second = password.chars().nth(second)? == letter;
-        Some(first ^ second)
-    })().expect(&format!("invalid input {}", input))
+        Ok(Entry { first, second, letter, password: password.to_string() })
+    }
+
+    fn is_valid(&self) -> bool {
+        let first = self.password.chars().nth(self.first) == Some(self.letter);
+        let second = self.password.chars().nth(self.second) == Some(self.letter);
+        first ^ second
+    }
 }
 
 /// Divides a string into exactly two halves separated by a character.
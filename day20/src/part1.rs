@@ -1,6 +1,6 @@
-use std::io::{self, BufRead};
 use lazy_static::lazy_static;
 use regex::Regex;
+use common::{Answer, Error, ErrorKind, ParseError, Solution};
 
 const TILE_SIZE: usize = 10;
 
@@ -35,7 +35,7 @@ impl Border {
 }
 
 #[derive(Debug)]
-struct Tile {
+pub struct Tile {
     id: u32,
     
     /// The edges.
@@ -43,56 +43,73 @@ struct Tile {
 }
 
 impl Tile {
-    fn parse(input: &[String]) -> Option<Tile> {
-        if input.len() != TILE_SIZE + 1 { return None; }
-
-        let id_line = &input[0];
+    /// Parses a tile from `lines`, which must be slices of `input`.
+    fn parse(input: &str, lines: &[&str]) -> Result<Tile, ParseError> {
+        let id_line = lines[0];
         lazy_static! {
             static ref REGEX: Regex = Regex::new(r"^Tile (\d+):$").unwrap();
         }
-        let tile_id = REGEX
-            .captures(&id_line)?.get(1)?
-            .as_str().parse().ok()?;
+        let tile_id = REGEX.captures(id_line)
+            .ok_or_else(|| ParseError::at(input, id_line, ErrorKind::Expected("'Tile <id>:'")))?;
+        let tile_id = common::parse_number(input, tile_id.get(1).unwrap().as_str())?;
 
-        let input = &input[1..];
-        if input.iter().find(|line| line.len() != TILE_SIZE) != None { return None; }
-        Some(Tile {
+        let rows = &lines[1..];
+        if rows.len() < TILE_SIZE {
+            return Err(ParseError::after(input, lines[lines.len() - 1], ErrorKind::Expected("10 rows of tile data")));
+        } else if rows.len() > TILE_SIZE {
+            return Err(ParseError::at(input, rows[TILE_SIZE], ErrorKind::Expected("a blank line after each tile")));
+        }
+        for row in rows {
+            if let Some((index, c)) = row.char_indices().find(|(_, c)| *c != '#' && *c != '.') {
+                return Err(ParseError::at(input, &row[index..index + c.len_utf8()], ErrorKind::UnexpectedChar(c)));
+            } else if row.len() != TILE_SIZE {
+                return Err(ParseError::at(input, row, ErrorKind::Expected("a row of 10 characters")));
+            }
+        }
+        let input = rows;
+        fn border(chars: impl Iterator<Item=u8>) -> Border {
+            Border::parse(chars).expect("rows were checked above")
+        }
+        Ok(Tile {
             id: tile_id,
             border: [
-                Border::parse(input.first()?.bytes())?,
-                Border::parse(input.iter().map(|line| line.bytes().last().unwrap()))?,
-                Border::parse(input.last()?.bytes())?,
-                Border::parse(input.iter().map(|line| line.bytes().next().unwrap()))?
+                border(input[0].bytes()),
+                border(input.iter().map(|line| line.as_bytes()[TILE_SIZE - 1])),
+                border(input[TILE_SIZE - 1].bytes()),
+                border(input.iter().map(|line| line.as_bytes()[0]))
             ]
         })
     }
 }
 
-pub fn run() {
-    let stdin = io::stdin();
-    let tiles = stdin.lock().lines()
-        .map(|line| line.expect("read error")).collect::<Vec<_>>()
-        .split(|line| line.is_empty())
-        .filter(|split| !split.is_empty())
-        .map(|tile| Tile::parse(&tile).expect("invalid input"))
-        .collect::<Vec<_>>();
+pub struct Part1;
 
-    let corners = tiles.iter().filter(|tile| {
-        // for each border...
-        let matches = tile.border.iter().cloned().flat_map(|border| {
-            // for each other tile...
-            tiles.iter().filter(|other_tile| other_tile.id != tile.id)
-                .zip(std::iter::repeat(border)).flat_map(|(other_tile, border)| 
-                    // for each other border...
-                    other_tile.border.iter().filter(move |other_border|
-                        border.raw == other_border.raw || border.flipped().raw == other_border.raw
-                ).map(move |_| other_tile)
-        )});
+impl Solution for Part1 {
+    type Input = Vec<Tile>;
 
-        // corners are adjacent to exactly 2 other tiles
-        matches.count() == 2
-    }).collect::<Vec<_>>();
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        Ok(common::collect_all(common::input::records(input)
+            .map(|tile| Tile::parse(input, &tile)))?)
+    }
+
+    fn solve(tiles: Self::Input) -> Result<Answer, Error> {
+        let corners = tiles.iter().filter(|tile| {
+            // for each border...
+            let matches = tile.border.iter().cloned().flat_map(|border| {
+                // for each other tile...
+                tiles.iter().filter(|other_tile| other_tile.id != tile.id)
+                    .zip(std::iter::repeat(border)).flat_map(|(other_tile, border)| 
+                        // for each other border...
+                        other_tile.border.iter().filter(move |other_border|
+                            border.raw == other_border.raw || border.flipped().raw == other_border.raw
+                    ).map(move |_| other_tile)
+            )});
 
-    assert_eq!(corners.len(), 4);
-    println!("{}", corners.iter().map(|tile| tile.id as u64).product::<u64>())
+            // corners are adjacent to exactly 2 other tiles
+            matches.count() == 2
+        }).collect::<Vec<_>>();
+
+        if corners.len() != 4 { return Err(Error::NoSolution); }
+        Ok(corners.iter().map(|tile| tile.id as u64).product::<u64>().into())
+    }
 }
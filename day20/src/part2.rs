@@ -1,7 +1,8 @@
-use std::io::{self, BufRead};
 use std::{cell::RefCell, collections::HashMap};
 use lazy_static::lazy_static;
 use regex::Regex;
+use common::{Answer, Error, ErrorKind, ParseError, Solution};
+use grid::Grid;
 
 const TILE_SIZE: usize = 10;
 const CONTENT_SIZE: usize = TILE_SIZE-2;
@@ -71,7 +72,7 @@ impl Border {
 }
 
 #[derive(Debug)]
-struct Tile {
+pub struct Tile {
     id: u32,
     
     /// The edges.
@@ -83,38 +84,48 @@ struct Tile {
 }
 
 impl Tile {
-    fn parse(input: &[String]) -> Option<Tile> {
-        if input.len() != TILE_SIZE + 1 { return None; }
-
-        let id_line = &input[0];
+    /// Parses a tile from `lines`, which must be slices of `input`.
+    fn parse(input: &str, lines: &[&str]) -> Result<Tile, ParseError> {
+        let id_line = lines[0];
         lazy_static! {
             static ref REGEX: Regex = Regex::new(r"^Tile (\d+):$").unwrap();
         }
-        let tile_id = REGEX
-            .captures(&id_line)?.get(1)?
-            .as_str().parse().ok()?;
-
-        let input = &input[1..];
-        if input.iter().find(|line| line.len() != TILE_SIZE) != None { return None; }
+        let tile_id = REGEX.captures(id_line)
+            .ok_or_else(|| ParseError::at(input, id_line, ErrorKind::Expected("'Tile <id>:'")))?;
+        let tile_id = common::parse_number(input, tile_id.get(1).unwrap().as_str())?;
+
+        let rows = &lines[1..];
+        if rows.len() < TILE_SIZE {
+            return Err(ParseError::after(input, lines[lines.len() - 1], ErrorKind::Expected("10 rows of tile data")));
+        } else if rows.len() > TILE_SIZE {
+            return Err(ParseError::at(input, rows[TILE_SIZE], ErrorKind::Expected("a blank line after each tile")));
+        }
+        for row in rows {
+            if let Some((index, c)) = row.char_indices().find(|(_, c)| *c != '#' && *c != '.') {
+                return Err(ParseError::at(input, &row[index..index + c.len_utf8()], ErrorKind::UnexpectedChar(c)));
+            } else if row.len() != TILE_SIZE {
+                return Err(ParseError::at(input, row, ErrorKind::Expected("a row of 10 characters")));
+            }
+        }
+        let input = rows;
+        fn border(chars: impl Iterator<Item=u8>) -> Border {
+            Border::parse(chars).expect("rows were checked above")
+        }
 
         let mut content = [0; CONTENT_SIZE];
         for i in 0..CONTENT_SIZE {
-            content[i] = input[i+1][1..(CONTENT_SIZE+1)].bytes().try_fold(0, |accum, next|
-                Some(accum << 1 | match next {
-                    b'#' => 1,
-                    b'.' => 0,
-                    _ => return None
-                })
-            )?;
+            content[i] = input[i+1][1..(CONTENT_SIZE+1)].bytes().fold(0, |accum, next|
+                accum << 1 | (next == b'#') as u16
+            );
         }
 
-        Some(Tile {
+        Ok(Tile {
             id: tile_id,
             border: [
-                Border::parse(input.first()?.bytes())?,
-                Border::parse(input.iter().map(|line| line.bytes().last().unwrap()))?,
-                Border::parse(input.last()?.bytes())?,
-                Border::parse(input.iter().map(|line| line.bytes().next().unwrap()))?
+                border(input[0].bytes()),
+                border(input.iter().map(|line| line.as_bytes()[TILE_SIZE - 1])),
+                border(input[TILE_SIZE - 1].bytes()),
+                border(input.iter().map(|line| line.as_bytes()[0]))
             ],
             content,
             memoized_matches: RefCell::new(None)
@@ -271,145 +282,134 @@ impl Match {
     }
 }
 
-pub fn run() {
-    let stdin = io::stdin();
-    let mut remaining_tiles = stdin.lock().lines()
-        .map(|line| line.expect("read error")).collect::<Vec<_>>()
-        .split(|line| line.is_empty())
-        .filter(|split| !split.is_empty())
-        .map(|tile| Tile::parse(&tile).expect("invalid input"))
-        .map(|tile| (tile.id, tile))
-        .collect::<HashMap<u32, Tile>>();
-
-    let width = (remaining_tiles.len() as f64).sqrt() as usize;
-    assert_eq!(width*width, remaining_tiles.len());
-
-    // Arbitrarily declare one corner to be the top left
-    let top_left_id = remaining_tiles.values()
-        // corners only match two tiles
-        .find(|tile| tile.matches(&remaining_tiles).iter().filter(|m| m.is_some()).count() == 2)
-        .expect("no corner pieces found").id;
-    let mut top_left = remaining_tiles.remove(&top_left_id).unwrap();
-
-    // Rotate it until the rightmost border is occupied
-    while top_left.matches(&remaining_tiles)[Edge::Right.index()].is_none() {
-        top_left.rotate_cw();
-    }
-    // Flip it so that the bottom border is occupied
-    if top_left.matches(&remaining_tiles)[Edge::Bottom.index()].is_none() {
-        top_left.flip_vert();
+pub struct Part2;
+
+impl Solution for Part2 {
+    /// The tiles, indexed by ID.
+    type Input = HashMap<u32, Tile>;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        let mut tiles = HashMap::new();
+        let mut errors = Vec::new();
+        for lines in common::input::records(input) {
+            match Tile::parse(input, &lines) {
+                Ok(tile) if tiles.contains_key(&tile.id) =>
+                    errors.push(ParseError::at(input, lines[0], ErrorKind::Duplicate("tile ID"))),
+                Ok(tile) => { tiles.insert(tile.id, tile); },
+                Err(e) => errors.push(e)
+            }
+        }
+        if !errors.is_empty() { return Err(errors.into()); }
+
+        let width = (tiles.len() as f64).sqrt() as usize;
+        if width*width != tiles.len() {
+            return Err(Error::InvalidInput(format!("{} tiles can't be arranged into a square", tiles.len())));
+        }
+        Ok(tiles)
     }
 
-    let mut grid = Vec::<Tile>::new();
-    grid.push(top_left);
-    
-    while !remaining_tiles.is_empty() {
-        // Find the next piece and add it to the grid.
-        //
-        // Are we advancing rightwards or downwards?
-        let horizontal = (grid.len() % width) != 0;
-        let prev = if horizontal { grid.last().unwrap() } else { &grid[grid.len()-width] };
-        let dir = if horizontal { Edge::Right } else { Edge::Bottom };
-
-        let next_match = prev.matches(&remaining_tiles)[dir.index()].unwrap();
-        let mut next = remaining_tiles.remove(&next_match.other_tile)
-            .unwrap_or_else(|| panic!("missing tile {}", next_match.other_tile));
-
-        // Orient the new piece correctly.
-        let mut edge_on_next =  next_match.other_edge;
-        let mut next_flipped = next_match.flipped;
-        while edge_on_next != dir.inverse() {
-            next.rotate_cw();
-            edge_on_next = edge_on_next.advanced(1);
-            if [Edge::Top, Edge::Bottom].contains(&edge_on_next) { next_flipped = !next_flipped; }
+    fn solve(mut remaining_tiles: Self::Input) -> Result<Answer, Error> {
+        let width = (remaining_tiles.len() as f64).sqrt() as usize;
+
+        // Declare the corner with the lowest ID to be the top left, so that
+        // the tiles are put together the same way every time
+        let top_left_id = remaining_tiles.values()
+            // corners only match two tiles
+            .filter(|tile| tile.matches(&remaining_tiles).iter().filter(|m| m.is_some()).count() == 2)
+            .min_by_key(|tile| tile.id)
+            .ok_or(Error::NoSolution)?.id;
+        let mut top_left = remaining_tiles.remove(&top_left_id).unwrap();
+
+        // Rotate it until the rightmost border is occupied
+        while top_left.matches(&remaining_tiles)[Edge::Right.index()].is_none() {
+            top_left.rotate_cw();
         }
-        if next_flipped {
-            if horizontal { next.flip_vert(); }
-            else { next.flip_horiz(); }
+        // Flip it so that the bottom border is occupied
+        if top_left.matches(&remaining_tiles)[Edge::Bottom.index()].is_none() {
+            top_left.flip_vert();
         }
-        assert_eq!(&prev.border[dir.index()].raw, &next.border[dir.inverse().index()].raw);
-
-        grid.push(next);
-    }
 
-    // Print the content, for debugging
-    for superrow in 0..width { // width and height are the same;
-        for subrow in 0..CONTENT_SIZE {
-            for tile in grid[superrow*width..(superrow+1)*width].iter() {
-                let mut bits = tile.content[subrow];
-                for _ in 0..CONTENT_SIZE {
-                    bits <<= 1;
-                    print!("{}", if bits & (1 << CONTENT_SIZE) != 0 { '#' } else { '.' })
-                }
-                print!(" ");
+        let mut grid = Vec::<Tile>::new();
+        grid.push(top_left);
+    
+        while !remaining_tiles.is_empty() {
+            // Find the next piece and add it to the grid.
+            //
+            // Are we advancing rightwards or downwards?
+            let horizontal = !grid.len().is_multiple_of(width);
+            let prev = if horizontal { grid.last().unwrap() } else { &grid[grid.len()-width] };
+            let dir = if horizontal { Edge::Right } else { Edge::Bottom };
+
+            let next_match = prev.matches(&remaining_tiles)[dir.index()].ok_or(Error::NoSolution)?;
+            let mut next = remaining_tiles.remove(&next_match.other_tile).ok_or(Error::NoSolution)?;
+
+            // Orient the new piece correctly.
+            let mut edge_on_next =  next_match.other_edge;
+            let mut next_flipped = next_match.flipped;
+            while edge_on_next != dir.inverse() {
+                next.rotate_cw();
+                edge_on_next = edge_on_next.advanced(1);
+                if [Edge::Top, Edge::Bottom].contains(&edge_on_next) { next_flipped = !next_flipped; }
+            }
+            if next_flipped {
+                if horizontal { next.flip_vert(); }
+                else { next.flip_horiz(); }
             }
-            println!();
-        }
-        println!();
-    }
 
-    // Find sea monsters
-    const SEA_MONSTER: [&str; 3] = [
-        "                  # ",
-        "#    ##    ##    ###",
-        " #  #  #  #  #  #   "
-    ];
-
-
-    fn find_sea_monster(
-        x: usize, y: usize,
-        flipped: bool, rotation: i32,
-        grid: &[Tile], width: usize)
-    -> bool {
-        // Look for sea monster at (x, y)
-        for (offs_y, monster_row) in SEA_MONSTER.iter().enumerate() {
-            for (offs_x, c) in monster_row.bytes().enumerate() {
-                let (x, y) = (x as isize, y as isize);
-                let (offs_x, offs_y) = (offs_x as isize, offs_y as isize);
-                // Transform coordinates
-                let (xformed_x, xformed_y) = match rotation {
-                    0 => ( (x + offs_x),  (y + offs_y)),
-                    1 => ( (y + offs_y), -(x + offs_x)),
-                    2 => (-(x + offs_x), -(y + offs_y)),
-                    3 => (-(y + offs_y),  (x + offs_x)),
-                    _ => unreachable!()
+            // A palindromic edge matches whichever way round the piece is, so
+            // the flip above is a guess. Check it against the piece's other
+            // sides: the piece above it, or the edge of the image.
+            let (row, col) = (grid.len() / width, grid.len() % width);
+            let fits = |tile: &Tile| {
+                let top = if row == 0 {
+                    tile.matches(&remaining_tiles)[Edge::Top.index()].is_none()
+                } else {
+                    grid[grid.len()-width].border[Edge::Bottom.index()] == tile.border[Edge::Top.index()]
                 };
-                let xformed_x = if flipped { -xformed_x } else { xformed_x };
-                let (xformed_x, xformed_y) = (
-                    xformed_x.rem_euclid((CONTENT_SIZE*width) as isize) as usize,
-                    xformed_y.rem_euclid((CONTENT_SIZE*width) as isize) as usize
-                );
-
-                let (tile_x, tile_y) = (xformed_x / CONTENT_SIZE, xformed_y / CONTENT_SIZE);
-                if tile_x > width || tile_y > width { return false; }
-
-                let (cell_x, cell_y) = (xformed_x % CONTENT_SIZE, xformed_y % CONTENT_SIZE);
-                let row = grid[tile_y*width + tile_x].content[cell_y];
-                let bit = (row >> (CONTENT_SIZE - 1 - cell_x)) & 1;
-                if c == b'#' && bit == 0 { return false; }
+                let left = if col == 0 {
+                    tile.matches(&remaining_tiles)[Edge::Left.index()].is_none()
+                } else {
+                    grid[grid.len()-1].border[Edge::Right.index()] == tile.border[Edge::Left.index()]
+                };
+                top && left
+            };
+            if !fits(&next) {
+                if horizontal { next.flip_vert(); }
+                else { next.flip_horiz(); }
+                if !fits(&next) { return Err(Error::NoSolution); }
             }
-        }
-        true
-    }
+            assert_eq!(&prev.border[dir.index()].raw, &next.border[dir.inverse().index()].raw);
 
-    let mut monsters = 0;
-    for &flipped in [true, false].iter() {
-        for rotation in 0..4 {
-            for y in 0..(CONTENT_SIZE*width) as usize {
-                for x in 0..(CONTENT_SIZE*width) as usize {
-                    if find_sea_monster(x, y, flipped, rotation, &grid, width) { monsters += 1; }
-                }
-            }
+            grid.push(next);
         }
-    }
-
-    let tiles_per_sea_monster = SEA_MONSTER.iter()
-        .map(|s| s.bytes()).flatten()
-        .filter(|&c| c == b'#').count();
 
-    let total_tiles = grid.iter()
-        .flat_map(|tile| tile.content.iter())
-        .map(|word| word.count_ones()).sum::<u32>() as usize;
+        // Assemble the image, without the tile borders.
+        let size = CONTENT_SIZE*width;
+        let image = Grid::from_fn(size, size, |x, y| {
+            let tile = &grid[(y / CONTENT_SIZE)*width + x / CONTENT_SIZE];
+            let row = tile.content[y % CONTENT_SIZE];
+            (row >> (CONTENT_SIZE - 1 - x % CONTENT_SIZE)) & 1 == 1
+        });
 
-    println!("{}", total_tiles - monsters*tiles_per_sea_monster);
+        // Find sea monsters
+        const SEA_MONSTER: [&str; 3] = [
+            "                  # ",
+            "#    ##    ##    ###",
+            " #  #  #  #  #  #   "
+        ];
+        let monster = Grid::from_rows(SEA_MONSTER.iter().map(|row| row.bytes().map(|c| c == b'#').collect()).collect());
+        let monster_cells = monster.positions().filter(|&position| monster[position]).collect::<Vec<_>>();
+
+        // Look for the monster in every orientation of the image.
+        let monsters = image.orientations().map(|image| {
+            let xs = 0..=image.width().saturating_sub(monster.width());
+            let ys = 0..=image.height().saturating_sub(monster.height());
+            ys.flat_map(|y| xs.clone().map(move |x| (x, y)))
+                .filter(|&(x, y)| monster_cells.iter().all(|&(dx, dy)| image.get((x + dx, y + dy)) == Some(&true)))
+                .count()
+        }).sum::<usize>();
+
+        let total_tiles = image.iter().filter(|&&cell| cell).count();
+        Ok((total_tiles - monsters*monster_cells.len()).into())
+    }
 }
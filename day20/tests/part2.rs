@@ -0,0 +1,11 @@
+use common::Solution;
+use day20::part2::Part2;
+
+#[test]
+fn palindromic_edges() {
+    // Starting from tile 1511, the lowest-numbered corner, the tiles meet along
+    // edges which read the same both ways, so the match alone doesn't say which
+    // way round they go.
+    let tiles = Part2::parse(include_str!("../test2")).unwrap();
+    assert_eq!(Part2::solve(tiles).unwrap().to_string(), "145");
+}
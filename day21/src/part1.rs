@@ -1,25 +1,27 @@
-use std::io::{self, BufRead};
 use std::collections::{HashMap, HashSet};
 use regex::Regex;
 use lazy_static::lazy_static;
+use common::{Answer, Error, ErrorKind, ParseError, Solution};
 
-struct Recipe {
+pub struct Recipe {
     ingredients: HashSet<String>,
     allergens: HashSet<String>
 }
 
 impl Recipe {
-    fn parse(input: &str) -> Option<Recipe> {
+    /// Parses a recipe from `line`, which must be a slice of `input`.
+    fn parse(input: &str, line: &str) -> Result<Recipe, ParseError> {
         lazy_static! {
             static ref REGEX: Regex = Regex::new(r"^((?:\w++ ?)+) \(contains ([\w\s,]+)\)$").unwrap();
         };
 
-        let matches = REGEX.captures(input)?;
+        let matches = REGEX.captures(line)
+            .ok_or_else(|| ParseError::at(input, line, ErrorKind::Expected("'<ingredients> (contains <allergens>)'")))?;
 
-        let ingredients = matches.get(1)?.as_str().split_whitespace();
-        let allergens = matches.get(2)?.as_str().split(", ");
+        let ingredients = matches.get(1).unwrap().as_str().split_whitespace();
+        let allergens = matches.get(2).unwrap().as_str().split(", ");
 
-        Some(Recipe {
+        Ok(Recipe {
             ingredients: ingredients.map(str::to_string).collect(),
             allergens: allergens.map(str::to_string).collect()
         })
@@ -31,36 +33,43 @@ struct Ingredient {
     possible_allergens: HashSet<String>
 }
 
-pub fn run() {
-    let recipes = io::stdin().lock().lines()
-        .map(|line| line.expect("read error"))
-        .map(|line| Recipe::parse(&line).expect("invalid input"))
-        .collect::<Vec<Recipe>>();
+pub struct Part1;
 
-    let mut ingredients = HashMap::<String, Ingredient>::new();
-    for recipe in recipes.iter() {
-        // Add all of this recipe's allergens to each of this recipe's ingredients
-        for ingredient in recipe.ingredients.iter() {
-            let entry = if let Some(entry) = ingredients.get_mut(ingredient) { entry }
-                        else { &mut *ingredients.entry(ingredient.to_string()).or_default() };
-            entry.possible_allergens.extend(recipe.allergens.iter().cloned());
-        }
-    }
+impl Solution for Part1 {
+    type Input = Vec<Recipe>;
 
-    for (ingredient_name, ingredient) in ingredients.iter_mut() {
-        // If a recipe contains one of our possible allergens but doesn't contain us,
-        // then remove that allergen from our list of possibilities.
-        recipes.iter()
-            .filter(|recipe| !recipe.ingredients.contains(ingredient_name))
-            .flat_map(|recipe| recipe.allergens.iter())
-            .for_each(|impossible_allergen| { ingredient.possible_allergens.remove(impossible_allergen); });
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        Ok(common::collect_all(input.lines()
+            .map(|line| Recipe::parse(input, line)))?)
     }
 
-    // Find occurences of ingredients with no possible allergens
-    let result = recipes.iter()
-        .flat_map(|recipe| recipe.ingredients.iter())
-        .map(|name| ingredients.get(name).expect("missing ingredient"))
-        .filter(|ingredient| ingredient.possible_allergens.is_empty())
-        .count();
-    println!("{}", result);
+    fn solve(recipes: Self::Input) -> Result<Answer, Error> {
+
+        let mut ingredients = HashMap::<String, Ingredient>::new();
+        for recipe in recipes.iter() {
+            // Add all of this recipe's allergens to each of this recipe's ingredients
+            for ingredient in recipe.ingredients.iter() {
+                let entry = if let Some(entry) = ingredients.get_mut(ingredient) { entry }
+                            else { &mut *ingredients.entry(ingredient.to_string()).or_default() };
+                entry.possible_allergens.extend(recipe.allergens.iter().cloned());
+            }
+        }
+
+        for (ingredient_name, ingredient) in ingredients.iter_mut() {
+            // If a recipe contains one of our possible allergens but doesn't contain us,
+            // then remove that allergen from our list of possibilities.
+            recipes.iter()
+                .filter(|recipe| !recipe.ingredients.contains(ingredient_name))
+                .flat_map(|recipe| recipe.allergens.iter())
+                .for_each(|impossible_allergen| { ingredient.possible_allergens.remove(impossible_allergen); });
+        }
+
+        // Find occurences of ingredients with no possible allergens
+        let result = recipes.iter()
+            .flat_map(|recipe| recipe.ingredients.iter())
+            .map(|name| &ingredients[name])
+            .filter(|ingredient| ingredient.possible_allergens.is_empty())
+            .count();
+        Ok(result.into())
+    }
 }
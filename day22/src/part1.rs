@@ -1,51 +1,68 @@
-use std::io::{self, BufRead};
-use std::collections::VecDeque;
-
-pub fn run() {
-    let stdin = io::stdin();
-    let input = stdin.lock().lines()
-        .collect::<Result<Vec<_>, _>>()
-        .expect("read error");
-
-    let mut input = input
-        .split(|line| line.is_empty())
-        .map(|player|
-            player.iter().skip(1)
-            .map(|line| line.parse::<i32>().expect("invalid input"))
-            .collect::<VecDeque<i32>>()
-    );
-
-    let (mut p1, mut p2) = (
-        input.next().unwrap(),
-        input.next().unwrap()
-    );
-    assert!(input.next().is_none());
-
-    while let (Some(&c1), Some(&c2)) = (p1.front(), p2.front()) {
-        p1.pop_front();
-        p2.pop_front();
-
-        match c1.cmp(&c2) {
-            std::cmp::Ordering::Greater => {
-                p1.push_back(c1);
-                p1.push_back(c2);
-            },
-            std::cmp::Ordering::Less => {
-                p2.push_back(c2);
-                p2.push_back(c1);
-            },
-            std::cmp::Ordering::Equal => {
-                todo!("tie");
-            }
+use std::collections::{HashSet, VecDeque};
+use common::{Answer, Error, ErrorKind, ParseError, Solution};
+
+/// Parses both players' decks. Every card must be a distinct positive number, so that no round can end in a tie.
+fn parse_decks(input: &str) -> Result<(VecDeque<i32>, VecDeque<i32>), Vec<ParseError>> {
+    let players = common::input::records(input).collect::<Vec<_>>();
+    if players.len() < 2 {
+        return Err(ParseError::after(input, input, ErrorKind::Expected("a deck for each player")).into());
+    } else if players.len() > 2 {
+        return Err(ParseError::at(input, players[2][0], ErrorKind::Expected("only two players")).into());
+    }
+
+    let mut seen = HashSet::new();
+    let mut errors = Vec::new();
+    let mut decks = players.iter().enumerate().map(|(index, player)| {
+        if player[0] != format!("Player {}:", index + 1) {
+            errors.push(ParseError::at(input, player[0], ErrorKind::Expected("a 'Player <n>:' header")));
         }
+        common::collect_all(player.iter().skip(1).map(|line| {
+            let card = common::parse_number::<i32>(input, line)?;
+            if card <= 0 { return Err(ParseError::at(input, line, ErrorKind::OutOfRange)); }
+            if !seen.insert(card) { return Err(ParseError::at(input, line, ErrorKind::Duplicate("card"))); }
+            Ok(card)
+        })).unwrap_or_else(|e| { errors.extend(e); VecDeque::new() })
+    }).collect::<Vec<VecDeque<i32>>>();
+
+    if !errors.is_empty() { return Err(errors); }
+    let p2 = decks.pop().unwrap();
+    let p1 = decks.pop().unwrap();
+    Ok((p1, p2))
+}
+
+pub struct Part1;
+
+impl Solution for Part1 {
+    /// Each player's deck.
+    type Input = (VecDeque<i32>, VecDeque<i32>);
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        Ok(parse_decks(input)?)
     }
 
-    let winning_player;
-    if p1.is_empty() { winning_player = p2; }
-    else { winning_player = p1; }
+    fn solve((mut p1, mut p2): Self::Input) -> Result<Answer, Error> {
+        while let (Some(&c1), Some(&c2)) = (p1.front(), p2.front()) {
+            p1.pop_front();
+            p2.pop_front();
 
-    let score: i32 = winning_player.into_iter().rev()
-        .enumerate().map(|(index, card)| (index as i32 + 1) * card)
-        .sum();
-    println!("{}", score);
+            match c1.cmp(&c2) {
+                std::cmp::Ordering::Greater => {
+                    p1.push_back(c1);
+                    p1.push_back(c2);
+                },
+                std::cmp::Ordering::Less => {
+                    p2.push_back(c2);
+                    p2.push_back(c1);
+                },
+                std::cmp::Ordering::Equal => unreachable!("cards are checked for duplicates while parsing")
+            }
+        }
+
+        let winning_player = if p1.is_empty() { p2 } else { p1 };
+
+        let score: i32 = winning_player.into_iter().rev()
+            .enumerate().map(|(index, card)| (index as i32 + 1) * card)
+            .sum();
+        Ok(score.into())
+    }
 }
@@ -1,8 +1,8 @@
-use std::io::{self, BufRead};
 use std::collections::{HashSet, VecDeque};
+use common::{Answer, Error, ErrorKind, ParseError, Solution};
 
 #[derive(Debug, Clone, Hash, Eq, PartialEq)]
-struct GameState {
+pub struct GameState {
     p1: VecDeque<i32>,
     p2: VecDeque<i32>
 }
@@ -63,7 +63,7 @@ impl GameState {
                 } else { match c1.cmp(&c2) {
                     std::cmp::Ordering::Greater => Player::P1,
                     std::cmp::Ordering::Less => Player::P2,
-                    _ => panic!("tie")
+                    _ => unreachable!("cards are checked for duplicates while parsing")
                 }}
             };
 
@@ -83,23 +83,46 @@ impl GameState {
     }
 }
 
-pub fn run() {
-    let stdin = io::stdin();
-    let input = stdin.lock().lines()
-        .collect::<Result<Vec<_>, _>>()
-        .expect("read error");
-
-    let mut input = input
-        .split(|line| line.is_empty())
-        .map(|player|
-            player.iter().skip(1)
-            .map(|line| line.parse::<i32>().expect("invalid input"))
-            .collect::<VecDeque<i32>>()
-    );
-
-    let game = GameState {
-        p1: input.next().unwrap(),
-        p2: input.next().unwrap()
-    };
-    println!("{}", game.run_game().1);
+/// Parses both players' decks. Every card must be a distinct positive number, so that no round can end in a tie.
+fn parse_decks(input: &str) -> Result<(VecDeque<i32>, VecDeque<i32>), Vec<ParseError>> {
+    let players = common::input::records(input).collect::<Vec<_>>();
+    if players.len() < 2 {
+        return Err(ParseError::after(input, input, ErrorKind::Expected("a deck for each player")).into());
+    } else if players.len() > 2 {
+        return Err(ParseError::at(input, players[2][0], ErrorKind::Expected("only two players")).into());
+    }
+
+    let mut seen = HashSet::new();
+    let mut errors = Vec::new();
+    let mut decks = players.iter().enumerate().map(|(index, player)| {
+        if player[0] != format!("Player {}:", index + 1) {
+            errors.push(ParseError::at(input, player[0], ErrorKind::Expected("a 'Player <n>:' header")));
+        }
+        common::collect_all(player.iter().skip(1).map(|line| {
+            let card = common::parse_number::<i32>(input, line)?;
+            if card <= 0 { return Err(ParseError::at(input, line, ErrorKind::OutOfRange)); }
+            if !seen.insert(card) { return Err(ParseError::at(input, line, ErrorKind::Duplicate("card"))); }
+            Ok(card)
+        })).unwrap_or_else(|e| { errors.extend(e); VecDeque::new() })
+    }).collect::<Vec<VecDeque<i32>>>();
+
+    if !errors.is_empty() { return Err(errors); }
+    let p2 = decks.pop().unwrap();
+    let p1 = decks.pop().unwrap();
+    Ok((p1, p2))
+}
+
+pub struct Part2;
+
+impl Solution for Part2 {
+    type Input = GameState;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        let (p1, p2) = parse_decks(input)?;
+        Ok(GameState { p1, p2 })
+    }
+
+    fn solve(game: Self::Input) -> Result<Answer, Error> {
+        Ok(game.run_game().1.into())
+    }
 }
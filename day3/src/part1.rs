@@ -1,21 +1,25 @@
-use std::io::{self, BufRead};
+use common::{Answer, Error, Solution};
+use grid::Grid;
 
-pub fn run() {
-    // The horizontal position on the map.
-    let mut x_pos = 0;
-    let mut trees = 0;
-    let mut line_length: Option<usize> = None;
-    for line in io::stdin().lock().lines() {
-        let line = line.expect("read error");
+pub struct Part1;
 
-        if let Some(length) = line_length {
-            assert_eq!(length, line.len(), "mismatched line lengths");
-        } else {
-            line_length = Some(line.len());
-        }
+impl Solution for Part1 {
+    /// The map, with true for each tree.
+    type Input = Grid<bool>;
 
-        if line.as_bytes()[x_pos] == '#' as u8 { trees += 1; }
-        x_pos = (x_pos + 3) % line.len();
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        Ok(Grid::parse(input, |c| match c {
+            '.' => Some(false),
+            '#' => Some(true),
+            _ => None
+        })?)
+    }
+
+    fn solve(map: Self::Input) -> Result<Answer, Error> {
+        // The map repeats to the right, so wrap around horizontally.
+        let trees = (0..map.height())
+            .filter(|&y| *map.get_wrapping(((y*3) as isize, y as isize)))
+            .count();
+        Ok(trees.into())
     }
-    println!("{}", trees);
 }
@@ -1,46 +1,38 @@
-use std::io::{self, BufRead};
+use common::{Answer, Error, Solution};
+use grid::Grid;
 
-pub fn run() {
-    // The horizontal position on the map.
-    let lines = io::stdin().lock().lines()
-        .map(|line| line.expect("read error")).collect();
-    let trees = [
-        (1,1),
-        (3,1),
-        (5,1),
-        (7,1),
-        (1,2)
-    ].iter()
-        .map(|(x, y)| check_slope(&lines, *x, *y))
-        .fold(1, |accum, next| accum*next);
+pub struct Part2;
 
-    println!("{:?}", trees);
-}
-
-fn check_slope(lines: &Vec<String>, x_slope: usize, y_slope: usize) -> usize {
-    // The current horizontal position.
-    let mut x_pos = 0;
-
-    // The number of lines to skip vertically before the next one we check.
-    let mut y_skip = 0;
+impl Solution for Part2 {
+    /// The map, with true for each tree.
+    type Input = Grid<bool>;
 
-    let mut trees = 0;
-    let mut line_length: Option<usize> = None;
-
-    for line in lines {
-        if let Some(length) = line_length {
-            assert_eq!(length, line.len(), "mismatched line lengths");
-        } else {
-            line_length = Some(line.len());
-        }
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        Ok(Grid::parse(input, |c| match c {
+            '.' => Some(false),
+            '#' => Some(true),
+            _ => None
+        })?)
+    }
 
-        if y_skip == 0 {
-            if line.as_bytes()[x_pos as usize] == '#' as u8 { trees += 1; }
-            x_pos = (x_pos + x_slope) % line.len() as usize;
-            y_skip = y_slope;
-        }
-        y_skip -= 1;
+    fn solve(map: Self::Input) -> Result<Answer, Error> {
+        let trees = [
+            (1,1),
+            (3,1),
+            (5,1),
+            (7,1),
+            (1,2)
+        ].iter()
+            .map(|(x, y)| check_slope(&map, *x, *y))
+            .product::<usize>();
+
+        Ok(trees.into())
     }
+}
 
-    trees
+fn check_slope(map: &Grid<bool>, x_slope: usize, y_slope: usize) -> usize {
+    // The map repeats to the right, so wrap around horizontally.
+    (0..map.height()).step_by(y_slope).enumerate()
+        .filter(|&(step, y)| *map.get_wrapping(((step*x_slope) as isize, y as isize)))
+        .count()
 }
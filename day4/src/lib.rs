@@ -0,0 +1,11 @@
+use common::Example;
+
+pub mod part1;
+pub mod part2;
+
+/// The examples from the puzzle description.
+pub const EXAMPLES: &[Example] = &[
+    Example { input: include_str!("../test"), answers: [Some("2"), Some("2")] },
+    Example { input: include_str!("../test2"), answers: [None, Some("0")] },
+    Example { input: include_str!("../test3"), answers: [None, Some("4")] },
+];
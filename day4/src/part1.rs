@@ -1,29 +1,46 @@
-use std::io::{self, BufRead};
 use std::collections::HashSet;
+use common::{Answer, Error, ErrorKind, ParseError, Solution};
 
-pub fn run() {
-    let valid = io::stdin().lock().lines()
-        .map(|line| line.expect("read error")).collect::<Vec<_>>()
-        .split(|line| line.is_empty())
-        .filter(|passport| is_valid(passport)).count();
-    println!("{}", valid);
+pub struct Part1;
+
+/// A list of (name, value) fields.
+pub type Passport = Vec<(String, String)>;
+
+impl Solution for Part1 {
+    type Input = Vec<Passport>;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        Ok(common::collect_all(common::input::records(input)
+            .map(|passport| parse_passport(input, &passport)))?)
+    }
+
+    fn solve(input: Self::Input) -> Result<Answer, Error> {
+        Ok(input.iter().filter(|passport| is_valid(passport)).count().into())
+    }
 }
 
-pub fn is_valid(passport: &[String]) -> bool {
+fn parse_passport(input: &str, passport: &[&str]) -> Result<Passport, ParseError> {
     // parse into list of (name, value)
-    let passport = passport.join(" ");
-    let fields = passport.split_whitespace()
-        .map(|field| field.splitn(2, ":"))
-        .map(|mut items| (items.nth(0).expect("invalid field"), items.nth(0).expect("invalid field")));
+    passport.iter()
+        .flat_map(|line| line.split_whitespace())
+        .map(|field| {
+            let mut items = field.splitn(2, ':');
+            match (items.next(), items.next()) {
+                (Some(name), Some(value)) => Ok((name.to_string(), value.to_string())),
+                _ => Err(ParseError::at(input, field, ErrorKind::Expected("a field of the form 'name:value'")))
+            }
+        }).collect()
+}
 
+pub fn is_valid(passport: &[(String, String)]) -> bool {
     // Required fields we haven't seen yet
     let mut required: HashSet<_> = [
         "byr", "iyr", "eyr", "hgt",
         "hcl", "ecl", "pid", 
-    ].iter().collect();
+    ].iter().cloned().collect();
 
-    for (name, _) in fields {
-        if !required.remove(&name) && name != "cid" { return false; }
+    for (name, _) in passport {
+        if !required.remove(name.as_str()) && name != "cid" { return false; }
     }
     required.is_empty()
 }
@@ -1,47 +1,65 @@
-use std::io::{self, BufRead};
 use std::collections::HashMap;
+use common::{Answer, Error, ErrorKind, ParseError, Solution};
 
-pub fn run() {
-    let valid = io::stdin().lock().lines()
-        .map(|line| line.expect("read error")).collect::<Vec<_>>()
-        .split(|line| line.is_empty())
-        .filter(|passport| is_valid(passport)).count();
-    println!("{}", valid);
+pub struct Part2;
+
+/// A list of (name, value) fields.
+pub type Passport = Vec<(String, String)>;
+
+impl Solution for Part2 {
+    type Input = Vec<Passport>;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        Ok(common::collect_all(common::input::records(input)
+            .map(|passport| parse_passport(input, &passport)))?)
+    }
+
+    fn solve(input: Self::Input) -> Result<Answer, Error> {
+        Ok(input.iter().filter(|passport| is_valid(passport)).count().into())
+    }
 }
 
-pub fn is_valid(passport: &[String]) -> bool {
+fn parse_passport(input: &str, passport: &[&str]) -> Result<Passport, ParseError> {
     // parse into list of (name, value)
-    let passport = passport.join(" ");
-    let fields = passport.split_whitespace()
-        .map(|field| field.splitn(2, ":"))
-        .map(|mut items| (items.nth(0).expect("invalid field"), items.nth(0).expect("invalid field")));
+    passport.iter()
+        .flat_map(|line| line.split_whitespace())
+        .map(|field| {
+            let mut items = field.splitn(2, ':');
+            match (items.next(), items.next()) {
+                (Some(name), Some(value)) => Ok((name.to_string(), value.to_string())),
+                _ => Err(ParseError::at(input, field, ErrorKind::Expected("a field of the form 'name:value'")))
+            }
+        }).collect()
+}
 
+pub fn is_valid(passport: &[(String, String)]) -> bool {
     // Fields and their validators
     type Validator = fn(&str) -> bool;
     let required: Vec<(&str, Validator)> = vec![
-        ("byr", |field: &str| field.len() == 4 && (1920..=2002).contains(&field.parse().unwrap())),
-        ("iyr", |field: &str| field.len() == 4 && (2010..=2020).contains(&field.parse().unwrap())),
-        ("eyr", |field: &str| field.len() == 4 && (2020..=2030).contains(&field.parse().unwrap())),
-        ("hgt", |field: &str| match (&field[0..field.len()-2].parse::<i32>(), &field[field.len()-2..]) {
-            (Err(_), _) => false,
-            (Ok(150..=193), "cm") => true,
-            (Ok(59..=76), "in") => true,
-            _ => false
+        ("byr", |field: &str| field.len() == 4 && matches!(field.parse::<i32>(), Ok(1920..=2002))),
+        ("iyr", |field: &str| field.len() == 4 && matches!(field.parse::<i32>(), Ok(2010..=2020))),
+        ("eyr", |field: &str| field.len() == 4 && matches!(field.parse::<i32>(), Ok(2020..=2030))),
+        ("hgt", |field: &str| if let Some(height) = field.strip_suffix("cm") {
+            matches!(height.parse::<i32>(), Ok(150..=193))
+        } else if let Some(height) = field.strip_suffix("in") {
+            matches!(height.parse::<i32>(), Ok(59..=76))
+        } else {
+            false
         }),
         ("hcl", |field: &str| field.len() == 7 && {
             let mut bytes = field.bytes();
             // String starts with # and only contains 0-9, a-f
-            bytes.nth(0) == Some(b'#') && 
-                bytes.filter(|c| "0123456789abcdef".bytes().find(|x| x == c).is_none()).nth(0).is_none()
+            bytes.next() == Some(b'#') && 
+                bytes.find(|c| "0123456789abcdef".bytes().find(|x| x == c).is_none()).is_none()
         }),
         ("ecl", |field: &str| ["amb", "blu", "brn", "gry", "grn", "hzl", "oth"].contains(&field)),
         ("pid", |field: &str| field.len() == 9 && field.parse::<u32>().is_ok()),
     ];
     let mut required: HashMap<_, _> = required.into_iter().collect();
 
-    for (name, value) in fields {
-        match required.remove(&name) {
-            Some(validator) => if !validator(&value) { println!("{}:{}", name, value); return false; }
+    for (name, value) in passport {
+        match required.remove(name.as_str()) {
+            Some(validator) => if !validator(value) { return false; }
             None => if name != "cid" { return false; }
         }
     }
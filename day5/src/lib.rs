@@ -0,0 +1,12 @@
+use common::Example;
+
+pub mod part1;
+pub mod part2;
+
+/// Example inputs with known answers.
+pub const EXAMPLES: &[Example] = &[
+    // The puzzle gives no example for part 2, so test2 is a run of seats
+    // with ID 105 missing.
+    Example { input: include_str!("../test"), answers: [Some("820"), None] },
+    Example { input: include_str!("../test2"), answers: [Some("110"), Some("105")] },
+];
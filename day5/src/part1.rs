@@ -1,15 +1,30 @@
-use std::io::{self, BufRead};
-
-pub fn run() {
-    let stdin = io::stdin();
-    let ids = stdin.lock().lines().map(|line|
-        line.expect("read error").chars().fold(0, |n, c|
-            (n << 1) | match c {
-                'F'|'L' => 0,
-                'B'|'R' => 1,
-                _ => panic!("invalid char '{}'", c)
+use common::{Answer, Error, ErrorKind, ParseError, Solution};
+
+pub struct Part1;
+
+impl Solution for Part1 {
+    type Input = Vec<usize>;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        let passes: Vec<_> = common::collect_all(input.lines().map(|line| {
+            if line.len() != 10 {
+                return Err(ParseError::at(input, line, ErrorKind::Expected("a 10-character boarding pass")));
             }
-        )
-    );
-    println!("{}", ids.max().expect("no boarding passes in input"));
+            line.char_indices().try_fold(0, |n, (index, c)|
+                Ok((n << 1) | match c {
+                    'F'|'L' => 0,
+                    'B'|'R' => 1,
+                    _ => return Err(ParseError::at(input, &line[index..index + c.len_utf8()], ErrorKind::UnexpectedChar(c)))
+                })
+            )
+        }))?;
+        if passes.is_empty() {
+            return Err(Error::InvalidInput("no boarding passes in input".to_string()));
+        }
+        Ok(passes)
+    }
+
+    fn solve(input: Self::Input) -> Result<Answer, Error> {
+        Ok(input.into_iter().max().ok_or(Error::NoSolution)?.into())
+    }
 }
@@ -1,22 +1,32 @@
-use std::io::{self, BufRead};
+use common::{Answer, Error, ErrorKind, ParseError, Solution};
 
-pub fn run() {
-    const MAX: usize = 1024; // asume no ID is higher than 1024
+pub struct Part2;
 
-    let stdin = io::stdin();
-    let ids = stdin.lock().lines().map(|line|
-        line.expect("read error").chars().fold(0, |n, c|
-            (n << 1) | match c {
-                'F'|'L' => 0,
-                'B'|'R' => 1,
-                _ => panic!("invalid char '{}'", c)
+impl Solution for Part2 {
+    type Input = Vec<usize>;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        Ok(common::collect_all(input.lines().map(|line| {
+            if line.len() != 10 {
+                return Err(ParseError::at(input, line, ErrorKind::Expected("a 10-character boarding pass")));
             }
-        )
-    );
+            line.char_indices().try_fold(0, |n, (index, c)|
+                Ok((n << 1) | match c {
+                    'F'|'L' => 0,
+                    'B'|'R' => 1,
+                    _ => return Err(ParseError::at(input, &line[index..index + c.len_utf8()], ErrorKind::UnexpectedChar(c)))
+                })
+            )
+        }))?)
+    }
+
+    fn solve(input: Self::Input) -> Result<Answer, Error> {
+        const MAX: usize = 1024; // asume no ID is higher than 1024
 
-    let mut exists = [false; MAX];
-    ids.for_each(|id| exists[id] = true);
+        let mut exists = [false; MAX];
+        input.into_iter().for_each(|id| exists[id] = true);
 
-    let id = (1..MAX-1).find(|id| !exists[*id] && exists[*id-1] && exists[*id+1]);
-    println!("{}", id.expect("no matching ID found"));
+        let id = (1..MAX-1).find(|id| !exists[*id] && exists[*id-1] && exists[*id+1]);
+        Ok(id.ok_or(Error::NoSolution)?.into())
+    }
 }
@@ -1,12 +1,22 @@
-use std::io::{self, BufRead};
 use std::collections::HashSet;
+use common::{Answer, Error, Solution};
 
-pub fn run() {
-    let stdin = io::stdin();
-    let lines = stdin.lock().lines().map(|line| line.expect("read error")).collect::<Vec<_>>();
-    let groups = lines.split(|line| line.is_empty());
-    let result: usize = groups.map(count_yes).sum();
-    println!("{}", result)
+pub struct Part2;
+
+impl Solution for Part2 {
+    /// Each group's answers, one line per person.
+    type Input = Vec<Vec<String>>;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        Ok(common::input::records(input)
+            .map(|group| group.into_iter().map(str::to_string).collect())
+            .collect())
+    }
+
+    fn solve(input: Self::Input) -> Result<Answer, Error> {
+        let result: usize = input.iter().map(|group| count_yes(group)).sum();
+        Ok(result.into())
+    }
 }
 
 /// Returns the number of questions for which anyone in the group answers "yes".
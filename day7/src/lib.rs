@@ -0,0 +1,10 @@
+use common::Example;
+
+pub mod part1;
+pub mod part2;
+
+/// The examples from the puzzle description.
+pub const EXAMPLES: &[Example] = &[
+    Example { input: include_str!("../test"), answers: [Some("4"), Some("32")] },
+    Example { input: include_str!("../test2"), answers: [None, Some("126")] },
+];
@@ -1,50 +1,87 @@
-use std::io::{self, BufRead};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use common::{Answer, Error, ErrorKind, ParseError, Solution};
 
-struct Bag {
-    color: String,
+pub struct Bag {
     contents: Vec<(usize, String)>
 }
 
 impl Bag {
-    fn parse(input: &str) -> Bag {
-        lazy_static::lazy_static! {
-            static ref OUTER: regex::Regex = regex::Regex::new(r"^(\w+ \w+) bags contain (.*)$").unwrap();
-            static ref INNER: regex::Regex = regex::Regex::new(r"^\s*(\d+) (\w+ \w+) bags?(?:\s*|\.)$").unwrap();
+    /// Parses every bag in the input, checking that no bag is described twice
+    /// and that every bag's contents are described somewhere.
+    fn parse_all(input: &str) -> Result<HashMap<String, Bag>, Vec<ParseError>> {
+        let mut errors = Vec::new();
+        let rules = input.lines()
+            .filter_map(|line| parse_rule(input, line).map_err(|e| errors.push(e)).ok())
+            .collect::<Vec<_>>();
+        // A bag whose rule failed to parse would show up as unknown everywhere it's contained.
+        let malformed = !errors.is_empty();
+
+        let mut colors = HashSet::new();
+        for (color, _) in &rules {
+            if !colors.insert(*color) {
+                errors.push(ParseError::at(input, color, ErrorKind::Duplicate("bag color")));
+            }
+        }
+        if !malformed {
+            errors.extend(rules.iter()
+                .flat_map(|(_, contents)| contents)
+                .filter(|(_, inner)| !colors.contains(inner))
+                .map(|(_, unknown)| ParseError::at(input, unknown, ErrorKind::Unknown("bag color"))));
         }
+        if !errors.is_empty() { return Err(errors); }
 
-        let outer = OUTER.captures(&input).unwrap_or_else(|| panic!("invalid line {}", input));
-        let color = outer.get(1).unwrap().as_str().to_string();
-        let contents: Vec<(usize, String)> = outer.get(2).unwrap().as_str()
-            .split(',').filter_map(|item|
-                match item {
-                    "no other bags." => None,
-                    _ =>  {
-                        let inner = INNER.captures(&item)
-                            .unwrap_or_else(|| panic!("invalid item '{}' in line '{}'", item, input));
-                        Some((inner.get(1).unwrap().as_str().parse::<usize>().unwrap(), // quantity 
-                         inner.get(2).unwrap().as_str().to_string()))
-                    }
-                }
-            ).collect();
-        Bag { color, contents }
+        Ok(rules.into_iter().map(|(color, contents)| {
+            let contents = contents.into_iter()
+                .map(|(quantity, inner)| (quantity, inner.to_string()))
+                .collect();
+            (color.to_string(), Bag { contents })
+        }).collect())
     }
 
     fn num_contained(&self, all_bags: &HashMap<String, Bag>) -> usize {
         self.contents.iter().fold(1, |accum, (count, color)|
-            accum + count*all_bags.get(color).unwrap_or_else(|| panic!("color {} missing", color))
-                .num_contained(&all_bags)
+            accum + count*all_bags[color].num_contained(all_bags)
         )
     }
 }
 
-pub fn run() {
-    let stdin = io::stdin();
-    let bags: HashMap<String, Bag> = stdin.lock().lines().map(|line| {
-        let bag = Bag::parse(&line.expect("read error"));
-        (bag.color.clone(), bag)
-    }).collect();
-    // -1 because, once again, the shiny gold bag isn't included :/
-    let result = bags.get("shiny gold").expect("no shiny gold bag").num_contained(&bags) - 1;
-    println!("{}", result);
+/// A bag's color, along with the quantity and color of each bag inside it.
+type Rule<'a> = (&'a str, Vec<(usize, &'a str)>);
+
+/// Parses a line of the input into a rule.
+fn parse_rule<'a>(input: &str, line: &'a str) -> Result<Rule<'a>, ParseError> {
+    lazy_static::lazy_static! {
+        static ref OUTER: regex::Regex = regex::Regex::new(r"^(\w+ \w+) bags contain (.*)$").unwrap();
+        static ref INNER: regex::Regex = regex::Regex::new(r"^\s*(\d+) (\w+ \w+) bags?(?:\s*|\.)$").unwrap();
+    }
+
+    let outer = OUTER.captures(line)
+        .ok_or_else(|| ParseError::at(input, line, ErrorKind::Expected("'<color> bags contain <contents>'")))?;
+    let color = outer.get(1).unwrap().as_str();
+    let contents = outer.get(2).unwrap().as_str()
+        .split(',').filter(|item| *item != "no other bags.")
+        .map(|item| {
+            let inner = INNER.captures(item)
+                .ok_or_else(|| ParseError::at(input, item, ErrorKind::Expected("'<quantity> <color> bags'")))?;
+            Ok((common::parse_number(input, inner.get(1).unwrap().as_str())?, // quantity
+                inner.get(2).unwrap().as_str()))
+        }).collect::<Result<_, _>>()?;
+    Ok((color, contents))
+}
+
+pub struct Part2;
+
+impl Solution for Part2 {
+    /// Each bag's rules, indexed by color.
+    type Input = HashMap<String, Bag>;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        Ok(Bag::parse_all(input)?)
+    }
+
+    fn solve(bags: Self::Input) -> Result<Answer, Error> {
+        // -1 because, once again, the shiny gold bag isn't included :/
+        let result = bags.get("shiny gold").ok_or(Error::NoSolution)?.num_contained(&bags) - 1;
+        Ok(result.into())
+    }
 }
@@ -0,0 +1,8 @@
+use common::{Error, Solution};
+use day7::part1::Part1;
+
+#[test]
+fn no_shiny_gold_bag() {
+    let bags = Part1::parse("light red bags contain no other bags.\n").unwrap();
+    assert!(matches!(Part1::solve(bags), Err(Error::NoSolution)));
+}
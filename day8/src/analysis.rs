@@ -0,0 +1,184 @@
+//! Finds out how a program behaves without running it.
+//!
+//! Every instruction has exactly one successor, so the control-flow graph is a set of paths which
+//! each end in a cycle, at the end of the program, or at a jump out of the program. The program's
+//! behaviour is decided entirely by which of those its first instruction leads to.
+
+use std::fmt::{self, Write};
+use crate::program::Instruction;
+
+/// What happens when a program is run.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    /// The program terminates by reaching the end.
+    Terminates,
+
+    /// The program loops forever, starting from the given instruction.
+    Loops(usize),
+
+    /// The program terminates when the instruction at the given position jumps outside of the
+    /// program, to the given position.
+    JumpsOut(usize, i64)
+}
+
+/// A summary of a program's control flow.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Analysis {
+    /// The instructions which can never run, in order.
+    pub unreachable: Vec<usize>,
+
+    /// The jumps which go outside of the program (other than to just past the end),
+    /// along with where they go.
+    pub out_of_range: Vec<(usize, i64)>,
+
+    /// Every cycle in the control-flow graph, each in the order it runs starting from its first
+    /// instruction in the program, ordered by where they start.
+    pub cycles: Vec<Vec<usize>>,
+
+    pub outcome: Outcome
+}
+
+impl Analysis {
+    /// Whether the program is guaranteed to terminate.
+    pub fn terminates(&self) -> bool {
+        !matches!(self.outcome, Outcome::Loops(_))
+    }
+}
+
+/// The instruction which runs after the one at `index`, or None if the program ends there.
+fn successor(program: &[Instruction], index: usize) -> Option<usize> {
+    program[index].successor(index, program.len())
+}
+
+/// Finds every cycle in the control-flow graph.
+fn cycles(program: &[Instruction]) -> Vec<Vec<usize>> {
+    #[derive(Copy, Clone, PartialEq, Eq)]
+    enum State { Unvisited, OnPath, Done }
+
+    let mut state = vec![State::Unvisited; program.len()];
+    let mut cycles = Vec::new();
+    for start in 0..program.len() {
+        // Follow the path from here until it reaches somewhere already visited.
+        let mut path = Vec::new();
+        let mut current = Some(start);
+        while let Some(index) = current.filter(|&index| state[index] == State::Unvisited) {
+            state[index] = State::OnPath;
+            path.push(index);
+            current = successor(program, index);
+        }
+        // If it ran into itself, the end of the path is a new cycle.
+        if let Some(index) = current.filter(|&index| state[index] == State::OnPath) {
+            let entry = path.iter().position(|&i| i == index).expect("the instruction is on the path");
+            let mut cycle = path[entry..].to_vec();
+            let first = (0..cycle.len()).min_by_key(|&i| cycle[i]).expect("cycles are never empty");
+            cycle.rotate_left(first);
+            cycles.push(cycle);
+        }
+        path.into_iter().for_each(|index| state[index] = State::Done);
+    }
+    cycles.sort_unstable_by_key(|cycle| cycle[0]);
+    cycles
+}
+
+/// Analyzes a program's control flow.
+pub fn analyze(program: &[Instruction]) -> Analysis {
+    let out_of_range = program.iter().enumerate()
+        .map(|(index, instruction)| (index, instruction.next(index)))
+        .filter(|&(_, next)| next < 0 || next > program.len() as i64)
+        .collect();
+
+    // Since there are no branches, the reachable instructions are the ones the program runs.
+    let mut reachable = vec![false; program.len()];
+    let mut index = 0;
+    let outcome = loop {
+        if program.is_empty() { break Outcome::Terminates; }
+        if reachable[index] { break Outcome::Loops(index); }
+        reachable[index] = true;
+        match successor(program, index) {
+            Some(next) => index = next,
+            None => match program[index].next(index) {
+                next if next == program.len() as i64 => break Outcome::Terminates,
+                next => break Outcome::JumpsOut(index, next)
+            }
+        }
+    };
+
+    Analysis {
+        unreachable: (0..program.len()).filter(|&index| !reachable[index]).collect(),
+        out_of_range,
+        cycles: cycles(program),
+        outcome
+    }
+}
+
+/// Formats a list of positions, collapsing consecutive runs into ranges.
+fn ranges(positions: &[usize]) -> String {
+    let mut result = Vec::new();
+    let mut rest = positions;
+    while let Some(&start) = rest.first() {
+        let length = rest.iter().enumerate().take_while(|&(i, &position)| position == start + i).count();
+        result.push(if length == 1 { start.to_string() } else { format!("{}-{}", start, start + length - 1) });
+        rest = &rest[length..];
+    }
+    result.join(", ")
+}
+
+impl fmt::Display for Analysis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.outcome {
+            Outcome::Terminates => writeln!(f, "the program always terminates")?,
+            Outcome::Loops(index) => writeln!(f, "the program never terminates: it loops forever from {}", index)?,
+            Outcome::JumpsOut(index, to) => writeln!(f, "the program always terminates: {} jumps out of the program to {}", index, to)?
+        }
+
+        if self.unreachable.is_empty() {
+            writeln!(f, "every instruction is reachable")?;
+        } else {
+            writeln!(f, "{} unreachable instruction(s): {}", self.unreachable.len(), ranges(&self.unreachable))?;
+        }
+
+        writeln!(f, "{} jump(s) out of range", self.out_of_range.len())?;
+        for (index, to) in &self.out_of_range {
+            writeln!(f, "  {} jumps to {}", index, to)?;
+        }
+
+        writeln!(f, "{} cycle(s)", self.cycles.len())?;
+        for cycle in &self.cycles {
+            let path = cycle.iter().map(|index| index.to_string()).collect::<Vec<_>>();
+            writeln!(f, "  {} -> {}", path.join(" -> "), cycle[0])?;
+        }
+        Ok(())
+    }
+}
+
+/// Exports a program's control-flow graph in Graphviz DOT format. Unreachable instructions are
+/// grayed out, cycles are drawn in red, and jumps out of the program lead to a node of their own.
+pub fn to_dot(program: &[Instruction], analysis: &Analysis) -> String {
+    let mut in_cycle = vec![false; program.len()];
+    analysis.cycles.iter().flatten().for_each(|&index| in_cycle[index] = true);
+
+    let mut dot = String::new();
+    // Writing to a String never fails.
+    let mut line = |text: String| writeln!(dot, "    {}", text).unwrap();
+    line("node [shape=box, fontname=monospace];".to_string());
+    line("start [shape=point];".to_string());
+    line("end [shape=doublecircle, label=\"end\"];".to_string());
+    line(format!("start -> {};", if program.is_empty() { "end" } else { "i0" }));
+    for (index, instruction) in program.iter().enumerate() {
+        let style = if analysis.unreachable.binary_search(&index).is_ok() { ", style=dashed, color=gray" } else { "" };
+        line(format!("i{} [label=\"{}: {}\"{}];", index, index, instruction, style));
+
+        let next = instruction.next(index);
+        let target = if next == program.len() as i64 {
+            "end".to_string()
+        } else if next < 0 || next > program.len() as i64 {
+            line(format!("out{} [shape=octagon, color=orange, label=\"{}\"];", index, next));
+            format!("out{}", index)
+        } else {
+            format!("i{}", next)
+        };
+        let color = if in_cycle[index] { " [color=red]" } else { "" };
+        line(format!("i{} -> {}{};", index, target, color));
+    }
+    format!("digraph program {{\n{}}}\n", dot)
+}
@@ -0,0 +1,314 @@
+//! An assembler and disassembler for an extended version of the boot code, for writing programs by hand.
+//!
+//! On top of `nop`, `acc` and `jmp`, the extended instruction set has four registers (`a`, which is
+//! the accumulator, and `b` through `d`), conditional jumps (`jz` and `jnz`), and an `out` instruction
+//! which outputs the value of a register. Instructions which take a register use the accumulator if
+//! none is given. Source can contain comments, from `#` to the end of the line, and labels, which
+//! jumps can use instead of offsets:
+//!
+//! ```text
+//! # counts down from 3
+//!         acc b +3
+//! loop:   out b
+//!         acc b -1
+//!         jnz b loop
+//! ```
+//!
+//! Assembling resolves labels to relative offsets, and disassembling produces the canonical
+//! `op ±n` form used by the puzzle, which assembles back into the same program.
+
+use std::{collections::HashMap, convert::TryFrom, fmt};
+use common::{ErrorKind, ParseError};
+use crate::program::{self, Opcode};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Register { A, B, C, D }
+
+/// A single instruction of the extended instruction set. Jumps are relative to the jump itself.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Instruction {
+    Nop(i32),
+    Acc(Register, i32),
+    Jmp(i32),
+
+    /// Jumps if the register is zero.
+    Jz(Register, i32),
+
+    /// Jumps if the register is not zero.
+    Jnz(Register, i32),
+
+    /// Outputs the value of the register.
+    Out(Register)
+}
+
+impl Register {
+    fn parse(text: &str) -> Option<Self> {
+        match text {
+            "a" => Some(Register::A),
+            "b" => Some(Register::B),
+            "c" => Some(Register::C),
+            "d" => Some(Register::D),
+             _ => None
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+impl fmt::Display for Register {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Register::A => "a",
+            Register::B => "b",
+            Register::C => "c",
+            Register::D => "d"
+        })
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // The accumulator is left out, so that puzzle programs disassemble to exactly what they were.
+        let register = |register: &Register| match register {
+            Register::A => String::new(),
+            register => format!("{} ", register)
+        };
+        match self {
+            Instruction::Nop(offset) => write!(f, "nop {:+}", offset),
+            Instruction::Acc(r, value) => write!(f, "acc {}{:+}", register(r), value),
+            Instruction::Jmp(offset) => write!(f, "jmp {:+}", offset),
+            Instruction::Jz(r, offset) => write!(f, "jz {}{:+}", register(r), offset),
+            Instruction::Jnz(r, offset) => write!(f, "jnz {}{:+}", register(r), offset),
+            Instruction::Out(Register::A) => write!(f, "out"),
+            Instruction::Out(r) => write!(f, "out {}", r)
+        }
+    }
+}
+
+impl From<program::Instruction> for Instruction {
+    fn from(instruction: program::Instruction) -> Self {
+        match instruction.opcode {
+            Opcode::Nop => Instruction::Nop(instruction.operand),
+            Opcode::Acc => Instruction::Acc(Register::A, instruction.operand),
+            Opcode::Jmp => Instruction::Jmp(instruction.operand)
+        }
+    }
+}
+
+impl Instruction {
+    /// Converts an instruction back to the puzzle's instruction set, if it's part of it.
+    pub fn to_puzzle(self) -> Option<program::Instruction> {
+        let (opcode, operand) = match self {
+            Instruction::Nop(offset) => (Opcode::Nop, offset),
+            Instruction::Acc(Register::A, value) => (Opcode::Acc, value),
+            Instruction::Jmp(offset) => (Opcode::Jmp, offset),
+            _ => return None
+        };
+        Some(program::Instruction { opcode, operand })
+    }
+}
+
+/// Where a jump goes, before labels are resolved.
+enum Target<'a> {
+    Offset(i32),
+    Label(&'a str)
+}
+
+/// An instruction whose jump target might still be a label.
+struct Unresolved<'a> {
+    /// The opcode and register, and the operand if it's not a jump target.
+    opcode: &'a str,
+    register: Register,
+    value: i32,
+    target: Option<Target<'a>>
+}
+
+fn is_label(text: &str) -> bool {
+    text.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && text.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Parses one line of source, which must be a slice of `input`, into an optional label and instruction.
+fn parse_line<'a>(input: &str, line: &'a str) -> Result<(Option<&'a str>, Option<Unresolved<'a>>), ParseError> {
+    let code = match line.find('#') {
+        Some(comment) => &line[..comment],
+        None => line
+    };
+    let (label, code) = match code.find(':') {
+        Some(colon) => {
+            let label = code[..colon].trim();
+            if !is_label(label) {
+                return Err(ParseError::at(input, &code[..colon], ErrorKind::Expected("a label name")));
+            }
+            (Some(label), &code[colon + 1..])
+        }
+        None => (None, code)
+    };
+
+    let words = code.split_whitespace().collect::<Vec<_>>();
+    let (&opcode, arguments) = match words.split_first() {
+        Some(split) => split,
+        None => return Ok((label, None))
+    };
+
+    let register = |text: &str| Register::parse(text)
+        .ok_or_else(|| ParseError::at(input, text, ErrorKind::Expected("a register from 'a' to 'd'")));
+    let target = |text: &'a str| {
+        if text.starts_with(|c: char| c == '+' || c == '-' || c.is_ascii_digit()) {
+            Ok(Target::Offset(common::parse_number::<i32>(input, text)?))
+        } else if is_label(text) {
+            Ok(Target::Label(text))
+        } else {
+            Err(ParseError::at(input, text, ErrorKind::Expected("a label or an offset")))
+        }
+    };
+
+    let mut instruction = Unresolved { opcode, register: Register::A, value: 0, target: None };
+    match (opcode, arguments) {
+        ("nop", []) | ("out", []) => (),
+        ("nop", [value]) | ("acc", [value]) => instruction.value = common::parse_number(input, value)?,
+        ("acc", [r, value]) => {
+            instruction.register = register(r)?;
+            instruction.value = common::parse_number(input, value)?;
+        }
+        ("jmp", [to]) | ("jz", [to]) | ("jnz", [to]) => instruction.target = Some(target(to)?),
+        ("jz", [r, to]) | ("jnz", [r, to]) => {
+            instruction.register = register(r)?;
+            instruction.target = Some(target(to)?);
+        }
+        ("out", [r]) => instruction.register = register(r)?,
+        _ => {
+            // The most arguments each instruction takes, and what they are.
+            let (most, expected) = match opcode {
+                "nop" => (1, "an optional offset"),
+                "acc" => (2, "an optional register and a value"),
+                "jmp" => (1, "a label or an offset"),
+                "jz" | "jnz" => (2, "an optional register, and a label or an offset"),
+                "out" => (1, "an optional register"),
+                _ => return Err(ParseError::at(input, opcode,
+                    ErrorKind::Expected("'nop', 'acc', 'jmp', 'jz', 'jnz' or 'out'")))
+            };
+            return Err(match arguments.get(most) {
+                Some(extra) => ParseError::at(input, extra, ErrorKind::Expected("end of line")),
+                None => ParseError::after(input, opcode, ErrorKind::Expected(expected))
+            });
+        }
+    }
+
+    Ok((label, Some(instruction)))
+}
+
+/// Assembles a program, reporting every problem in it.
+pub fn assemble(input: &str) -> Result<Vec<Instruction>, Vec<ParseError>> {
+    let mut errors = Vec::new();
+    let lines = input.lines()
+        .filter_map(|line| parse_line(input, line).map_err(|e| errors.push(e)).ok())
+        .collect::<Vec<_>>();
+    // A label on a malformed line would show up as unknown everywhere it's used.
+    let malformed = !errors.is_empty();
+
+    let mut labels = HashMap::new();
+    let mut unresolved = Vec::new();
+    for (label, instruction) in lines {
+        if let Some(label) = label {
+            if labels.insert(label, unresolved.len()).is_some() {
+                errors.push(ParseError::at(input, label, ErrorKind::Duplicate("label")));
+            }
+        }
+        unresolved.extend(instruction);
+    }
+
+    let program = common::collect_all(unresolved.iter().enumerate().map(|(index, instruction)| {
+        let offset = match instruction.target {
+            Some(Target::Offset(offset)) => offset,
+            Some(Target::Label(label)) => match labels.get(label) {
+                Some(&destination) => destination as i32 - index as i32,
+                None if malformed => 0,
+                None => return Err(ParseError::at(input, label, ErrorKind::Unknown("label")))
+            },
+            None => 0
+        };
+        let register = instruction.register;
+        Ok(match instruction.opcode {
+            "nop" => Instruction::Nop(instruction.value),
+            "acc" => Instruction::Acc(register, instruction.value),
+            "jmp" => Instruction::Jmp(offset),
+            "jz" => Instruction::Jz(register, offset),
+            "jnz" => Instruction::Jnz(register, offset),
+            "out" => Instruction::Out(register),
+            _ => unreachable!("parse_line only accepts known opcodes")
+        })
+    }));
+
+    match program {
+        Ok(program) if errors.is_empty() => Ok(program),
+        program => {
+            errors.extend(program.err().into_iter().flatten());
+            errors.sort_by_key(|e| (e.line, e.column));
+            Err(errors)
+        }
+    }
+}
+
+/// Disassembles a program into canonical source, with one `op ±n` instruction per line.
+pub fn disassemble(program: &[Instruction]) -> String {
+    program.iter().map(|instruction| format!("{}\n", instruction)).collect()
+}
+
+/// How a program stopped running.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Halt {
+    /// The program jumped to just past its last instruction.
+    Terminated,
+
+    /// The program jumped somewhere outside of itself.
+    OutOfBounds(i64),
+
+    /// The program ran for too long, and probably never terminates.
+    StepLimit
+}
+
+/// The result of running a program.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Run {
+    pub halt: Halt,
+
+    /// The registers when the program stopped. Like the accumulator in the
+    /// compiled backend, they wrap around rather than overflowing.
+    pub registers: [i32; 4],
+
+    /// Every value printed by an `out` instruction.
+    pub output: Vec<i32>
+}
+
+/// Runs a program for at most `step_limit` instructions.
+pub fn run(program: &[Instruction], step_limit: usize) -> Run {
+    let mut registers = [0; 4];
+    let mut output = Vec::new();
+    let mut pc = 0i64;
+    for _ in 0..step_limit {
+        if pc == program.len() as i64 { return Run { halt: Halt::Terminated, registers, output }; }
+        let instruction = match usize::try_from(pc).ok().and_then(|pc| program.get(pc)) {
+            Some(instruction) => instruction,
+            None => return Run { halt: Halt::OutOfBounds(pc), registers, output }
+        };
+        pc += match *instruction {
+            Instruction::Nop(_) => 1,
+            Instruction::Acc(r, value) => {
+                registers[r.index()] = registers[r.index()].wrapping_add(value);
+                1
+            }
+            Instruction::Jmp(offset) => offset as i64,
+            Instruction::Jz(r, offset) => if registers[r.index()] == 0 { offset as i64 } else { 1 },
+            Instruction::Jnz(r, offset) => if registers[r.index()] != 0 { offset as i64 } else { 1 },
+            Instruction::Out(r) => {
+                output.push(registers[r.index()]);
+                1
+            }
+        };
+    }
+    let halt = if pc == program.len() as i64 { Halt::Terminated } else { Halt::StepLimit };
+    Run { halt, registers, output }
+}
@@ -0,0 +1,139 @@
+//! A faster way to run boot code, for large generated programs.
+//!
+//! Every instruction does nothing more than add a constant to the accumulator and move to a
+//! constant position, so a whole run of instructions up to the next jump does too. A program
+//! compiles down to a table giving, for each position, the total it adds to the accumulator and
+//! where it ends up once it reaches the end of its run, so straight-line code runs in one step.
+//!
+//! Loops are still found at the exact instruction the interpreter would find them. The only way
+//! into the middle of a run is a jump, so once a run has been entered, entering it again always
+//! means the program is looping: either the new entry point has already run, or the first entry
+//! point is about to run again, and the accumulator at that point can be worked out from the table.
+//!
+//! Totals for a run are added up all at once, so the accumulator wraps around on overflow rather
+//! than panicking; it still ends up with the same value as running one instruction at a time.
+
+use crate::program::{Instruction, Opcode};
+
+/// Stands in for the next position of an instruction which jumps outside of the program.
+const OUT_OF_BOUNDS: u32 = u32::MAX;
+
+#[derive(Copy, Clone, Debug)]
+struct Op {
+    /// How much the accumulator increases by from here to the end of the run.
+    increment: i32,
+
+    /// Where the run goes once it ends.
+    next: u32,
+
+    /// The position of the last instruction in the run, which identifies it.
+    end: u32
+}
+
+/// How a compiled program stopped, and the accumulator when it did.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Halt {
+    /// The program jumped to just past its last instruction.
+    Terminated(i32),
+
+    /// The program was about to run an instruction for the second time.
+    Loop(i32),
+
+    /// The program jumped somewhere outside of itself.
+    OutOfBounds(i32)
+}
+
+/// A program compiled for fast execution.
+#[derive(Clone, Debug)]
+pub struct Compiled {
+    ops: Vec<Op>
+}
+
+impl Compiled {
+    /// Compiles a program. Panics if it is too long to index with a `u32`.
+    pub fn new(program: &[Instruction]) -> Self {
+        assert!(program.len() < OUT_OF_BOUNDS as usize, "the program is too long to compile");
+        let mut ops = vec![Op { increment: 0, next: 0, end: 0 }; program.len()];
+        // Work backwards, so that the rest of each run is already compiled.
+        for (index, instruction) in program.iter().enumerate().rev() {
+            let increment = if instruction.opcode == Opcode::Acc { instruction.operand } else { 0 };
+            ops[index] = if instruction.opcode != Opcode::Jmp && index + 1 < program.len() {
+                let rest = ops[index + 1];
+                Op { increment: increment.wrapping_add(rest.increment), ..rest }
+            } else {
+                let next = match instruction.successor(index, program.len()) {
+                    Some(next) => next as u32,
+                    None if instruction.next(index) == program.len() as i64 => program.len() as u32,
+                    None => OUT_OF_BOUNDS
+                };
+                Op { increment, next, end: index as u32 }
+            };
+        }
+        Compiled { ops }
+    }
+
+    /// Runs the program until it terminates, loops, or jumps out of bounds.
+    pub fn run(&self) -> Halt {
+        // Which runs have been entered, marked at their last instruction,
+        // and where each of them was entered.
+        let mut entered = Bitset::new(self.ops.len());
+        let mut entry_points = Bitset::new(self.ops.len());
+
+        let end = self.ops.len() as u32;
+        let mut accum = 0i32;
+        let mut pc = 0u32;
+        loop {
+            if pc == end { return Halt::Terminated(accum); }
+            if pc == OUT_OF_BOUNDS { return Halt::OutOfBounds(accum); }
+
+            let op = self.ops[pc as usize];
+            if entered.get(op.end as usize) {
+                // If this run was first entered after this point, everything up to there runs
+                // for the first time before the program loops.
+                let before = match entry_points.first_set(pc as usize, op.end as usize) {
+                    Some(first) => op.increment.wrapping_sub(self.ops[first].increment),
+                    None => 0
+                };
+                return Halt::Loop(accum.wrapping_add(before));
+            }
+            entered.set(op.end as usize);
+            entry_points.set(pc as usize);
+            accum = accum.wrapping_add(op.increment);
+            pc = op.next;
+        }
+    }
+}
+
+/// A fixed-size set of positions, packed into bits so that it stays small enough to fit in cache.
+struct Bitset {
+    words: Vec<u64>
+}
+
+impl Bitset {
+    fn new(len: usize) -> Self {
+        Bitset { words: vec![0; len.div_ceil(64)] }
+    }
+
+    fn get(&self, index: usize) -> bool {
+        self.words[index / 64] & (1 << (index % 64)) != 0
+    }
+
+    fn set(&mut self, index: usize) {
+        self.words[index / 64] |= 1 << (index % 64);
+    }
+
+    /// Finds the first position from `start` to `end` (inclusive) in the set.
+    fn first_set(&self, start: usize, end: usize) -> Option<usize> {
+        let mut index = start;
+        while index <= end {
+            // Skip the bits before `index` in its word.
+            let word = self.words[index / 64] >> (index % 64);
+            if word != 0 {
+                return Some(index + word.trailing_zeros() as usize).filter(|&found| found <= end);
+            }
+            index = (index / 64 + 1) * 64;
+        }
+        None
+    }
+}
+
@@ -0,0 +1,305 @@
+//! An interactive debugger for boot code, for finding out why a program loops.
+
+use std::{collections::BTreeSet, fmt, io::{self, BufRead, Write}};
+use crate::program::{Instruction, Opcode};
+
+/// A condition on the accumulator which pauses execution when it is met.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Watchpoint {
+    /// Pauses whenever the accumulator changes.
+    Change,
+
+    /// Pauses whenever the accumulator changes to the given value.
+    Equals(i32)
+}
+
+/// One executed instruction, and the state of the machine around it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Step {
+    pub pc: usize,
+    pub instruction: Instruction,
+    pub accum_before: i32,
+    pub accum_after: i32
+}
+
+/// Why the debugger paused.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Stop {
+    /// A single step finished without anything else happening.
+    Stepped,
+
+    /// The next instruction has a breakpoint on it.
+    Breakpoint(usize),
+
+    /// The last instruction triggered a watchpoint on the accumulator.
+    Watchpoint { old: i32, new: i32 },
+
+    /// The next instruction has already been executed, so the program will loop forever.
+    Loop(usize),
+
+    /// The program jumped to just past its last instruction.
+    Terminated,
+
+    /// The program jumped somewhere outside of itself.
+    OutOfBounds(i32)
+}
+
+impl fmt::Display for Stop {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Stop::Stepped => write!(f, "stepped"),
+            Stop::Breakpoint(pc) => write!(f, "breakpoint at {}", pc),
+            Stop::Watchpoint { old, new } => write!(f, "accumulator changed from {} to {}", old, new),
+            Stop::Loop(pc) => write!(f, "infinite loop: instruction {} is about to run a second time", pc),
+            Stop::Terminated => write!(f, "program terminated"),
+            Stop::OutOfBounds(pc) => write!(f, "jumped out of the program to {}", pc)
+        }
+    }
+}
+
+/// Runs a program one step at a time, remembering every step so they can be undone.
+pub struct Debugger {
+    program: Vec<Instruction>,
+    pc: i32,
+    accum: i32,
+
+    /// How many times each instruction has been executed.
+    visits: Vec<u32>,
+
+    breakpoints: BTreeSet<usize>,
+    watchpoints: Vec<Watchpoint>,
+
+    /// Every step executed so far, oldest first.
+    history: Vec<Step>
+}
+
+impl Debugger {
+    pub fn new(program: Vec<Instruction>) -> Self {
+        let visits = vec![0; program.len()];
+        Debugger {
+            program, pc: 0, accum: 0, visits,
+            breakpoints: BTreeSet::new(), watchpoints: Vec::new(), history: Vec::new()
+        }
+    }
+
+    pub fn program(&self) -> &[Instruction] { &self.program }
+    pub fn pc(&self) -> i32 { self.pc }
+    pub fn accum(&self) -> i32 { self.accum }
+
+    /// Every step executed so far, oldest first.
+    pub fn history(&self) -> &[Step] { &self.history }
+
+    /// Returns why the program can't run any further, or None if it can.
+    pub fn halted(&self) -> Option<Stop> {
+        if self.pc as usize == self.program.len() { Some(Stop::Terminated) }
+        else if self.pc < 0 || self.pc as usize > self.program.len() { Some(Stop::OutOfBounds(self.pc)) }
+        else { None }
+    }
+
+    /// Sets a breakpoint on an instruction. Returns false if there already was one.
+    pub fn add_breakpoint(&mut self, pc: usize) -> bool {
+        self.breakpoints.insert(pc)
+    }
+
+    /// Removes the breakpoint from an instruction. Returns false if there wasn't one.
+    pub fn remove_breakpoint(&mut self, pc: usize) -> bool {
+        self.breakpoints.remove(&pc)
+    }
+
+    pub fn breakpoints(&self) -> impl Iterator<Item = usize> + '_ {
+        self.breakpoints.iter().cloned()
+    }
+
+    pub fn watch(&mut self, watchpoint: Watchpoint) {
+        if !self.watchpoints.contains(&watchpoint) { self.watchpoints.push(watchpoint); }
+    }
+
+    pub fn clear_watchpoints(&mut self) {
+        self.watchpoints.clear();
+    }
+
+    pub fn watchpoints(&self) -> &[Watchpoint] { &self.watchpoints }
+
+    /// Executes a single instruction, ignoring breakpoints and loops.
+    pub fn step(&mut self) -> Stop {
+        if let Some(stop) = self.halted() { return stop; }
+
+        let pc = self.pc as usize;
+        let instruction = self.program[pc];
+        let accum_before = self.accum;
+        // Stepping keeps going through loops, so the accumulator can overflow; it wraps around,
+        // as it does in the compiled backend. A jump far enough to overflow is out of bounds anyway.
+        match instruction.opcode {
+            Opcode::Nop => (),
+            Opcode::Acc => self.accum = self.accum.wrapping_add(instruction.operand),
+            Opcode::Jmp => self.pc = self.pc.saturating_add(instruction.operand)
+        }
+        if instruction.opcode != Opcode::Jmp { self.pc += 1 }
+        self.visits[pc] += 1;
+        self.history.push(Step { pc, instruction, accum_before, accum_after: self.accum });
+
+        let (old, new) = (accum_before, self.accum);
+        let triggered = old != new && self.watchpoints.iter().any(|watchpoint| match watchpoint {
+            Watchpoint::Change => true,
+            Watchpoint::Equals(value) => new == *value
+        });
+        if triggered { Stop::Watchpoint { old, new } } else { Stop::Stepped }
+    }
+
+    /// Runs until a breakpoint, watchpoint or loop, or until the program halts.
+    /// The first instruction is always executed, so that resuming from a breakpoint makes progress.
+    pub fn resume(&mut self) -> Stop {
+        let mut stop = self.step();
+        while stop == Stop::Stepped {
+            if let Some(halted) = self.halted() { return halted; }
+            let pc = self.pc as usize;
+            if self.breakpoints.contains(&pc) { return Stop::Breakpoint(pc); }
+            if self.visits[pc] > 0 { return Stop::Loop(pc); }
+            stop = self.step();
+        }
+        stop
+    }
+
+    /// Undoes the last step. Returns false if there is nothing to undo.
+    pub fn reverse_step(&mut self) -> bool {
+        match self.history.pop() {
+            Some(step) => {
+                self.pc = step.pc as i32;
+                self.accum = step.accum_before;
+                self.visits[step.pc] -= 1;
+                true
+            }
+            None => false
+        }
+    }
+
+    /// Writes every step executed so far, oldest first.
+    pub fn dump_trace(&self, mut output: impl Write) -> io::Result<()> {
+        for (index, step) in self.history.iter().enumerate() {
+            writeln!(output, "{:>6}  {:>5}: {:<8}  accum {} -> {}",
+                index, step.pc, step.instruction.to_string(), step.accum_before, step.accum_after)?;
+        }
+        Ok(())
+    }
+}
+
+const HELP: &str = "\
+commands:
+  s, step [n]        execute n instructions (default 1)
+  r, reverse [n]     undo the last n steps (default 1)
+  c, continue        run until a breakpoint, watchpoint or loop, or until the program halts
+  b, break <pc>      set a breakpoint on an instruction
+  d, delete <pc>     remove a breakpoint
+  w, watch [value]   pause whenever the accumulator changes (to value, if given)
+  unwatch            remove every watchpoint
+  i, info            show the machine state, breakpoints and watchpoints
+  l, list [n]        show the instructions within n (default 3) of the current one
+  t, trace           show every step executed so far
+  h, help            show this message
+  q, quit            exit the debugger";
+
+/// Reads debugger commands from `input` until it ends or a `quit` command, writing results to `output`.
+pub fn repl(debugger: &mut Debugger, input: impl BufRead, mut output: impl Write) -> io::Result<()> {
+    writeln!(output, "{} instructions loaded; type 'help' for a list of commands", debugger.program().len())?;
+    show_current(debugger, &mut output)?;
+    write!(output, "(day8) ")?;
+    output.flush()?;
+
+    for line in input.lines() {
+        let line = line?;
+        let words = line.split_whitespace().collect::<Vec<_>>();
+        // An optional count or position after a command.
+        let argument = |default| words.get(1).map_or(Ok(default), |arg| arg.parse::<usize>());
+
+        match words[..] {
+            [] => (),
+            ["s"] | ["step"] | ["s", _] | ["step", _] => match argument(1) {
+                Ok(count) => {
+                    let mut stop = Stop::Stepped;
+                    for _ in 0..count {
+                        stop = debugger.step();
+                        if stop != Stop::Stepped { break; }
+                    }
+                    if stop != Stop::Stepped { writeln!(output, "{}", stop)?; }
+                    show_current(debugger, &mut output)?;
+                }
+                Err(_) => writeln!(output, "expected a number of steps")?
+            },
+            ["r"] | ["reverse"] | ["r", _] | ["reverse", _] => match argument(1) {
+                Ok(count) => {
+                    let undone = (0..count).take_while(|_| debugger.reverse_step()).count();
+                    if undone < count { writeln!(output, "reached the start of the program")?; }
+                    show_current(debugger, &mut output)?;
+                }
+                Err(_) => writeln!(output, "expected a number of steps")?
+            },
+            ["c"] | ["continue"] => {
+                writeln!(output, "{}", debugger.resume())?;
+                show_current(debugger, &mut output)?;
+            }
+            ["b", _] | ["break", _] => match argument(0) {
+                Ok(pc) if pc < debugger.program().len() => {
+                    if debugger.add_breakpoint(pc) { writeln!(output, "breakpoint set at {}", pc)?; }
+                    else { writeln!(output, "there is already a breakpoint at {}", pc)?; }
+                }
+                _ => writeln!(output, "expected an instruction between 0 and {}", debugger.program().len().saturating_sub(1))?
+            },
+            ["d", _] | ["delete", _] => match argument(0) {
+                Ok(pc) if debugger.remove_breakpoint(pc) => writeln!(output, "breakpoint at {} removed", pc)?,
+                _ => writeln!(output, "no such breakpoint")?
+            },
+            ["w"] | ["watch"] => debugger.watch(Watchpoint::Change),
+            ["w", value] | ["watch", value] => match value.parse() {
+                Ok(value) => debugger.watch(Watchpoint::Equals(value)),
+                Err(_) => writeln!(output, "expected a value for the accumulator")?
+            },
+            ["unwatch"] => debugger.clear_watchpoints(),
+            ["i"] | ["info"] => {
+                writeln!(output, "pc {}, accum {}, {} steps executed", debugger.pc(), debugger.accum(), debugger.history().len())?;
+                let breakpoints = debugger.breakpoints().map(|pc| pc.to_string()).collect::<Vec<_>>();
+                writeln!(output, "breakpoints: {}", if breakpoints.is_empty() { "none".to_string() } else { breakpoints.join(", ") })?;
+                for watchpoint in debugger.watchpoints() {
+                    match watchpoint {
+                        Watchpoint::Change => writeln!(output, "watching: any change to accum")?,
+                        Watchpoint::Equals(value) => writeln!(output, "watching: accum == {}", value)?
+                    }
+                }
+            }
+            ["l"] | ["list"] | ["l", _] | ["list", _] => match argument(3) {
+                Ok(radius) => list(debugger, radius, &mut output)?,
+                Err(_) => writeln!(output, "expected a number of instructions")?
+            },
+            ["t"] | ["trace"] => debugger.dump_trace(&mut output)?,
+            ["h"] | ["help"] => writeln!(output, "{}", HELP)?,
+            ["q"] | ["quit"] => return Ok(()),
+            _ => writeln!(output, "unknown command '{}'; type 'help' for a list of commands", line.trim())?
+        }
+
+        write!(output, "(day8) ")?;
+        output.flush()?;
+    }
+    writeln!(output)
+}
+
+/// Shows the instruction which will run next.
+fn show_current(debugger: &Debugger, output: &mut impl Write) -> io::Result<()> {
+    match debugger.halted() {
+        Some(stop) => writeln!(output, "halted ({}), accum {}", stop, debugger.accum()),
+        None => {
+            let pc = debugger.pc() as usize;
+            writeln!(output, "{:>5}: {:<8}  accum {}", pc, debugger.program()[pc].to_string(), debugger.accum())
+        }
+    }
+}
+
+/// Shows the instructions around the current one, marking the current instruction and any breakpoints.
+fn list(debugger: &Debugger, radius: usize, output: &mut impl Write) -> io::Result<()> {
+    let pc = debugger.pc().max(0) as usize;
+    let end = (pc + radius + 1).min(debugger.program().len());
+    for index in pc.saturating_sub(radius)..end {
+        let marker = if index == pc { '>' } else { ' ' };
+        let breakpoint = if debugger.breakpoints.contains(&index) { '*' } else { ' ' };
+        writeln!(output, "{}{}{:>5}: {}", marker, breakpoint, index, debugger.program()[index])?;
+    }
+    Ok(())
+}
@@ -0,0 +1,15 @@
+use common::Example;
+
+pub mod analysis;
+pub mod asm;
+pub mod compiled;
+pub mod debugger;
+pub mod part1;
+pub mod part2;
+pub mod program;
+pub mod repair;
+
+/// The examples from the puzzle description.
+pub const EXAMPLES: &[Example] = &[
+    Example { input: include_str!("../test"), answers: [Some("5"), Some("8")] },
+];
@@ -1,76 +1,54 @@
-use std::io::{self, BufRead};
+use common::{Answer, Error, ParseError, Solution};
+use crate::program::{self, Instruction, Opcode};
 
-struct Interpreter {
+pub struct Interpreter {
     program: Vec<Instruction>,
-    pc: i32,
-    accum: i32
-}
-
-#[derive(Debug)]
-struct Instruction {
-    opcode: Opcode,
-    operand: i32,
-
-    /// Whether or not this instruction has been executed.
-    executed: bool
-}
-
-#[derive(Eq, PartialEq, Debug)]
-enum Opcode {
-    Nop,
-    Acc,
-    Jmp
-}
 
-impl Instruction { fn parse(input: &str) -> Option<Self> {
-        let split = &mut input.split_whitespace();
-        let opcode = split.next().and_then(Opcode::parse)?;
-        let operand = split.next().and_then(|operand| operand.parse::<i32>().ok())?;
+    /// Whether or not each instruction has been executed.
+    executed: Vec<bool>,
 
-        Some(Instruction { opcode, operand, executed: false })
-    }
+    pc: i32,
+    accum: i32
 }
 
-impl Opcode {
-    fn parse(input: &str) -> Option<Self> {
-        match input {
-            "nop" => Some(Opcode::Nop),
-            "acc" => Some(Opcode::Acc),
-            "jmp" => Some(Opcode::Jmp),
-                _ => None
-        }
+impl Interpreter {
+    /// Parses the input into a program.
+    fn parse(input: &str) -> Result<Self, Vec<ParseError>> {
+        Ok(Interpreter::new(program::parse(input)?))
     }
-}
 
-impl Interpreter {
-    /// Parses the input into a program. Returns the bad instruction on failure.
-    fn parse(input: impl Iterator<Item=String>) -> Result<Self, String> {
-        let program = input.map(|line| Instruction::parse(&line).ok_or(line)).collect::<Result<Vec<Instruction>,_>>()?;
-        Ok(Interpreter { program, pc: 0, accum: 0 })
+    pub fn new(program: Vec<Instruction>) -> Self {
+        let executed = vec![false; program.len()];
+        Interpreter { program, executed, pc: 0, accum: 0 }
     }
 
-    fn run_until_infinite_loop(&mut self) -> i32 {
+    /// Returns the accumulator on an infinite loop, or None if the program terminates instead.
+    pub fn run_until_infinite_loop(&mut self) -> Option<i32> {
         loop {
-            let instruction = &mut self.program[self.pc as usize];
-            //println!("{:?}", instruction);
-            if instruction.executed { return self.accum; }
+            let instruction = self.program.get(self.pc as usize)?;
+            let executed = &mut self.executed[self.pc as usize];
+            if *executed { return Some(self.accum); }
             match instruction.opcode {
                 Opcode::Nop => (),
                 Opcode::Acc => self.accum += instruction.operand,
                 Opcode::Jmp => self.pc += instruction.operand
             }
             if instruction.opcode != Opcode::Jmp { self.pc += 1 }
-            instruction.executed = true;
+            *executed = true;
         }
     }
 }
 
+pub struct Part1;
 
-pub fn run() {
-    let stdin = io::stdin();
-    let mut interpreter = Interpreter::parse(
-        stdin.lock().lines().map(|line| line.expect("read error"))
-    ).expect("parse error");
-    let result = interpreter.run_until_infinite_loop();
-    println!("{}", result);
+impl Solution for Part1 {
+    type Input = Interpreter;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        Ok(Interpreter::parse(input)?)
+    }
+
+    fn solve(mut interpreter: Self::Input) -> Result<Answer, Error> {
+        Ok(interpreter.run_until_infinite_loop().ok_or(Error::NoSolution)?.into())
+    }
 }
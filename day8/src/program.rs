@@ -0,0 +1,137 @@
+use std::fmt;
+use common::{ErrorKind, ParseError};
+
+/// A single instruction of the handheld's boot code.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct Instruction {
+    pub opcode: Opcode,
+    pub operand: i32
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Opcode {
+    Nop,
+    Acc,
+    Jmp
+}
+
+impl Instruction {
+    /// Parses an instruction from `line`, which must be a slice of `input`.
+    pub fn parse(input: &str, line: &str) -> Result<Self, ParseError> {
+        let split = &mut line.split_whitespace();
+        let opcode = split.next()
+            .ok_or_else(|| ParseError::after(input, line, ErrorKind::Expected("an instruction")))?;
+        let opcode = Opcode::parse(opcode)
+            .ok_or_else(|| ParseError::at(input, opcode, ErrorKind::Expected("'nop', 'acc' or 'jmp'")))?;
+        let operand = split.next()
+            .ok_or_else(|| ParseError::after(input, line, ErrorKind::Expected("an operand")))?;
+        let operand = common::parse_number::<i32>(input, operand)?;
+        if let Some(extra) = split.next() {
+            return Err(ParseError::at(input, extra, ErrorKind::Expected("end of line")));
+        }
+
+        Ok(Instruction { opcode, operand })
+    }
+
+    /// The position of the instruction which runs after this one, if this one is at `index`.
+    /// This might be outside the program.
+    pub fn next(&self, index: usize) -> i64 {
+        match self.opcode {
+            Opcode::Jmp => index as i64 + self.operand as i64,
+            _ => index as i64 + 1
+        }
+    }
+
+    /// The instruction which runs after this one, if this one is at `index` in a program `len`
+    /// instructions long, or None if the program ends here. As in the puzzle, jumping anywhere
+    /// outside of the program ends it, not just jumping to right after the last instruction.
+    pub fn successor(&self, index: usize, len: usize) -> Option<usize> {
+        let next = self.next(index);
+        if (0..len as i64).contains(&next) { Some(next as usize) } else { None }
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {:+}", self.opcode, self.operand)
+    }
+}
+
+impl Opcode {
+    pub fn parse(input: &str) -> Option<Self> {
+        match input {
+            "nop" => Some(Opcode::Nop),
+            "acc" => Some(Opcode::Acc),
+            "jmp" => Some(Opcode::Jmp),
+                _ => None
+        }
+    }
+}
+
+impl fmt::Display for Opcode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Opcode::Nop => "nop",
+            Opcode::Acc => "acc",
+            Opcode::Jmp => "jmp"
+        })
+    }
+}
+
+/// Parses a program, one instruction per line.
+pub fn parse(input: &str) -> Result<Vec<Instruction>, Vec<ParseError>> {
+    common::collect_all(input.lines().map(|line| Instruction::parse(input, line)))
+}
+
+/// A linear congruential generator, which is plenty random enough for making up programs.
+struct Random(u64);
+
+impl Random {
+    /// A number from 0 up to but not including `range`.
+    fn below(&mut self, range: usize) -> usize {
+        self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        (self.0 >> 33) as usize % range
+    }
+}
+
+/// Generates a program of `len` instructions which runs each one exactly once, then loops
+/// back to the start. The same seed always generates the same program.
+pub fn generate(len: usize, seed: u64) -> Vec<Instruction> {
+    let mut random = Random(seed);
+
+    // Split the program into blocks which end in a jump, and link them up in a random order,
+    // always starting with the block at the start of the program.
+    let mut blocks = Vec::new();
+    let mut start = 0;
+    while start < len {
+        let end = (start + 1 + random.below(8)).min(len);
+        blocks.push(start..end);
+        start = end;
+    }
+    for i in (2..blocks.len()).rev() {
+        blocks.swap(i, 1 + random.below(i));
+    }
+
+    let mut program = vec![Instruction { opcode: Opcode::Nop, operand: 0 }; len];
+    for (index, block) in blocks.iter().enumerate() {
+        for position in block.clone() {
+            let operand = random.below(101) as i32 - 50;
+            let opcode = if random.below(4) == 0 { Opcode::Nop } else { Opcode::Acc };
+            program[position] = Instruction { opcode, operand };
+        }
+        let next = blocks.get(index + 1).map_or(0, |next| next.start);
+        let last = block.end - 1;
+        program[last] = Instruction { opcode: Opcode::Jmp, operand: next as i32 - last as i32 };
+    }
+    program
+}
+
+/// Generates `len` instructions at random, which might do anything: loop, terminate, or jump
+/// to just before the start or just past the end. The same seed always generates the same program.
+pub fn random(len: usize, seed: u64) -> Vec<Instruction> {
+    let mut random = Random(seed);
+    (0..len).map(|_| {
+        let opcode = [Opcode::Nop, Opcode::Acc, Opcode::Jmp][random.below(3)];
+        Instruction { opcode, operand: random.below(2 * len + 3) as i32 - len as i32 - 1 }
+    }).collect()
+}
@@ -0,0 +1,95 @@
+//! Finds every way to make a looping program terminate by swapping a single `nop` and `jmp`,
+//! in time linear in the length of the program.
+//!
+//! Every instruction has exactly one successor, so the instructions which terminate form a tree
+//! rooted at the end of the program. Walking it backwards from the end finds every instruction that
+//! terminates, and walking the program forwards from the start finds every instruction it executes;
+//! a repair is an executed instruction which, once flipped, leads to one that terminates.
+//!
+//! As in the original solution and part 1, jumping anywhere outside of the program terminates it,
+//! whether that's past the end or before the start.
+
+use crate::program::{Instruction, Opcode};
+
+/// A single flipped instruction which makes the program terminate.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Repair {
+    pub index: usize,
+    pub original: Opcode,
+    pub replacement: Opcode,
+
+    /// The accumulator when the repaired program terminates.
+    pub accum: i32
+}
+
+/// The instruction which runs after the one at `index`, if `index` had the given opcode, where
+/// `program.len()` stands for the end of the program, and everywhere else outside of it.
+fn successor(program: &[Instruction], index: usize, opcode: Opcode) -> usize {
+    Instruction { opcode, ..program[index] }.successor(index, program.len()).unwrap_or(program.len())
+}
+
+/// The opcode an instruction could have been corrupted from, if any.
+fn flipped(opcode: Opcode) -> Option<Opcode> {
+    match opcode {
+        Opcode::Nop => Some(Opcode::Jmp),
+        Opcode::Jmp => Some(Opcode::Nop),
+        Opcode::Acc => None
+    }
+}
+
+/// How much an instruction adds to the accumulator.
+fn increment(instruction: &Instruction) -> i32 {
+    if instruction.opcode == Opcode::Acc { instruction.operand } else { 0 }
+}
+
+/// For each instruction, and for the end of the program, the amount the accumulator increases by
+/// between reaching it and terminating, or None if running the program from there never terminates.
+pub fn terminating(program: &[Instruction]) -> Vec<Option<i32>> {
+    // The instructions which run immediately before each instruction.
+    let mut predecessors = vec![Vec::new(); program.len() + 1];
+    for (index, instruction) in program.iter().enumerate() {
+        predecessors[successor(program, index, instruction.opcode)].push(index);
+    }
+
+    // Walk the tree from the end, working out each instruction's total from its parent's.
+    let mut remaining = vec![None; program.len() + 1];
+    remaining[program.len()] = Some(0);
+    let mut stack = vec![program.len()];
+    while let Some(next) = stack.pop() {
+        for &index in &predecessors[next] {
+            remaining[index] = Some(remaining[next].unwrap() + increment(&program[index]));
+            stack.push(index);
+        }
+    }
+    remaining
+}
+
+/// Finds every instruction which makes the program terminate when flipped between `nop` and `jmp`,
+/// in order of position in the program. Finds none if the program already terminates, since then
+/// there's nothing to repair.
+pub fn repairs(program: &[Instruction]) -> Vec<Repair> {
+    let remaining = terminating(program);
+    if remaining[0].is_some() { return Vec::new(); }
+
+    let mut repairs = Vec::new();
+    let mut executed = vec![false; program.len()];
+    let mut accum = 0;
+    let mut index = 0;
+    // Walk the unmodified program until it repeats itself. None of the instructions on the way
+    // terminate, so flipping one can't lead back to itself.
+    while index < program.len() && !executed[index] {
+        executed[index] = true;
+        let instruction = &program[index];
+        if let Some(replacement) = flipped(instruction.opcode) {
+            if let Some(remaining) = remaining[successor(program, index, replacement)] {
+                repairs.push(Repair { index, original: instruction.opcode, replacement, accum: accum + remaining });
+            }
+        }
+
+        accum += increment(instruction);
+        index = successor(program, index, instruction.opcode);
+    }
+
+    repairs.sort_unstable_by_key(|repair| repair.index);
+    repairs
+}
@@ -0,0 +1,66 @@
+use day8::{analysis::{self, Outcome}, debugger::{Debugger, Stop}, program, repair};
+
+#[test]
+fn example() {
+    let program = program::parse(day8::EXAMPLES[0].input).unwrap();
+    let analysis = analysis::analyze(&program);
+    assert_eq!(analysis.outcome, Outcome::Loops(1));
+    assert!(!analysis.terminates());
+    assert_eq!(analysis.unreachable, vec![5, 8]);
+    assert_eq!(analysis.out_of_range, vec![]);
+    assert_eq!(analysis.cycles, vec![vec![1, 2, 6, 7, 3, 4]]);
+
+    let dot = analysis::to_dot(&program, &analysis);
+    assert!(dot.starts_with("digraph program {\n") && dot.ends_with("}\n"), "{}", dot);
+    assert!(dot.contains("i4 -> i1 [color=red];") && dot.contains("i8 -> end;"), "{}", dot);
+}
+
+#[test]
+fn separate_cycles_and_bad_jumps() {
+    let program = program::parse("jmp +0\nacc +1\njmp -1\njmp +5\nnop +0\n").unwrap();
+    let analysis = analysis::analyze(&program);
+    assert_eq!(analysis.outcome, Outcome::Loops(0));
+    assert_eq!(analysis.unreachable, vec![1, 2, 3, 4]);
+    assert_eq!(analysis.out_of_range, vec![(3, 8)]);
+    assert_eq!(analysis.cycles, vec![vec![0], vec![1, 2]]);
+    assert!(analysis::to_dot(&program, &analysis).contains("i3 -> out3;"));
+}
+
+#[test]
+fn jumping_out_terminates() {
+    for (input, outcome) in [("acc +1\njmp +5\n", Outcome::JumpsOut(1, 6)), ("nop +0\njmp -2\n", Outcome::JumpsOut(1, -1))] {
+        let program = program::parse(input).unwrap();
+        let analysis = analysis::analyze(&program);
+        assert_eq!(analysis.outcome, outcome);
+        assert!(analysis.terminates());
+        assert!(analysis.to_string().starts_with("the program always terminates"), "{}", analysis);
+        assert!(repair::terminating(&program)[0].is_some());
+    }
+}
+
+#[test]
+fn agrees_with_running_the_program() {
+    for seed in 0..1000 {
+        let len = 1 + seed as usize % 10;
+        let program = program::random(len, seed);
+
+        let analysis = analysis::analyze(&program);
+        let mut debugger = Debugger::new(program.clone());
+        let stop = debugger.resume();
+        let expected = match stop {
+            Stop::Terminated => Outcome::Terminates,
+            Stop::Loop(index) => Outcome::Loops(index),
+            Stop::OutOfBounds(to) => {
+                let last = debugger.history().last().unwrap();
+                Outcome::JumpsOut(last.pc, to as i64)
+            }
+            stop => panic!("unexpected stop {:?}", stop)
+        };
+        assert_eq!(analysis.outcome, expected, "{:?}", program);
+        assert_eq!(analysis.terminates(), repair::terminating(&program)[0].is_some(), "{:?}", program);
+
+        let executed = debugger.history().iter().map(|step| step.pc).collect::<std::collections::HashSet<_>>();
+        assert!(analysis.unreachable.iter().all(|index| !executed.contains(index)), "{:?}", program);
+        assert_eq!(analysis.unreachable.len() + executed.len(), len, "{:?}", program);
+    }
+}
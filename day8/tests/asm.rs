@@ -0,0 +1,62 @@
+use day8::asm::{self, Halt, Instruction, Register};
+
+#[test]
+fn labels_and_comments() {
+    let source = "\
+# counts down from 3
+        acc b +3
+loop:   out b
+        acc b -1   # one fewer
+        jnz b loop
+        jz end
+        nop
+end:
+";
+    let program = asm::assemble(source).unwrap();
+    assert_eq!(program, vec![
+        Instruction::Acc(Register::B, 3),
+        Instruction::Out(Register::B),
+        Instruction::Acc(Register::B, -1),
+        Instruction::Jnz(Register::B, -2),
+        Instruction::Jz(Register::A, 2),
+        Instruction::Nop(0)
+    ]);
+    assert_eq!(asm::disassemble(&program), "acc b +3\nout b\nacc b -1\njnz b -2\njz +2\nnop +0\n");
+
+    let run = asm::run(&program, 1000);
+    assert_eq!((run.halt, run.output), (Halt::Terminated, vec![3, 2, 1]));
+}
+
+#[test]
+fn puzzle_programs_round_trip() {
+    let input = day8::EXAMPLES[0].input;
+    let program = asm::assemble(input).unwrap();
+    assert_eq!(asm::disassemble(&program), input);
+
+    let puzzle = day8::program::parse(input).unwrap();
+    assert_eq!(program.iter().map(|i| i.to_puzzle().unwrap()).collect::<Vec<_>>(), puzzle);
+    assert_eq!(asm::run(&program, 1000).halt, Halt::StepLimit);
+}
+
+#[test]
+fn registers_wrap_around() {
+    let program = asm::assemble("loop: acc +1000000000\njmp loop\n").unwrap();
+    let run = asm::run(&program, 10);
+    assert_eq!(run.halt, Halt::StepLimit);
+    assert_eq!(run.registers[0], 1_000_000_000i32.wrapping_mul(5));
+}
+
+#[test]
+fn reports_every_error() {
+    let errors = asm::assemble("x: acc +1\nx: jmp y\nacc e +1\njz a +1 +2\nfoo\n").unwrap_err();
+    let errors = errors.iter().map(|e| (e.line, e.column, e.kind.to_string())).collect::<Vec<_>>();
+    assert_eq!(errors, vec![
+        (2, 1, "duplicate label".to_string()),
+        (3, 5, "expected a register from 'a' to 'd'".to_string()),
+        (4, 9, "expected end of line".to_string()),
+        (5, 1, "expected 'nop', 'acc', 'jmp', 'jz', 'jnz' or 'out'".to_string())
+    ]);
+
+    let errors = asm::assemble("jmp nowhere\n").unwrap_err();
+    assert_eq!((errors[0].line, errors[0].column, errors[0].kind.to_string()), (1, 5, "unknown label".to_string()));
+}
@@ -0,0 +1,38 @@
+use day8::{compiled::{Compiled, Halt}, debugger::{Debugger, Stop}, part1::Interpreter, program::{self, Instruction, Opcode}};
+
+/// Runs a program one instruction at a time.
+fn interpret(program: &[Instruction]) -> Halt {
+    let mut debugger = Debugger::new(program.to_vec());
+    match debugger.resume() {
+        Stop::Terminated => Halt::Terminated(debugger.accum()),
+        Stop::Loop(_) => Halt::Loop(debugger.accum()),
+        Stop::OutOfBounds(_) => Halt::OutOfBounds(debugger.accum()),
+        stop => panic!("unexpected stop {:?}", stop)
+    }
+}
+
+#[test]
+fn example() {
+    let program = program::parse(day8::EXAMPLES[0].input).unwrap();
+    assert_eq!(Compiled::new(&program).run(), Halt::Loop(5));
+    assert_eq!(Compiled::new(&[]).run(), Halt::Terminated(0));
+}
+
+#[test]
+fn agrees_with_the_interpreter() {
+    for seed in 0..5000 {
+        let program = program::random(1 + seed as usize % 16, seed);
+        assert_eq!(Compiled::new(&program).run(), interpret(&program), "{:?}", program);
+    }
+}
+
+#[test]
+fn generated_programs() {
+    for &(len, seed) in &[(1, 1), (2, 2), (1000, 3), (100_000, 4)] {
+        let program = program::generate(len, seed);
+        let expected = Interpreter::new(program.clone()).run_until_infinite_loop();
+        let sum = program.iter().filter(|i| i.opcode == Opcode::Acc).map(|i| i.operand).sum::<i32>();
+        assert_eq!(expected, Some(sum), "every instruction should run exactly once");
+        assert_eq!(Some(Compiled::new(&program).run()), expected.map(Halt::Loop));
+    }
+}
@@ -0,0 +1,80 @@
+use day8::{debugger::{Debugger, Stop, Watchpoint}, program};
+
+fn example() -> Debugger {
+    Debugger::new(program::parse(day8::EXAMPLES[0].input).unwrap())
+}
+
+#[test]
+fn breakpoints_and_loops() {
+    let mut debugger = example();
+    debugger.add_breakpoint(4);
+    assert_eq!(debugger.resume(), Stop::Breakpoint(4));
+    assert_eq!((debugger.pc(), debugger.accum()), (4, 5));
+
+    // Resuming from a breakpoint runs the instruction it's on.
+    assert_eq!(debugger.resume(), Stop::Loop(1));
+    assert_eq!(debugger.accum(), 5);
+}
+
+#[test]
+fn watchpoints() {
+    let mut debugger = example();
+    debugger.watch(Watchpoint::Equals(2));
+    assert_eq!(debugger.resume(), Stop::Watchpoint { old: 1, new: 2 });
+    assert_eq!(debugger.pc(), 7);
+
+    debugger.clear_watchpoints();
+    debugger.watch(Watchpoint::Change);
+    assert_eq!(debugger.resume(), Stop::Watchpoint { old: 2, new: 5 });
+}
+
+#[test]
+fn reverse_stepping() {
+    let mut debugger = example();
+    assert_eq!(debugger.resume(), Stop::Loop(1));
+    let trace = debugger.history().to_vec();
+    assert_eq!(trace.iter().map(|step| step.pc).collect::<Vec<_>>(), vec![0, 1, 2, 6, 7, 3, 4]);
+
+    assert!(debugger.reverse_step() && debugger.reverse_step());
+    assert_eq!((debugger.pc(), debugger.accum()), (3, 2));
+
+    // Running forward again retraces the same steps, and still notices the loop.
+    assert_eq!(debugger.resume(), Stop::Loop(1));
+    assert_eq!(debugger.history(), &trace[..]);
+
+    while debugger.reverse_step() {}
+    assert_eq!((debugger.pc(), debugger.accum(), debugger.history().len()), (0, 0, 0));
+}
+
+#[test]
+fn halting() {
+    let mut debugger = Debugger::new(program::parse("acc +3\njmp -5\n").unwrap());
+    assert_eq!(debugger.resume(), Stop::OutOfBounds(-4));
+    assert_eq!(debugger.step(), Stop::OutOfBounds(-4));
+
+    let mut debugger = Debugger::new(program::parse("nop +0\nacc -1\n").unwrap());
+    assert_eq!(debugger.resume(), Stop::Terminated);
+    assert_eq!(debugger.accum(), -1);
+}
+
+#[test]
+fn stepping_through_overflow() {
+    let mut debugger = Debugger::new(program::parse("acc +2000000000\njmp -1\n").unwrap());
+    for _ in 0..4 { assert_eq!(debugger.step(), Stop::Stepped); }
+    assert_eq!(debugger.accum(), 4_000_000_000u32 as i32);
+    assert!(debugger.reverse_step() && debugger.reverse_step());
+    assert_eq!(debugger.accum(), 2_000_000_000);
+
+    let mut debugger = Debugger::new(program::parse("nop +0\njmp +2147483647\n").unwrap());
+    assert_eq!(debugger.resume(), Stop::OutOfBounds(i32::MAX));
+}
+
+#[test]
+fn repl_dumps_the_trace() {
+    let mut debugger = example();
+    let mut output = Vec::new();
+    day8::debugger::repl(&mut debugger, &b"step 2\ntrace\nquit\n"[..], &mut output).unwrap();
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.contains("0: nop +0    accum 0 -> 0"), "{}", output);
+    assert!(output.contains("1: acc +1    accum 0 -> 1"), "{}", output);
+}
@@ -0,0 +1,67 @@
+use day8::{debugger::{Debugger, Stop}, program::{self, Instruction, Opcode}, repair::{self, Repair}};
+
+/// Runs a program the slow way, returning the accumulator if it terminates.
+fn run(program: Vec<Instruction>) -> Option<i32> {
+    let mut debugger = Debugger::new(program);
+    match debugger.resume() {
+        Stop::Terminated => Some(debugger.accum()),
+        Stop::OutOfBounds(_) => Some(debugger.accum()),
+        _ => None
+    }
+}
+
+/// Repairs a program the slow way, by flipping each instruction in turn and running it.
+fn brute_force(program: &[Instruction]) -> Vec<Repair> {
+    if run(program.to_vec()).is_some() { return Vec::new(); }
+    (0..program.len()).filter_map(|index| {
+        let replacement = match program[index].opcode {
+            Opcode::Nop => Opcode::Jmp,
+            Opcode::Jmp => Opcode::Nop,
+            Opcode::Acc => return None
+        };
+        let mut flipped = program.to_vec();
+        flipped[index].opcode = replacement;
+        run(flipped).map(|accum| Repair { index, original: program[index].opcode, replacement, accum })
+    }).collect()
+}
+
+#[test]
+fn example() {
+    let program = program::parse(day8::EXAMPLES[0].input).unwrap();
+    assert_eq!(repair::repairs(&program), vec![
+        Repair { index: 7, original: Opcode::Jmp, replacement: Opcode::Nop, accum: 8 }
+    ]);
+}
+
+#[test]
+fn jumps_out_of_the_program() {
+    // Jumping past the end and jumping to before the start both terminate.
+    let program = program::parse("nop +5\nnop -2\nacc +1\njmp -3\n").unwrap();
+    assert_eq!(repair::repairs(&program), vec![
+        Repair { index: 0, original: Opcode::Nop, replacement: Opcode::Jmp, accum: 0 },
+        Repair { index: 1, original: Opcode::Nop, replacement: Opcode::Jmp, accum: 0 },
+        Repair { index: 3, original: Opcode::Jmp, replacement: Opcode::Nop, accum: 1 }
+    ]);
+}
+
+#[test]
+fn already_terminates() {
+    for input in ["nop +0\nacc +1\njmp +2\nnop +0\n", "acc +1\njmp +7\nnop +0\n", "acc +1\njmp -3\n"] {
+        let program = program::parse(input).unwrap();
+        assert_eq!(repair::terminating(&program)[0], Some(1));
+        assert_eq!(repair::repairs(&program), vec![]);
+    }
+}
+
+#[test]
+fn matches_brute_force() {
+    let mut found = 0;
+    for seed in 0..2000 {
+        let program = program::random(1 + seed as usize % 12, seed);
+
+        let expected = brute_force(&program);
+        found += expected.len();
+        assert_eq!(repair::repairs(&program), expected, "{:?}", program);
+    }
+    assert!(found > 100, "only {} repairs were tested", found);
+}
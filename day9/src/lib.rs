@@ -0,0 +1,12 @@
+use common::Example;
+
+pub mod part1;
+pub mod part2;
+
+/// Example inputs with known answers.
+pub const EXAMPLES: &[Example] = &[
+    // The puzzle's example uses a preamble of 5 numbers, but our solution
+    // assumes 25, so this is a custom example: powers of two, followed
+    // by 7, which is not the sum of any two of them but is 1 + 2 + 4.
+    Example { input: include_str!("../test"), answers: [Some("7"), Some("5")] },
+];
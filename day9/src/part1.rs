@@ -1,24 +1,30 @@
-use std::io::{self, BufRead};
+use common::{Answer, Error, Solution};
 
-pub fn run() {
-    const SIZE: usize = 25;
-    let stdin = io::stdin();
-    let input = stdin.lock().lines().map(|line|
-        line.expect("read error").parse::<usize>().expect("invalid input")
-    ).collect::<Vec<_>>();
-    for window in input.windows(SIZE + 1) {
-        let target = window.last().unwrap();
-        let preceding = &window[0..SIZE];
+pub struct Part1;
 
-        let found = preceding.iter().find(|&i|
-            preceding.iter().find(|&j|
-                i + j == *target
-            ) != None
-        ) != None;
+impl Solution for Part1 {
+    type Input = Vec<usize>;
 
-        if !found {
-            println!("{}", target);
-            break;
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        Ok(common::input::numbers(input)?)
+    }
+
+    fn solve(input: Self::Input) -> Result<Answer, Error> {
+        const SIZE: usize = 25;
+        for window in input.windows(SIZE + 1) {
+            let target = window.last().unwrap();
+            let preceding = &window[0..SIZE];
+
+            let found = preceding.iter().any(|&i|
+                preceding.iter().any(|&j|
+                    i + j == *target
+                )
+            );
+
+            if !found {
+                return Ok((*target).into());
+            }
         }
+        Err(Error::NoSolution)
     }
 }
@@ -0,0 +1,107 @@
+use crate::{points, Grid};
+
+/// An N-dimensional grid which grows to fit whatever is stored in it.
+/// Every cell outside of the grid's bounds holds a default value.
+#[derive(Clone, Debug)]
+pub struct ExpandingGrid<T, const N: usize> {
+    /// The cells within the bounds, with the first coordinate varying fastest.
+    cells: Vec<T>,
+
+    /// The bounds of the grid; `min` is inclusive and `max` is exclusive.
+    min: [isize; N],
+    max: [isize; N],
+
+    default: T
+}
+
+impl<T: Clone + PartialEq, const N: usize> ExpandingGrid<T, N> {
+    /// Creates an empty grid, where every cell holds `default`.
+    pub fn new(default: T) -> Self {
+        ExpandingGrid { cells: Vec::new(), min: [0; N], max: [0; N], default }
+    }
+
+    /// Creates a grid containing a 2D grid, placed with its top left corner at the origin.
+    /// Every coordinate after the first two is zero.
+    /// Panics if `N` is less than 2.
+    pub fn from_plane(plane: &Grid<T>, default: T) -> Self {
+        assert!(N >= 2, "a plane needs at least two dimensions");
+        let mut grid = Self::new(default);
+        let mut max = [1; N];
+        max[0] = plane.width() as isize;
+        max[1] = plane.height() as isize;
+        grid.reserve([0; N], max);
+
+        for (x, y) in plane.positions() {
+            let mut point = [0; N];
+            point[0] = x as isize;
+            point[1] = y as isize;
+            grid.set(point, plane[(x, y)].clone());
+        }
+        grid
+    }
+
+    /// The bounds of the grid. The first point is inclusive and the second is exclusive.
+    /// Every cell outside of these bounds holds the default value.
+    pub fn bounds(&self) -> ([isize; N], [isize; N]) {
+        (self.min, self.max)
+    }
+
+    /// The index of a point in `cells`, or None if it lies outside the bounds.
+    fn index(&self, point: [isize; N]) -> Option<usize> {
+        let mut index = 0;
+        for axis in (0..N).rev() {
+            if !(self.min[axis]..self.max[axis]).contains(&point[axis]) { return None; }
+            let size = (self.max[axis] - self.min[axis]) as usize;
+            index = index*size + (point[axis] - self.min[axis]) as usize;
+        }
+        Some(index)
+    }
+
+    /// Returns a reference to the cell at the given point.
+    pub fn get(&self, point: [isize; N]) -> &T {
+        match self.index(point) {
+            Some(index) => &self.cells[index],
+            None => &self.default
+        }
+    }
+
+    /// Sets the cell at the given point, expanding the grid if necessary.
+    pub fn set(&mut self, point: [isize; N], value: T) {
+        if self.index(point).is_none() {
+            // Cells outside the bounds already hold the default.
+            if value == self.default { return; }
+            let mut max = point;
+            max.iter_mut().for_each(|x| *x += 1);
+            self.reserve(point, max);
+        }
+        let index = self.index(point).expect("the grid was expanded to fit");
+        self.cells[index] = value;
+    }
+
+    /// Expands the grid, if necessary, so that its bounds include the box from `min` (inclusive) to `max` (exclusive).
+    /// Reserving space up front avoids repeatedly expanding the grid when filling it in.
+    pub fn reserve(&mut self, min: [isize; N], max: [isize; N]) {
+        let mut new_min = min;
+        let mut new_max = max;
+        if !self.cells.is_empty() {
+            for axis in 0..N {
+                new_min[axis] = new_min[axis].min(self.min[axis]);
+                new_max[axis] = new_max[axis].max(self.max[axis]);
+            }
+        }
+        if new_min == self.min && new_max == self.max { return; }
+
+        let old = std::mem::replace(self, ExpandingGrid {
+            cells: Vec::new(),
+            min: new_min,
+            max: new_max,
+            default: self.default.clone()
+        });
+        self.cells = points(new_min, new_max).map(|point| old.get(point).clone()).collect();
+    }
+
+    /// Iterates over every point within the bounds, along with its cell.
+    pub fn iter(&self) -> impl Iterator<Item = ([isize; N], &T)> {
+        points(self.min, self.max).zip(self.cells.iter())
+    }
+}
@@ -0,0 +1,162 @@
+use std::ops::{Index, IndexMut};
+use common::ParseError;
+use crate::Neighborhood;
+
+/// A fixed-size 2D grid, indexed by `(x, y)` from the top left.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Grid<T> {
+    /// The cells, row by row.
+    cells: Vec<T>,
+    width: usize,
+    height: usize
+}
+
+impl<T> Grid<T> {
+    /// Creates a grid by calling `cell(x, y)` for each position.
+    pub fn from_fn(width: usize, height: usize, mut cell: impl FnMut(usize, usize) -> T) -> Self {
+        let cells = (0..height).flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| cell(x, y))
+            .collect();
+        Grid { cells, width, height }
+    }
+
+    /// Creates a grid from a list of rows.
+    /// Panics if the rows are not all the same width.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Self {
+        let width = rows.first().map_or(0, Vec::len);
+        let height = rows.len();
+        assert!(rows.iter().all(|row| row.len() == width), "rows are not all the same width");
+        Grid { cells: rows.into_iter().flatten().collect(), width, height }
+    }
+
+    /// Parses a rectangular map of characters, converting each one with `cell`.
+    /// Returns an error for every row which is malformed.
+    pub fn parse(input: &str, cell: impl Fn(char) -> Option<T>) -> Result<Self, Vec<ParseError>> {
+        Ok(Self::from_rows(common::input::grid(input, cell)?))
+    }
+
+    pub fn width(&self) -> usize { self.width }
+    pub fn height(&self) -> usize { self.height }
+
+    /// Returns a reference to the cell at the given position, or None if it is out of bounds.
+    pub fn get(&self, (x, y): (usize, usize)) -> Option<&T> {
+        if x < self.width && y < self.height { self.cells.get(y*self.width + x) }
+        else { None }
+    }
+
+    /// Returns a mutable reference to the cell at the given position, or None if it is out of bounds.
+    pub fn get_mut(&mut self, (x, y): (usize, usize)) -> Option<&mut T> {
+        if x < self.width && y < self.height { self.cells.get_mut(y*self.width + x) }
+        else { None }
+    }
+
+    /// Returns a reference to the cell at the given position, wrapping around
+    /// the edges as if the grid repeated forever in every direction.
+    /// Panics if the grid is empty.
+    pub fn get_wrapping(&self, (x, y): (isize, isize)) -> &T {
+        let x = x.rem_euclid(self.width as isize) as usize;
+        let y = y.rem_euclid(self.height as isize) as usize;
+        &self[(x, y)]
+    }
+
+    /// Applies an offset to the given position and bounds-checks the result.
+    pub fn offset(&self, (x, y): (usize, usize), (dx, dy): (isize, isize)) -> Option<(usize, usize)> {
+        let x = x.checked_add_signed(dx).filter(|&x| x < self.width)?;
+        let y = y.checked_add_signed(dy).filter(|&y| y < self.height)?;
+        Some((x, y))
+    }
+
+    /// Iterates over the positions of the neighbors of a cell which lie within the grid.
+    pub fn neighbors(&self, position: (usize, usize), neighborhood: Neighborhood)
+        -> impl Iterator<Item = (usize, usize)> + '_ {
+        neighborhood.offsets().filter_map(move |[dx, dy]| self.offset(position, (dx, dy)))
+    }
+
+    /// Looks along a line from `from` (not including `from` itself), moving by `direction` each step.
+    /// Returns the position of the first cell for which `stop` returns true,
+    /// or None if the line leaves the grid first.
+    pub fn cast(&self, from: (usize, usize), direction: (isize, isize), mut stop: impl FnMut(&T) -> bool)
+        -> Option<(usize, usize)> {
+        let mut position = from;
+        loop {
+            position = self.offset(position, direction)?;
+            if stop(&self[position]) { return Some(position); }
+        }
+    }
+
+    /// Iterates over every position in the grid, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    /// Iterates over every cell in the grid, row by row.
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.cells.iter()
+    }
+
+    /// Iterates over the rows of the grid.
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // chunks() panics on a chunk size of zero, but a grid with no width has no rows anyway.
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// Creates a grid of the same size by applying `f` to each cell.
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid { cells: self.cells.iter().map(f).collect(), width: self.width, height: self.height }
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    /// Creates a grid with every cell set to `value`.
+    pub fn new(width: usize, height: usize, value: T) -> Self {
+        Grid { cells: vec![value; width*height], width, height }
+    }
+
+    /// Returns a copy of the grid rotated 90 degrees clockwise.
+    pub fn rotated(&self) -> Self {
+        Grid::from_fn(self.height, self.width, |x, y| self[(y, self.height - 1 - x)].clone())
+    }
+
+    /// Returns a copy of the grid mirrored left-to-right.
+    pub fn flipped(&self) -> Self {
+        Grid::from_fn(self.width, self.height, |x, y| self[(self.width - 1 - x, y)].clone())
+    }
+
+    /// Returns a copy of the grid mirrored across its main diagonal, swapping rows and columns.
+    pub fn transposed(&self) -> Self {
+        Grid::from_fn(self.height, self.width, |x, y| self[(y, x)].clone())
+    }
+
+    /// Iterates over all 8 ways the grid can be rotated and flipped, starting with the grid as it is.
+    pub fn orientations(&self) -> impl Iterator<Item = Self> {
+        let mut next = Some(self.clone());
+        let mut count = 0;
+        std::iter::from_fn(move || {
+            let current = next.take()?;
+            count += 1;
+            next = match count {
+                8 => None,
+                4 => Some(current.rotated().flipped()),
+                _ => Some(current.rotated())
+            };
+            Some(current)
+        })
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    /// Panics if the position is out of bounds.
+    fn index(&self, position: (usize, usize)) -> &T {
+        self.get(position).expect("position out of bounds")
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    /// Panics if the position is out of bounds.
+    fn index_mut(&mut self, position: (usize, usize)) -> &mut T {
+        self.get_mut(position).expect("position out of bounds")
+    }
+}
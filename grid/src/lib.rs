@@ -0,0 +1,13 @@
+//! Grids of cells, for the many puzzles that take place on a map.
+//!
+//! [`Grid`] is a fixed-size 2D grid, which is what most puzzles need. [`ExpandingGrid`]
+//! has any number of dimensions and grows to fit whatever is stored in it, for puzzles
+//! that take place on an infinite map.
+
+mod expanding;
+mod fixed;
+mod neighbors;
+
+pub use expanding::ExpandingGrid;
+pub use fixed::Grid;
+pub use neighbors::{neighbors, points, Neighborhood, Points};
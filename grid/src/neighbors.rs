@@ -0,0 +1,73 @@
+/// Which cells count as a cell's neighbors.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Neighborhood {
+    /// The cells sharing a face with a cell: 4 in 2D, 6 in 3D, and 2N in N dimensions.
+    VonNeumann,
+
+    /// The cells sharing a face, edge or corner with a cell: 8 in 2D, 26 in 3D, and 3^N - 1 in N dimensions.
+    Moore
+}
+
+impl Neighborhood {
+    /// Iterates over the offsets from a cell to each of its neighbors.
+    pub fn offsets<const N: usize>(self) -> impl Iterator<Item = [isize; N]> {
+        points([-1; N], [2; N]).filter(move |offset| {
+            let distance = offset.iter().map(|d| d.abs()).sum::<isize>();
+            match self {
+                Neighborhood::VonNeumann => distance == 1,
+                Neighborhood::Moore => distance != 0
+            }
+        })
+    }
+}
+
+/// Iterates over the neighbors of `point`.
+pub fn neighbors<const N: usize>(point: [isize; N], neighborhood: Neighborhood) -> impl Iterator<Item = [isize; N]> {
+    neighborhood.offsets().map(move |offset: [isize; N]| {
+        let mut neighbor = point;
+        neighbor.iter_mut().zip(offset.iter()).for_each(|(x, dx)| *x += dx);
+        neighbor
+    })
+}
+
+/// Iterates over every point in the box from `min` (inclusive) to `max` (exclusive).
+/// The first coordinate varies fastest.
+pub fn points<const N: usize>(min: [isize; N], max: [isize; N]) -> Points<N> {
+    let empty = min.iter().zip(max.iter()).any(|(min, max)| min >= max);
+    Points { next: if empty { None } else { Some(min) }, min, max }
+}
+
+/// An iterator over the points in a box. See [`points`].
+#[derive(Clone, Debug)]
+pub struct Points<const N: usize> {
+    next: Option<[isize; N]>,
+    min: [isize; N],
+    max: [isize; N]
+}
+
+impl<const N: usize> Iterator for Points<N> {
+    type Item = [isize; N];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let result = self.next?;
+
+        // Advance like an odometer, carrying into the next axis whenever one rolls over.
+        let mut next = result;
+        let mut axis = 0;
+        loop {
+            if axis == N {
+                self.next = None;
+                break;
+            }
+            next[axis] += 1;
+            if next[axis] < self.max[axis] {
+                self.next = Some(next);
+                break;
+            }
+            next[axis] = self.min[axis];
+            axis += 1;
+        }
+
+        Some(result)
+    }
+}
@@ -0,0 +1,66 @@
+use grid::{ExpandingGrid, Grid, Neighborhood};
+
+fn parse(map: &str) -> Grid<char> {
+    Grid::parse(map, Some).unwrap()
+}
+
+#[test]
+fn neighborhoods() {
+    assert_eq!(Neighborhood::VonNeumann.offsets::<2>().count(), 4);
+    assert_eq!(Neighborhood::Moore.offsets::<2>().count(), 8);
+    assert_eq!(Neighborhood::VonNeumann.offsets::<3>().count(), 6);
+    assert_eq!(Neighborhood::Moore.offsets::<4>().count(), 80);
+
+    let grid = parse("abc\ndef\n");
+    let mut corner = grid.neighbors((0, 0), Neighborhood::Moore).map(|p| grid[p]).collect::<Vec<_>>();
+    corner.sort_unstable();
+    assert_eq!(corner, vec!['b', 'd', 'e']);
+    assert_eq!(grid.neighbors((1, 1), Neighborhood::VonNeumann).count(), 3);
+}
+
+#[test]
+fn casting_and_wrapping() {
+    let grid = parse("#..\n...\n..#\n");
+    assert_eq!(grid.cast((0, 0), (1, 1), |&c| c == '#'), Some((2, 2)));
+    assert_eq!(grid.cast((0, 0), (1, 0), |&c| c == '#'), None);
+    assert_eq!(grid.cast((2, 2), (-1, -1), |&c| c == '#'), Some((0, 0)));
+
+    assert_eq!(*grid.get_wrapping((3, 3)), '#');
+    assert_eq!(*grid.get_wrapping((-1, -1)), '#');
+    assert_eq!(grid.get((3, 0)), None);
+}
+
+#[test]
+fn transforms() {
+    let grid = parse("ab\ncd\nef\n");
+    assert_eq!(grid.rotated(), parse("eca\nfdb\n"));
+    assert_eq!(grid.flipped(), parse("ba\ndc\nfe\n"));
+    assert_eq!(grid.transposed(), parse("ace\nbdf\n"));
+
+    let orientations = grid.orientations().collect::<Vec<_>>();
+    assert_eq!(orientations.len(), 8);
+    assert_eq!(orientations[0], grid);
+    assert_eq!(orientations[2], grid.rotated().rotated());
+    assert_eq!(orientations[4], grid.flipped());
+    for (i, a) in orientations.iter().enumerate() {
+        assert!(orientations[i + 1..].iter().all(|b| a != b), "orientation {} is repeated", i);
+    }
+}
+
+#[test]
+fn expanding() {
+    let plane = Grid::parse(".#\n#.\n", |c| Some(c == '#')).unwrap();
+    let mut grid = ExpandingGrid::<bool, 3>::from_plane(&plane, false);
+    assert_eq!(grid.bounds(), ([0, 0, 0], [2, 2, 1]));
+    assert!(*grid.get([1, 0, 0]) && *grid.get([0, 1, 0]));
+    assert!(!*grid.get([5, 5, 5]));
+
+    grid.set([-2, 1, 3], true);
+    assert_eq!(grid.bounds(), ([-2, 0, 0], [2, 2, 4]));
+    assert!(*grid.get([-2, 1, 3]) && *grid.get([1, 0, 0]) && *grid.get([0, 1, 0]));
+    assert_eq!(grid.iter().filter(|(_, &cell)| cell).count(), 3);
+
+    // Storing the default outside the bounds doesn't need to expand anything.
+    grid.set([10, 10, 10], false);
+    assert_eq!(grid.bounds(), ([-2, 0, 0], [2, 2, 4]));
+}
@@ -0,0 +1,11 @@
+use common::Example;
+
+pub mod part1;
+pub mod part2;
+
+/// The examples from the puzzle description.
+pub const EXAMPLES: &[Example] = &[
+    // TODO: paste the example into `test` and fill in its answers. Until
+    // then, `aoc all` and `aoc bench` skip this day.
+    Example { input: include_str!("../test"), answers: [None, None] },
+];
@@ -1,8 +1,15 @@
-use std::io::{self, BufRead};
+use common::{Answer, Error, Solution};
 
-pub fn run() {
-    for line in io::stdin().lock().lines() {
-        println!("{}", line.expect("read error"));
+pub struct Part2;
+
+impl Solution for Part2 {
+    type Input = Vec<String>;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        Ok(input.lines().map(str::to_string).collect())
+    }
+
+    fn solve(input: Self::Input) -> Result<Answer, Error> {
+        todo!()
     }
-    todo!()
 }